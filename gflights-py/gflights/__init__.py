@@ -40,6 +40,7 @@ from gflights._gflights import (  # noqa: F401
     LegInfo,
     Offer,
     PriceEntry,
+    ValidationError,
 )
 from gflights._types import (  # noqa: F401
     Currency,
@@ -54,6 +55,7 @@ from gflights._types import (  # noqa: F401
 __all__ = [
     "Client",
     "GFlightsError",
+    "ValidationError",
     "FlightResult",
     "LegInfo",
     "LayoverInfo",
@@ -148,6 +148,8 @@ class Client:
         :returns: Coroutine → ``list[FlightResult]``.
         :raises ValueError: on invalid dates, enums, or > 9 passengers.
         :raises gflights.GFlightsError: on network / parse failure.
+        :raises gflights.ValidationError: when the search cannot return results
+            (e.g. a past date); ``diagnostics`` lists every problem.
 
         Example::
 
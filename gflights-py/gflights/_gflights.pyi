@@ -15,6 +15,21 @@ class GFlightsError(Exception):
     """
     ...
 
+class ValidationError(GFlightsError):
+    """Raised before a search is sent when its parameters cannot return results
+    (departure in the past, the same airport at both ends, …).
+
+    Every problem is reported at once::
+
+        try:
+            flights = await client.search(...)
+        except gflights.ValidationError as e:
+            for d in e.diagnostics:
+                print(d["field"], d["severity"], d["message"])
+    """
+
+    diagnostics: list[dict[str, str]]
+
 class LegInfo:
     from_airport: str
     to_airport: str
@@ -26,7 +26,7 @@ use gflights::{
     requests::{
         api::ApiClient,
         config::{
-            Config, ConfigBuilder, Currency, DealConfig, ExploreConfig, ExploreDate,
+            Config, ConfigBuilder, Currency, DealConfig, Diagnostic, ExploreConfig, ExploreDate,
             ExploreDuration, MultiCityConfig,
         },
     },
@@ -106,10 +106,58 @@ fn parse_airline_filters(list: &[String]) -> PyResult<Vec<AirlineFilter>> {
 
 pyo3::create_exception!(_gflights, GFlightsError, pyo3::exceptions::PyException);
 
-fn anyhow_to_py(e: anyhow::Error) -> PyErr {
+fn to_py_err(e: impl std::fmt::Display) -> PyErr {
     GFlightsError::new_err(e.to_string())
 }
 
+pyo3::create_exception!(_gflights, ValidationError, GFlightsError);
+
+/// `ValidationError` for `diagnostics`, with a `diagnostics` attribute holding
+/// one `{"field", "severity", "message"}` dict per problem.
+fn validation_err(diagnostics: &[Diagnostic]) -> PyErr {
+    let message = gflights::Error::Validation(diagnostics.to_vec()).to_string();
+    Python::with_gil(|py| {
+        let err = ValidationError::new_err(message);
+        let entries = diagnostics
+            .iter()
+            .map(|d| {
+                let dict = PyDict::new(py);
+                dict.set_item("field", &d.field)?;
+                dict.set_item("severity", d.severity.to_string())?;
+                dict.set_item("message", &d.message)?;
+                Ok(dict)
+            })
+            .collect::<PyResult<Vec<_>>>();
+        match entries.and_then(|e| err.value(py).setattr("diagnostics", e)) {
+            Ok(()) => err,
+            Err(e) => e,
+        }
+    })
+}
+
+/// Map a config `build()` failure: validation failures become
+/// `ValidationError`, anything else `GFlightsError`.
+fn build_err(e: anyhow::Error) -> PyErr {
+    match e.downcast_ref::<gflights::Error>() {
+        Some(gflights::Error::Validation(diagnostics)) => validation_err(diagnostics),
+        _ => to_py_err(e),
+    }
+}
+
+/// Refuse to send a search whose full validation (dates included) found
+/// errors.  Warnings are not raised.
+fn check_diagnostics(diagnostics: Vec<Diagnostic>) -> PyResult<()> {
+    let errors: Vec<Diagnostic> = diagnostics
+        .into_iter()
+        .filter(Diagnostic::is_error)
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(validation_err(&errors))
+    }
+}
+
 /// Render an `Option<T>` for a Python-style `__repr__`: the inner value, or
 /// the literal `None` — never Rust's `Some(..)` wrapper.
 fn repr_opt<T: std::fmt::Display>(o: &Option<T>) -> String {
@@ -854,7 +902,7 @@ impl Client {
         let mut client = match proxy {
             Some(p) => rt
                 .block_on(ApiClient::new_with_proxy(p))
-                .map_err(anyhow_to_py)?,
+                .map_err(to_py_err)?,
             None => rt.block_on(ApiClient::new()),
         };
         if let Some(ua) = user_agent {
@@ -952,23 +1000,24 @@ impl Client {
             let mut builder = Config::builder()
                 .departure(&from_airport, &client)
                 .await
-                .map_err(anyhow_to_py)?
+                .map_err(to_py_err)?
                 .destination(&to_airport, &client)
                 .await
-                .map_err(anyhow_to_py)?
+                .map_err(to_py_err)?
                 .departing_date(dep_date);
             builder = filters.apply(builder);
             if let Some(r) = ret_date {
                 builder = builder.return_date(r);
             }
 
-            let config = builder.build().map_err(anyhow_to_py)?;
+            let config = builder.build().map_err(build_err)?;
+            check_diagnostics(config.validate())?;
             // Strict "via": filter out non-stops that Google's other_flights
             // container returns even when a connecting airport is requested.
             let flights = client
                 .request_flights(&config)
                 .await
-                .map_err(anyhow_to_py)?
+                .map_err(to_py_err)?
                 .get_all_flights_via(&config.connecting_airports);
 
             Python::with_gil(|py| {
@@ -1061,18 +1110,19 @@ impl Client {
             let mut builder = Config::builder()
                 .departure(&from_airport, &client)
                 .await
-                .map_err(anyhow_to_py)?
+                .map_err(to_py_err)?
                 .destination(&to_airport, &client)
                 .await
-                .map_err(anyhow_to_py)?
+                .map_err(to_py_err)?
                 .departing_date(dep_date);
             builder = filters.apply(builder);
-            let config = builder.build().map_err(anyhow_to_py)?;
+            let config = builder.build().map_err(build_err)?;
+            check_diagnostics(config.validate())?;
 
             let graph = client
                 .request_graph(&config, Months::new(months))
                 .await
-                .map_err(anyhow_to_py)?;
+                .map_err(to_py_err)?;
 
             let mut entries: Vec<PriceEntry> = graph
                 .get_all_graphs()
@@ -1186,19 +1236,20 @@ impl Client {
             let mut builder = Config::builder()
                 .departure(&from_airport, &client)
                 .await
-                .map_err(anyhow_to_py)?
+                .map_err(to_py_err)?
                 .destination(&to_airport, &client)
                 .await
-                .map_err(anyhow_to_py)?
+                .map_err(to_py_err)?
                 .departing_date(dep_s)
                 .return_date(ret_s);
             builder = filters.apply(builder);
-            let config = builder.build().map_err(anyhow_to_py)?;
+            let config = builder.build().map_err(build_err)?;
+            check_diagnostics(config.validate())?;
 
             let grid = client
                 .request_date_grid(&config, dep_s, dep_e, ret_s, ret_e)
                 .await
-                .map_err(anyhow_to_py)?;
+                .map_err(to_py_err)?;
 
             Python::with_gil(|py| {
                 grid.entries
@@ -1297,14 +1348,15 @@ impl Client {
                 builder = builder
                     .add_leg(from, to, *date, &client)
                     .await
-                    .map_err(anyhow_to_py)?;
+                    .map_err(to_py_err)?;
             }
 
-            let config = builder.build().map_err(anyhow_to_py)?;
+            let config = builder.build().map_err(build_err)?;
+            check_diagnostics(config.validate())?;
             let flights = client
                 .request_multi_city_flights(&config)
                 .await
-                .map_err(anyhow_to_py)?
+                .map_err(to_py_err)?
                 .get_all_flights();
 
             Python::with_gil(|py| {
@@ -1395,7 +1447,7 @@ impl Client {
         let interest = interest
             .map(|i| gflights::requests::config::explore::resolve_interest(&i))
             .transpose()
-            .map_err(anyhow_to_py)?;
+            .map_err(to_py_err)?;
 
         let client = self.client.clone();
 
@@ -1412,7 +1464,7 @@ impl Client {
                     client
                         .request_city(&from_airport)
                         .await
-                        .map_err(anyhow_to_py)?
+                        .map_err(to_py_err)?
                         .to_city_list()
                 };
 
@@ -1434,7 +1486,7 @@ impl Client {
             let results = client
                 .request_explore(&config)
                 .await
-                .map_err(anyhow_to_py)?;
+                .map_err(to_py_err)?;
 
             Python::with_gil(|py| {
                 results
@@ -1531,7 +1583,7 @@ impl Client {
                     client
                         .request_city(&from_airport)
                         .await
-                        .map_err(anyhow_to_py)?
+                        .map_err(to_py_err)?
                         .to_city_list()
                 };
 
@@ -1545,7 +1597,7 @@ impl Client {
                 travellers: travelers,
             };
 
-            let results = client.request_deals(&config).await.map_err(anyhow_to_py)?;
+            let results = client.request_deals(&config).await.map_err(to_py_err)?;
 
             Python::with_gil(|py| {
                 results
@@ -1666,18 +1718,19 @@ impl Client {
             let mut builder = Config::builder()
                 .departure(&from_airport, &client)
                 .await
-                .map_err(anyhow_to_py)?
+                .map_err(to_py_err)?
                 .destination(&to_airport, &client)
                 .await
-                .map_err(anyhow_to_py)?
+                .map_err(to_py_err)?
                 .departing_date(dep_start);
             builder = filters.apply(builder);
-            let config = builder.build().map_err(anyhow_to_py)?;
+            let config = builder.build().map_err(build_err)?;
+            check_diagnostics(config.validate())?;
 
             let results = client
                 .cheapest_dates(&config, chrono::Months::new(months), trip_duration_days)
                 .await
-                .map_err(anyhow_to_py)?;
+                .map_err(to_py_err)?;
 
             Python::with_gil(|py| {
                 results
@@ -1786,16 +1839,17 @@ impl Client {
             let mut builder = Config::builder()
                 .departure(&from_airport, &client)
                 .await
-                .map_err(anyhow_to_py)?
+                .map_err(to_py_err)?
                 .destination(&to_airport, &client)
                 .await
-                .map_err(anyhow_to_py)?
+                .map_err(to_py_err)?
                 .departing_date(dep_date);
             builder = filters.apply(builder);
             if let Some(r) = ret_date {
                 builder = builder.return_date(r);
             }
-            let config = builder.build().map_err(anyhow_to_py)?;
+            let config = builder.build().map_err(build_err)?;
+            check_diagnostics(config.validate())?;
 
             // Lock in the cheapest outbound (and return for round trips).
             // Honour the "via" filter strictly so the fixed leg connects through
@@ -1803,7 +1857,7 @@ impl Client {
             let first = client
                 .request_flights(&config)
                 .await
-                .map_err(anyhow_to_py)?
+                .map_err(to_py_err)?
                 .get_all_flights_via(&config.connecting_airports)
                 .into_iter()
                 .next();
@@ -1816,12 +1870,12 @@ impl Client {
             config
                 .fixed_flights
                 .add_element(first)
-                .map_err(anyhow_to_py)?;
+                .map_err(to_py_err)?;
             if config.return_date.is_some() {
                 if let Some(ret) = client
                     .request_flights(&config)
                     .await
-                    .map_err(anyhow_to_py)?
+                    .map_err(to_py_err)?
                     .get_all_flights_via(&config.connecting_airports)
                     .into_iter()
                     .next()
@@ -1829,11 +1883,11 @@ impl Client {
                     config
                         .fixed_flights
                         .add_element(ret)
-                        .map_err(anyhow_to_py)?;
+                        .map_err(to_py_err)?;
                 }
             }
 
-            let offers = client.request_offer(&config).await.map_err(anyhow_to_py)?;
+            let offers = client.request_offer(&config).await.map_err(to_py_err)?;
             let mut groups: Vec<_> = offers
                 .response
                 .iter()
@@ -1921,5 +1975,6 @@ fn _gflights(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Offer>()?;
     m.add_class::<BookingOption>()?;
     m.add("GFlightsError", m.py().get_type::<GFlightsError>())?;
+    m.add("ValidationError", m.py().get_type::<ValidationError>())?;
     Ok(())
 }
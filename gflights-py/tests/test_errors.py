@@ -109,3 +109,24 @@ def test_gflights_error_is_not_value_error():
 def test_gflights_error_is_not_runtime_error():
     """GFlightsError is distinct from RuntimeError (not a subclass)."""
     assert not issubclass(gflights.GFlightsError, RuntimeError)
+
+
+# ---------------------------------------------------------------------------
+# ValidationError — searches that cannot return results
+# ---------------------------------------------------------------------------
+
+def test_validation_error_is_gflights_error():
+    assert issubclass(gflights.ValidationError, gflights.GFlightsError)
+
+
+async def test_same_origin_and_destination_raises_validation_error(client):
+    # IATA codes resolve without a network call, so this fails in build().
+    with pytest.raises(gflights.ValidationError) as info:
+        await client.search(origin="LHR", destination="LHR", date="2030-08-01")
+    assert [d["field"] for d in info.value.diagnostics] == ["destination"]
+    assert info.value.diagnostics[0]["severity"] == "error"
+
+
+async def test_past_departure_raises_validation_error(client):
+    with pytest.raises(gflights.ValidationError, match="in the past"):
+        await client.search(origin="LHR", destination="JFK", date="2001-08-01")
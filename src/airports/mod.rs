@@ -0,0 +1,297 @@
+//! Embedded database of major commercial airports.
+//!
+//! About 240 of the busiest airports ship with the crate (`airports.csv`
+//! next to this file), each with its IATA and ICAO codes, name, city, country,
+//! coordinates, IANA time zone and IATA metropolitan-area code.  Lookups need
+//! no network and no [`ApiClient`](crate::requests::api::ApiClient):
+//!
+//! ```
+//! use gflights::airports;
+//!
+//! let lhr = airports::by_iata("LHR").expect("bundled");
+//! assert_eq!(lhr.time_zone, "Europe/London");
+//! assert_eq!(lhr.metro, Some("LON"));
+//! assert_eq!(airports::in_city("zurich")[0].iata, "ZRH");
+//! assert_eq!(airports::within(lhr.latitude, lhr.longitude, 50.0)[0].0.iata, "LHR");
+//! ```
+//!
+//! The set is deliberately small: a code that is missing here is not
+//! necessarily wrong, it is just not bundled.  [`ConfigBuilder`] uses the
+//! database first and falls back to Google's city lookup for anything it does
+//! not know.
+//!
+//! [`ConfigBuilder`]: crate::requests::config::ConfigBuilder
+
+use std::sync::OnceLock;
+
+use crate::parsers::common::{Location, PlaceType};
+
+const DATA: &str = include_str!("airports.csv");
+
+/// Mean Earth radius used for great-circle distances.
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// One airport from the embedded database.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Airport {
+    /// IATA code, e.g. `"LHR"`.
+    pub iata: &'static str,
+    /// ICAO code, e.g. `"EGLL"`.
+    pub icao: &'static str,
+    pub name: &'static str,
+    /// City the airport is listed under (English name).
+    pub city: &'static str,
+    /// ISO 3166-1 alpha-2 country code, e.g. `"GB"`.
+    pub country: &'static str,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// IANA time zone, e.g. `"Europe/London"`.
+    pub time_zone: &'static str,
+    /// IATA metropolitan-area code shared with the city's other airports
+    /// (`"LON"` for Heathrow), if the city has one.
+    pub metro: Option<&'static str>,
+}
+
+impl Airport {
+    /// The [`Location`] to use for this airport in a search.
+    pub fn location(&self) -> Location {
+        Location {
+            loc_identifier: self.iata.to_owned(),
+            loc_type: PlaceType::Airport,
+            location_name: Some(self.iata.to_owned()),
+        }
+    }
+
+    /// Great-circle distance in kilometres from this airport to a point.
+    pub fn distance_km(&self, latitude: f64, longitude: f64) -> f64 {
+        distance_km((self.latitude, self.longitude), (latitude, longitude))
+    }
+}
+
+impl Location {
+    /// Details for this location from the embedded database, when it is an
+    /// airport the database knows.
+    pub fn airport(&self) -> Option<&'static Airport> {
+        match self.loc_type {
+            PlaceType::Airport => by_iata(&self.loc_identifier),
+            _ => None,
+        }
+    }
+}
+
+/// Every airport in the database, in file order.
+pub fn all() -> &'static [Airport] {
+    static AIRPORTS: OnceLock<Vec<Airport>> = OnceLock::new();
+    AIRPORTS.get_or_init(|| parse(DATA))
+}
+
+/// Looks up an airport by IATA code, ignoring case.
+pub fn by_iata(code: &str) -> Option<&'static Airport> {
+    all().iter().find(|a| a.iata.eq_ignore_ascii_case(code))
+}
+
+/// Looks up an airport by ICAO code, ignoring case.
+pub fn by_icao(code: &str) -> Option<&'static Airport> {
+    all().iter().find(|a| a.icao.eq_ignore_ascii_case(code))
+}
+
+/// Airports listed under the city `name`.  The match is exact but ignores
+/// case and accents, so `"sao paulo"` finds `"São Paulo"`.  Cities with the
+/// same name in different countries are all returned.
+pub fn in_city(name: &str) -> Vec<&'static Airport> {
+    let wanted = fold(name.trim());
+    all().iter().filter(|a| fold(a.city) == wanted).collect()
+}
+
+/// Airports sharing the IATA metropolitan-area code `code` (`"LON"`,
+/// `"NYC"`, …), ignoring case.  Empty for an unknown code.
+pub fn in_metro(code: &str) -> Vec<&'static Airport> {
+    all()
+        .iter()
+        .filter(|a| a.metro.is_some_and(|m| m.eq_ignore_ascii_case(code)))
+        .collect()
+}
+
+/// Airports within `radius_km` of a point, nearest first, with their
+/// distance in kilometres.
+pub fn within(latitude: f64, longitude: f64, radius_km: f64) -> Vec<(&'static Airport, f64)> {
+    let mut found: Vec<_> = all()
+        .iter()
+        .map(|a| (a, a.distance_km(latitude, longitude)))
+        .filter(|(_, km)| *km <= radius_km)
+        .collect();
+    found.sort_by(|a, b| a.1.total_cmp(&b.1));
+    found
+}
+
+/// Great-circle distance in kilometres between two `(latitude, longitude)`
+/// points.
+pub fn distance_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (from.0.to_radians(), to.0.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (to.1 - from.1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+/// The point whose distances to the `references` (`(point, km)` pairs) fit
+/// best, searched from `start`.  Needs at least two references; with only
+/// two, of the two points that fit, the one nearer `start` is returned.
+/// Accurate for the few hundred kilometres a nearby-airport list spans.
+pub(crate) fn locate(references: &[((f64, f64), f64)], start: (f64, f64)) -> Option<(f64, f64)> {
+    if references.len() < 2 {
+        return None;
+    }
+    // Work on a plane tangent at `start`, in kilometres.
+    let km_per_lat = EARTH_RADIUS_KM.to_radians();
+    let km_per_lon = km_per_lat * start.0.to_radians().cos();
+    let project =
+        |(lat, lon): (f64, f64)| ((lon - start.1) * km_per_lon, (lat - start.0) * km_per_lat);
+    let points: Vec<((f64, f64), f64)> = references
+        .iter()
+        .map(|(p, km)| (project(*p), *km))
+        .collect();
+    let (mut x, mut y) = (0.0_f64, 0.0_f64);
+    // Gauss-Newton on the distance residuals.
+    for _ in 0..50 {
+        let (mut a11, mut a12, mut a22, mut b1, mut b2) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for ((px, py), km) in &points {
+            let (dx, dy) = (x - px, y - py);
+            let d = dx.hypot(dy).max(1e-6);
+            let (jx, jy, r) = (dx / d, dy / d, d - km);
+            a11 += jx * jx;
+            a12 += jx * jy;
+            a22 += jy * jy;
+            b1 += jx * r;
+            b2 += jy * r;
+        }
+        let det = a11 * a22 - a12 * a12;
+        if det.abs() < 1e-9 {
+            break;
+        }
+        let step_x = (a22 * b1 - a12 * b2) / det;
+        let step_y = (a11 * b2 - a12 * b1) / det;
+        x -= step_x;
+        y -= step_y;
+        if step_x.hypot(step_y) < 1e-3 {
+            break;
+        }
+    }
+    (x.is_finite() && y.is_finite()).then(|| (start.0 + y / km_per_lat, start.1 + x / km_per_lon))
+}
+
+fn parse(data: &'static str) -> Vec<Airport> {
+    data.lines()
+        .skip(1)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let f: Vec<&'static str> = line.split(',').collect();
+            assert_eq!(f.len(), 9, "malformed airports.csv row: {line}");
+            Airport {
+                iata: f[0],
+                icao: f[1],
+                name: f[2],
+                city: f[3],
+                country: f[4],
+                latitude: f[5].parse().expect("airports.csv latitude"),
+                longitude: f[6].parse().expect("airports.csv longitude"),
+                time_zone: f[7],
+                metro: Some(f[8]).filter(|m| !m.is_empty()),
+            }
+        })
+        .collect()
+}
+
+/// Lower-cases `s` and strips the accents used in the bundled city names.
+fn fold(s: &str) -> String {
+    s.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ä' | 'ã' | 'å' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'í' | 'ì' | 'î' | 'ï' => 'i',
+            'ó' | 'ò' | 'ô' | 'ö' | 'õ' | 'ø' => 'o',
+            'ú' | 'ù' | 'û' | 'ü' => 'u',
+            'ç' | 'č' => 'c',
+            'ñ' => 'n',
+            'š' => 's',
+            'ž' => 'z',
+            'ł' => 'l',
+            other => other,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every row parses, codes are well-formed and unique, and coordinates
+    /// are in range.
+    #[test]
+    fn bundled_data_is_well_formed() {
+        let airports = all();
+        assert!(airports.len() > 150);
+        let mut seen = std::collections::HashSet::new();
+        for a in airports {
+            assert!(seen.insert(a.iata), "duplicate {}", a.iata);
+            assert!(a.iata.len() == 3 && a.iata.bytes().all(|b| b.is_ascii_uppercase()));
+            assert!(a.icao.len() == 4 && a.icao.bytes().all(|b| b.is_ascii_uppercase()));
+            assert_eq!(a.country.len(), 2, "{}", a.iata);
+            assert!(a.time_zone.contains('/'), "{}", a.iata);
+            assert!((-90.0..=90.0).contains(&a.latitude), "{}", a.iata);
+            assert!((-180.0..=180.0).contains(&a.longitude), "{}", a.iata);
+            if let Some(metro) = a.metro {
+                assert_eq!(metro.len(), 3, "{}", a.iata);
+            }
+        }
+    }
+
+    #[test]
+    fn lookups_ignore_case_and_accents() {
+        assert_eq!(by_iata("jfk").map(|a| a.icao), Some("KJFK"));
+        assert_eq!(by_icao("egll").map(|a| a.iata), Some("LHR"));
+        assert!(by_iata("QQQ").is_none());
+
+        let sao: Vec<_> = in_city("Sao Paulo").iter().map(|a| a.iata).collect();
+        assert_eq!(sao, ["GRU", "CGH"]);
+        let tokyo: Vec<_> = in_metro("tyo").iter().map(|a| a.iata).collect();
+        assert_eq!(tokyo, ["HND", "NRT"]);
+    }
+
+    #[test]
+    fn distance_and_location() {
+        let lhr = by_iata("LHR").unwrap();
+        let jfk = by_iata("JFK").unwrap();
+        let km = lhr.distance_km(jfk.latitude, jfk.longitude);
+        assert!((5520.0..5580.0).contains(&km), "LHR–JFK is {km} km");
+
+        let around_heathrow: Vec<_> = within(lhr.latitude, lhr.longitude, 50.0)
+            .iter()
+            .map(|(a, _)| a.iata)
+            .collect();
+        assert_eq!(around_heathrow[0], "LHR");
+        assert!(around_heathrow.contains(&"LGW"));
+        assert!(!around_heathrow.contains(&"STN"), "Stansted is ~70 km away");
+
+        let loc = lhr.location();
+        assert_eq!(loc.loc_identifier, "LHR");
+        assert_eq!(loc.airport(), Some(lhr));
+    }
+
+    #[test]
+    fn locate_fits_a_point_to_its_distances() {
+        let charing_cross = (51.5074, -0.1278);
+        let references: Vec<_> = ["LHR", "LGW", "STN"]
+            .iter()
+            .map(|code| {
+                let a = by_iata(code).unwrap();
+                let point = (a.latitude, a.longitude);
+                (point, distance_km(point, charing_cross))
+            })
+            .collect();
+        let found = locate(&references, (51.6, 0.1)).unwrap();
+        assert!(distance_km(found, charing_cross) < 1.0, "{found:?}");
+        assert_eq!(locate(&references[..1], (51.6, 0.1)), None);
+    }
+}
@@ -5,16 +5,16 @@ use gflights::parsers::common::{TravelClass, Travelers};
 use gflights::requests::api::ApiClient;
 use gflights::requests::config::Config;
 
-use super::OutputFormat;
+use super::{report_diagnostics, OutputFormat};
 
 /// Arguments for the `cheap` subcommand.
 #[derive(Parser, Debug)]
 pub struct CheapArgs {
-    /// Departure airport IATA code or city name (e.g. LUX, "London").
+    /// Departure airport IATA code, metro code or city name (e.g. LUX, LON, "London").
     #[arg(long)]
     pub from: String,
 
-    /// Destination airport IATA code or city name (e.g. JFK, "New York").
+    /// Destination airport IATA code, metro code or city name (e.g. JFK, NYC, "New York").
     #[arg(long)]
     pub to: String,
 
@@ -54,6 +54,7 @@ pub async fn cmd_cheap(args: CheapArgs, client: &ApiClient) -> Result<()> {
         .travelers(travelers)
         .travel_class(args.class)
         .build()?;
+    report_diagnostics(config.validate())?;
 
     let results = client
         .cheapest_dates(&config, Months::new(args.months), args.trip_days)
@@ -5,16 +5,16 @@ use gflights::parsers::common::{StopOptions, TravelClass, Travelers};
 use gflights::requests::api::ApiClient;
 use gflights::requests::config::Config;
 
-use super::OutputFormat;
+use super::{report_diagnostics, OutputFormat};
 
 /// Arguments for the `dgrid` subcommand.
 #[derive(Parser, Debug)]
 pub struct DateGridArgs {
-    /// Departure airport IATA code or city name.
+    /// Departure airport IATA code, metro code or city name.
     #[arg(long)]
     pub from: String,
 
-    /// Destination airport IATA code or city name.
+    /// Destination airport IATA code, metro code or city name.
     #[arg(long)]
     pub to: String,
 
@@ -66,6 +66,7 @@ pub async fn cmd_date_grid(args: DateGridArgs, client: &ApiClient) -> Result<()>
         .travel_class(args.class)
         .stop_options(args.stops)
         .build()?;
+    report_diagnostics(config.validate())?;
 
     let grid = client
         .request_date_grid(
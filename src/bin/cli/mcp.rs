@@ -13,9 +13,12 @@ use chrono::{Months, NaiveDate};
 use serde_json::{json, Value};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Stdout};
 
-use gflights::parsers::common::{Location, PlaceType, StopOptions, TravelClass, Travelers};
+use gflights::parsers::common::{
+    Location, PlaceType, StopOptions, TimeWindow, TravelClass, Travelers,
+};
 use gflights::requests::api::ApiClient;
-use gflights::requests::config::{Config, DealConfig, ExploreConfig, ExploreDate};
+use gflights::requests::config::{Config, DealConfig, Diagnostic, ExploreConfig, ExploreDate};
+use gflights::requests::flexible::FlexibleDates;
 
 /// MCP protocol revision this server implements.
 const PROTOCOL_VERSION: &str = "2025-06-18";
@@ -128,19 +131,26 @@ async fn send_error(out: &mut Stdout, id: Value, code: i64, message: &str) -> Re
 
 fn tool_catalog() -> Vec<Value> {
     let route_props = json!({
-        "from": { "type": "string", "description": "Departure IATA code or city name" },
-        "to": { "type": "string", "description": "Destination IATA code or city name" },
+        "from": { "type": "string", "description": "Departure IATA code, metro code (e.g. LON) or city name" },
+        "to": { "type": "string", "description": "Destination IATA code, metro code (e.g. NYC) or city name" },
         "date": { "type": "string", "description": "Departure date YYYY-MM-DD" },
         "return_date": { "type": "string", "description": "Return date YYYY-MM-DD (omit for one-way)" },
         "adults": { "type": "integer", "minimum": 1, "default": 1 },
+        "child_ages": { "type": "array", "items": { "type": "integer", "minimum": 0 }, "description": "Age of each child on the travel date; under 2 is an infant in their own seat, 12 and over an adult fare" },
         "class": { "type": "string", "enum": ["economy", "premium-economy", "business", "first"] },
-        "stops": { "type": "string", "enum": ["all", "nonstop", "one-stop"] }
+        "stops": { "type": "string", "enum": ["all", "nonstop", "one-stop"] },
+        "depart_times": { "type": "array", "items": { "type": "string" }, "description": "Outbound departure windows, any of which may match: early-morning, morning, afternoon, evening, red-eye, by-<window> (until it ends) or an hour range like 6-10" },
+        "arrive_times": { "type": "array", "items": { "type": "string" }, "description": "Outbound arrival windows, same values as depart_times" },
+        "return_depart_times": { "type": "array", "items": { "type": "string" }, "description": "Return departure windows, same values as depart_times" },
+        "return_arrive_times": { "type": "array", "items": { "type": "string" }, "description": "Return arrival windows, e.g. [\"by-evening\"]" },
+        "flex_days": { "type": "integer", "minimum": 0, "description": "Also search this many days either side of date (and return_date); results are tagged with their dates" },
+        "flex_best": { "type": "integer", "minimum": 1, "default": 3, "description": "With flex_days, how many of the cheapest date pairs to search in full" }
     });
 
     vec![
         json!({
             "name": "search",
-            "description": "Search flights for a route and date (one-way or round-trip). Returns itineraries with price, stops, duration, and legs.",
+            "description": "Search flights for a route and date (one-way or round-trip). Returns itineraries with price, stops, duration, and legs. With flex_days, returns {dates, itineraries} for the cheapest nearby dates, each itinerary tagged with its dates.",
             "inputSchema": { "type": "object", "properties": route_props, "required": ["from", "to", "date"] }
         }),
         json!({
@@ -277,6 +287,41 @@ fn travelers_for(adults: u32) -> std::result::Result<Travelers, String> {
     Travelers::new(vec![adults as i32, 0, 0, 0]).map_err(|e| e.to_string())
 }
 
+/// The time windows listed under `key`, empty when it is absent.
+fn time_windows(args: &Value, key: &str) -> std::result::Result<Vec<TimeWindow>, String> {
+    args.get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|v| {
+            v.as_str()
+                .ok_or_else(|| format!("{key}: {v} is not a string"))?
+                .parse()
+                .map_err(|e| format!("{key}: {e}"))
+        })
+        .collect()
+}
+
+/// `adults` plus one passenger per entry of `child_ages`, checked against
+/// the airlines' rules for infants and minors.
+fn travelers_with_children(args: &Value, adults: u32) -> std::result::Result<Travelers, String> {
+    let ages = args
+        .get("child_ages")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|v| {
+            v.as_u64()
+                .map(|n| n as u32)
+                .ok_or_else(|| format!("child_ages: {v} is not an age"))
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    ages.into_iter()
+        .fold(Travelers::builder().adults(adults), |b, age| b.aged(age))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
 /// Build a route `Config` from the common argument set shared by search,
 /// price_graph, and cheapest_dates. `with_return` controls whether a
 /// `return_date` argument is honoured.
@@ -298,7 +343,7 @@ async fn build_route_config(
         .await
         .map_err(|e| e.to_string())?
         .departing_date(date)
-        .travelers(travelers_for(adults)?);
+        .travelers(travelers_with_children(args, adults)?);
 
     if let Some(c) = opt_str(args, "class") {
         b = b.travel_class(parse_class(&c)?);
@@ -306,13 +351,45 @@ async fn build_route_config(
     if let Some(s) = opt_str(args, "stops") {
         b = b.stop_options(parse_stops(&s)?);
     }
+    for window in time_windows(args, "depart_times")? {
+        b = b.depart_during(window);
+    }
+    for window in time_windows(args, "arrive_times")? {
+        b = b.arrive_during(window);
+    }
     if with_return {
         if let Some(ret) = opt_str(args, "return_date") {
             b = b.return_date(parse_date(&ret)?);
         }
+        for window in time_windows(args, "return_depart_times")? {
+            b = b.return_depart_during(window);
+        }
+        for window in time_windows(args, "return_arrive_times")? {
+            b = b.return_arrive_during(window);
+        }
     }
 
-    b.build().map_err(|e| e.to_string())
+    let config = b.build().map_err(config_error)?;
+    let diagnostics = config.validate();
+    if diagnostics.iter().any(Diagnostic::is_error) {
+        return Err(diagnostics_error(&diagnostics));
+    }
+    Ok(config)
+}
+
+/// Error text for a search that failed validation: a JSON object listing
+/// every diagnostic, so the client can fix each field in one round-trip.
+fn diagnostics_error(diagnostics: &[Diagnostic]) -> String {
+    json!({ "error": "invalid search", "diagnostics": diagnostics }).to_string()
+}
+
+/// Error text for a failed `build()`: validation failures become
+/// [`diagnostics_error`], anything else stays plain text.
+fn config_error(e: anyhow::Error) -> String {
+    match e.downcast_ref::<gflights::Error>() {
+        Some(gflights::Error::Validation(diagnostics)) => diagnostics_error(diagnostics),
+        _ => e.to_string(),
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -321,11 +398,20 @@ async fn build_route_config(
 
 async fn tool_search(args: &Value, client: &ApiClient) -> std::result::Result<String, String> {
     let config = build_route_config(args, client, true).await?;
+    if let Some(days) = opt_u32(args, "flex_days") {
+        let best = opt_u32(args, "flex_best").map_or(FlexibleDates::DEFAULT_BEST, |n| n as usize);
+        let res = client
+            .request_flights_flexible(&config, &FlexibleDates::new(days).with_best(best))
+            .await
+            .map_err(|e| e.to_string())?;
+        return serde_json::to_string(&res).map_err(|e| e.to_string());
+    }
     let res = client
         .request_flights(&config)
         .await
         .map_err(|e| e.to_string())?;
-    let flights = res.get_all_flights();
+    // Google's time filter is a single hour range; apply the windows exactly.
+    let flights = res.get_flights_filtered(&[], &config.departing_times);
     serde_json::to_string(&flights).map_err(|e| e.to_string())
 }
 
@@ -481,6 +567,33 @@ mod tests {
         assert!(parse_stops("zzz").is_err());
     }
 
+    #[test]
+    fn validation_errors_are_reported_as_json() {
+        let err = Config::builder()
+            .departing_date(parse_date("2030-05-01").unwrap())
+            .departure_location(Location {
+                loc_identifier: "LHR".into(),
+                loc_type: PlaceType::Airport,
+                location_name: None,
+            })
+            .destination_location(Location {
+                loc_identifier: "LHR".into(),
+                loc_type: PlaceType::Airport,
+                location_name: None,
+            })
+            .max_price(0)
+            .build()
+            .unwrap_err();
+        let v: Value = serde_json::from_str(&config_error(err)).unwrap();
+        let diagnostics = v["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0]["field"].as_str(), Some("destination"));
+        assert_eq!(diagnostics[0]["severity"].as_str(), Some("error"));
+        assert_eq!(diagnostics[1]["field"].as_str(), Some("max_price"));
+
+        assert_eq!(config_error(anyhow::anyhow!("boom")), "boom");
+    }
+
     #[test]
     fn req_str_reports_missing() {
         let v = json!({ "from": "LHR" });
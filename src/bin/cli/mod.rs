@@ -3,7 +3,7 @@ use chrono::NaiveDate;
 use clap::{Parser, Subcommand, ValueEnum};
 use gflights::parsers::common::{StopOptions, TravelClass, Travelers};
 use gflights::requests::api::ApiClient;
-use gflights::requests::config::{Config, Currency};
+use gflights::requests::config::{validate, Config, Currency, Diagnostic};
 use rustyline::error::ReadlineError;
 use rustyline::DefaultEditor;
 
@@ -58,9 +58,10 @@ pub struct Cli {
     pub user_agent: Option<String>,
 
     /// Route all requests through a proxy (e.g. http://host:3128,
-    /// socks5://127.0.0.1:9050). Supports http(s) and socks5.
+    /// socks5://127.0.0.1:9050). Supports http(s) and socks5. Repeat to
+    /// rotate across a pool; rate-limited or failing proxies are skipped.
     #[arg(long, global = true)]
-    pub proxy: Option<String>,
+    pub proxy: Vec<String>,
 
     /// Result currency for prices, applied to every request (e.g. euro,
     /// us-dollar, british-pound).
@@ -145,14 +146,22 @@ pub enum Commands {
 /// Options shared by all subcommands that use a single date-pair route.
 #[derive(Parser, Debug)]
 pub struct CommonArgs {
-    /// Departure airport IATA code or city name (e.g. LHR, "London").
+    /// Departure airport IATA code, metro code or city name (e.g. LHR, LON, "London").
     #[arg(long)]
     pub from: String,
 
-    /// Destination airport IATA code or city name (e.g. JFK, "New York").
+    /// Destination airport IATA code, metro code or city name (e.g. JFK, NYC, "New York").
     #[arg(long)]
     pub to: String,
 
+    /// Search every airport within this many km of --from (up to 7, nearest first).
+    #[arg(long = "from-radius", value_name = "KM")]
+    pub from_radius: Option<f64>,
+
+    /// Search every airport within this many km of --to (up to 7, nearest first).
+    #[arg(long = "to-radius", value_name = "KM")]
+    pub to_radius: Option<f64>,
+
     /// Outbound departure date in YYYY-MM-DD format.
     #[arg(long)]
     pub date: NaiveDate,
@@ -177,6 +186,11 @@ pub struct CommonArgs {
     #[arg(long = "infants-lap", default_value = "0")]
     pub infants_lap: u32,
 
+    /// Age of a child on the travel date; repeatable.  Under 2 books an
+    /// infant in their own seat, 12 and over an adult fare.
+    #[arg(long = "child-age", value_name = "YEARS")]
+    pub child_ages: Vec<u32>,
+
     /// Travel class.
     #[arg(long, default_value = "economy")]
     pub class: TravelClass,
@@ -195,18 +209,27 @@ pub struct CommonArgs {
 // ---------------------------------------------------------------------------
 
 pub async fn build_config(common: &CommonArgs, client: &ApiClient) -> Result<Config> {
-    let travelers = Travelers::new(vec![
-        common.adults as i32,
-        common.children as i32,
-        common.infants_lap as i32,
-        common.infants_seat as i32,
-    ])?;
-
-    let mut builder = Config::builder()
-        .departure(&common.from, client)
-        .await?
-        .destination(&common.to, client)
-        .await?
+    let travelers = build_travelers(
+        common.adults,
+        common.children,
+        common.infants_seat,
+        common.infants_lap,
+        &common.child_ages,
+    )?;
+
+    let builder = match common.from_radius {
+        Some(km) => {
+            Config::builder()
+                .departure_within(&common.from, km, client)
+                .await?
+        }
+        None => Config::builder().departure(&common.from, client).await?,
+    };
+    let builder = match common.to_radius {
+        Some(km) => builder.destination_within(&common.to, km, client).await?,
+        None => builder.destination(&common.to, client).await?,
+    };
+    let mut builder = builder
         .departing_date(common.date)
         .travelers(travelers)
         .travel_class(common.class)
@@ -216,7 +239,37 @@ pub async fn build_config(common: &CommonArgs, client: &ApiClient) -> Result<Con
         builder = builder.return_date(ret);
     }
 
-    builder.build()
+    let config = builder.build()?;
+    report_diagnostics(config.validate())?;
+    Ok(config)
+}
+
+/// Passengers from the count flags plus one per `--child-age`, checked
+/// against the airlines' rules for infants and minors.
+pub fn build_travelers(
+    adults: u32,
+    children: u32,
+    infants_seat: u32,
+    infants_lap: u32,
+    child_ages: &[u32],
+) -> Result<Travelers> {
+    let builder = Travelers::builder()
+        .adults(adults)
+        .children(children)
+        .infants_in_seat(infants_seat)
+        .infants_on_lap(infants_lap);
+    let builder = child_ages.iter().fold(builder, |b, age| b.aged(*age));
+    Ok(builder.build()?)
+}
+
+/// Prints the warnings among `diagnostics` to stderr, or fails with every
+/// error when there are any, so a search that cannot return results is
+/// never sent.
+pub fn report_diagnostics(diagnostics: Vec<Diagnostic>) -> Result<()> {
+    for warning in validate::into_result(diagnostics)? {
+        eprintln!("{warning}");
+    }
+    Ok(())
 }
 
 // ---------------------------------------------------------------------------
@@ -436,6 +489,115 @@ mod tests {
         }
     }
 
+    #[test]
+    fn repl_parse_search_with_flex_and_radius() {
+        let rc = parse(&[
+            "search",
+            "--from",
+            "PRG",
+            "--from-radius",
+            "150",
+            "--to",
+            "LIS",
+            "--date",
+            "2026-08-01",
+            "--flex",
+            "3",
+        ])
+        .expect("search with --flex should parse");
+        match rc.command {
+            Commands::Search(args) => {
+                assert_eq!(args.flex, Some(3));
+                assert_eq!(args.flex_best, 3);
+                assert_eq!(args.common.from_radius, Some(150.0));
+                assert_eq!(args.common.to_radius, None);
+            }
+            other => panic!("expected Search, got {other:?}"),
+        }
+        assert!(parse(&[
+            "search",
+            "--from",
+            "PRG",
+            "--to",
+            "LIS",
+            "--date",
+            "2026-08-01",
+            "--flex",
+            "3",
+            "--flex-best",
+            "0"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn repl_parse_search_time_windows() {
+        let rc = parse(&[
+            "search",
+            "--from",
+            "LHR",
+            "--to",
+            "FRA",
+            "--date",
+            "2026-09-07",
+            "--return",
+            "2026-09-11",
+            "--depart",
+            "morning",
+            "--depart",
+            "6-8",
+            "--return-arrive",
+            "by-evening",
+        ])
+        .expect("search with time windows should parse");
+        let Commands::Search(args) = rc.command else {
+            panic!("expected Search");
+        };
+        assert_eq!(args.depart.len(), 2);
+        assert_eq!(args.return_arrive[0].end_hour(), 21);
+        assert!(parse(&[
+            "search",
+            "--from",
+            "LHR",
+            "--to",
+            "FRA",
+            "--date",
+            "2026-09-07",
+            "--arrive",
+            "noon"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn child_ages_are_categorised_and_checked() {
+        let rc = parse(&[
+            "search",
+            "--from",
+            "LHR",
+            "--to",
+            "FAO",
+            "--date",
+            "2026-08-01",
+            "--adults",
+            "2",
+            "--child-age",
+            "9",
+            "--child-age",
+            "1",
+        ])
+        .expect("search with --child-age should parse");
+        let Commands::Search(args) = rc.command else {
+            panic!("expected Search");
+        };
+        assert_eq!(args.common.child_ages, [9, 1]);
+        let t = build_travelers(2, 0, 0, 0, &args.common.child_ages).expect("valid family");
+        assert_eq!((t.adults, t.children, t.infant_in_seat), (2, 1, 1));
+
+        // Children alone are refused before any request is sent.
+        assert!(build_travelers(0, 0, 0, 0, &[9, 7]).is_err());
+    }
+
     #[test]
     fn repl_parse_dgrid_command() {
         let rc = parse(&[
@@ -643,6 +805,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn repl_parse_mcity_leg_time_windows() {
+        let rc = parse(&[
+            "mcity",
+            "--leg",
+            "LHR,FRA,2026-09-07,early-morning+morning",
+            "--leg",
+            "FRA,LHR,2026-09-11,,by-evening",
+        ])
+        .expect("mcity legs with time windows should parse");
+        let Commands::MultiCity(args) = rc.command else {
+            panic!("expected MultiCity");
+        };
+        assert_eq!(args.legs[0].depart.len(), 2);
+        assert!(args.legs[0].arrive.is_empty());
+        assert!(args.legs[1].depart.is_empty());
+        assert_eq!(args.legs[1].arrive[0].end_hour(), 21);
+        assert!(parse(&[
+            "mcity",
+            "--leg",
+            "LHR,FRA,2026-09-07,lunch",
+            "--leg",
+            "FRA,LHR,2026-09-11"
+        ])
+        .is_err());
+    }
+
     #[test]
     fn repl_parse_mcity_invalid_leg_format() {
         let result = parse(&["mcity", "--leg", "LUX-FCO-2026-09-10"]);
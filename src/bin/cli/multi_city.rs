@@ -1,43 +1,71 @@
 use anyhow::Result;
 use chrono::NaiveDate;
 use clap::Parser;
-use gflights::parsers::common::{SortOrder, TravelClass, Travelers};
+use gflights::parsers::common::{SortOrder, TimeWindow, TravelClass};
 use gflights::requests::api::ApiClient;
-use gflights::requests::config::MultiCityConfig;
+use gflights::requests::config::{LegFilters, MultiCityConfig};
 
-use super::OutputFormat;
+use super::{build_travelers, report_diagnostics, OutputFormat};
 
 /// A single leg specified on the command line.
 ///
-/// Use the repeatable `--leg FROM TO DATE` flag to add each leg.
+/// Use the repeatable `--leg FROM,TO,DATE[,DEPART[,ARRIVE]]` flag to add each
+/// leg.  DEPART and ARRIVE are time windows (see `search --depart`), several
+/// joined with `+`, e.g. `LHR,FRA,2026-09-10,early-morning+morning,by-evening`.
 #[derive(Debug, Clone)]
 pub struct LegArg {
     pub from: String,
     pub to: String,
     pub date: NaiveDate,
+    pub depart: Vec<TimeWindow>,
+    pub arrive: Vec<TimeWindow>,
+}
+
+impl LegArg {
+    /// Per-leg filters carrying this leg's time windows.
+    fn filters(&self) -> LegFilters {
+        let filters = self
+            .depart
+            .iter()
+            .fold(LegFilters::default(), |f, w| f.depart_during(*w));
+        self.arrive.iter().fold(filters, |f, w| f.arrive_during(*w))
+    }
+}
+
+/// `+`-separated time windows; empty for an empty string.
+fn parse_windows(s: &str) -> Result<Vec<TimeWindow>> {
+    s.split('+')
+        .map(str::trim)
+        .filter(|w| !w.is_empty())
+        .map(str::parse)
+        .collect()
 }
 
 impl std::str::FromStr for LegArg {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let parts: Vec<&str> = s.splitn(3, ',').collect();
-        if parts.len() != 3 {
-            anyhow::bail!("leg must be FROM,TO,DATE (e.g. LUX,FCO,2026-09-10), got: {s}");
+        let parts: Vec<&str> = s.splitn(5, ',').collect();
+        if parts.len() < 3 {
+            anyhow::bail!(
+                "leg must be FROM,TO,DATE[,DEPART[,ARRIVE]] (e.g. LUX,FCO,2026-09-10), got: {s}"
+            );
         }
         Ok(LegArg {
             from: normalize_location(parts[0].trim()),
             to: normalize_location(parts[1].trim()),
             date: NaiveDate::parse_from_str(parts[2].trim(), "%Y-%m-%d")
                 .map_err(|e| anyhow::anyhow!("invalid date '{}': {e}", parts[2]))?,
+            depart: parse_windows(parts.get(3).copied().unwrap_or_default())?,
+            arrive: parse_windows(parts.get(4).copied().unwrap_or_default())?,
         })
     }
 }
 
 /// Normalize a location string for use with [`MultiCityConfigBuilder::add_leg`].
 ///
-/// 3-char all-alphabetic tokens are treated as IATA airport codes and uppercased
-/// (e.g. `"lux"` → `"LUX"`).  Anything else is a city or region name and is
+/// 3-char all-alphabetic tokens are treated as IATA airport or metro codes and
+/// uppercased (e.g. `"lux"` → `"LUX"`, `"lon"` → `"LON"`).  Anything else is a city or region name and is
 /// preserved as-is so the city-lookup API can match it correctly
 /// (e.g. `"London"` stays `"London"`, not `"LONDON"`).
 fn normalize_location(s: &str) -> String {
@@ -72,6 +100,11 @@ pub struct MultiCityArgs {
     #[arg(long = "infants-lap", default_value = "0")]
     pub infants_lap: u32,
 
+    /// Age of a child on the travel date; repeatable.  Under 2 books an
+    /// infant in their own seat, 12 and over an adult fare.
+    #[arg(long = "child-age", value_name = "YEARS")]
+    pub child_ages: Vec<u32>,
+
     /// Travel class.
     #[arg(long, default_value = "economy")]
     pub class: TravelClass,
@@ -90,12 +123,13 @@ pub async fn cmd_multi_city(args: MultiCityArgs, client: &ApiClient) -> Result<(
         anyhow::bail!("multi-city requires at least 2 legs");
     }
 
-    let travelers = Travelers::new(vec![
-        args.adults as i32,
-        args.children as i32,
-        args.infants_lap as i32,
-        args.infants_seat as i32,
-    ])?;
+    let travelers = build_travelers(
+        args.adults,
+        args.children,
+        args.infants_seat,
+        args.infants_lap,
+        &args.child_ages,
+    )?;
 
     let mut builder = MultiCityConfig::builder()
         .travellers(travelers)
@@ -104,13 +138,17 @@ pub async fn cmd_multi_city(args: MultiCityArgs, client: &ApiClient) -> Result<(
 
     for leg in &args.legs {
         builder = builder
-            .add_leg(&leg.from, &leg.to, leg.date, client)
+            .add_leg_with_filters(&leg.from, &leg.to, leg.date, client, leg.filters())
             .await?;
     }
 
     let config = builder.build()?;
+    report_diagnostics(config.validate())?;
     let results = client.request_multi_city_flights(&config).await?;
-    let flights = results.get_all_flights();
+    // The results are options for the first leg; Google's time filter is a
+    // single hour range, so check that leg's windows client-side.
+    let first_leg_times = &config.legs[0].departing_times;
+    let flights = results.get_flights_filtered(&[], first_leg_times);
 
     if flights.is_empty() {
         eprintln!("No flights found.");
@@ -1,7 +1,8 @@
 use anyhow::Result;
 use clap::Parser;
-use gflights::parsers::common::{AirlineFilter, SortOrder, StopoverDuration};
+use gflights::parsers::common::{AirlineFilter, SortOrder, StopoverDuration, TimeWindow};
 use gflights::requests::api::ApiClient;
+use gflights::requests::flexible::{DatedItinerary, FlexibleDates};
 
 use super::{build_config, CommonArgs, OutputFormat};
 use gflights::requests::config::Config;
@@ -43,6 +44,24 @@ pub struct SearchArgs {
     #[arg(long = "via")]
     pub connecting_airports: Vec<String>,
 
+    /// Outbound departure window: early-morning, morning, afternoon, evening,
+    /// red-eye, by-<window> (until it ends) or an hour range such as 6-10.
+    /// May be repeated to allow several.
+    #[arg(long = "depart", value_name = "WINDOW")]
+    pub depart: Vec<TimeWindow>,
+
+    /// Outbound arrival window (same values as --depart).  May be repeated.
+    #[arg(long = "arrive", value_name = "WINDOW")]
+    pub arrive: Vec<TimeWindow>,
+
+    /// Return departure window (same values as --depart).  May be repeated.
+    #[arg(long = "return-depart", value_name = "WINDOW")]
+    pub return_depart: Vec<TimeWindow>,
+
+    /// Return arrival window, e.g. by-evening.  May be repeated.
+    #[arg(long = "return-arrive", value_name = "WINDOW")]
+    pub return_arrive: Vec<TimeWindow>,
+
     /// Show a CO₂ emissions column (kg per passenger).
     #[arg(long = "show-co2")]
     pub show_co2: bool,
@@ -50,6 +69,20 @@ pub struct SearchArgs {
     /// Show detailed info: layover airports and +1 marker for next-day arrivals.
     #[arg(long)]
     pub detail: bool,
+
+    /// Flexible dates: also search up to this many days either side of --date
+    /// (and --return), and show the cheapest date pairs together.
+    #[arg(long, value_name = "DAYS")]
+    pub flex: Option<u32>,
+
+    /// With --flex, how many of the cheapest date pairs to search in full.
+    #[arg(
+        long = "flex-best",
+        value_name = "N",
+        default_value_t = FlexibleDates::DEFAULT_BEST,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub flex_best: usize,
 }
 
 pub async fn cmd_search(args: SearchArgs, client: &ApiClient) -> Result<()> {
@@ -68,31 +101,69 @@ pub async fn cmd_search(args: SearchArgs, client: &ApiClient) -> Result<()> {
     if let Some(mins) = args.max_layover {
         config.stopover_max = StopoverDuration::Minutes(mins);
     }
+    for window in args.depart {
+        config.departing_times = config.departing_times.depart_during(window);
+    }
+    for window in args.arrive {
+        config.departing_times = config.departing_times.arrive_during(window);
+    }
+    for window in args.return_depart {
+        config.return_times = config.return_times.depart_during(window);
+    }
+    for window in args.return_arrive {
+        config.return_times = config.return_times.arrive_during(window);
+    }
 
-    let results = client.request_flights(&config).await?;
-    // Strict "via": Google's other_flights container leaks non-stops that skip
-    // the requested connecting airport, so filter client-side.
-    let mut flights = results.get_all_flights_via(&config.connecting_airports);
+    let mut flights: Vec<DatedItinerary> = match args.flex {
+        Some(days) => {
+            let flex = FlexibleDates::new(days).with_best(args.flex_best);
+            let results = client.request_flights_flexible(&config, &flex).await?;
+            for failed in &results.failed {
+                let date = &failed.date;
+                match date.return_date {
+                    Some(ret) => eprintln!(
+                        "Search for {} → {ret} failed: {}",
+                        date.departure_date, failed.error
+                    ),
+                    None => eprintln!(
+                        "Search for {} failed: {}",
+                        date.departure_date, failed.error
+                    ),
+                }
+            }
+            results.itineraries
+        }
+        None => {
+            let results = client.request_flights(&config).await?;
+            // Strict "via" and time windows: Google's other_flights container leaks
+            // non-stops that skip the requested connecting airport, and its time
+            // filter is a single hour range, so filter client-side.
+            results
+                .get_flights_filtered(&config.connecting_airports, &config.departing_times)
+                .into_iter()
+                .map(|itinerary| DatedItinerary {
+                    departure_date: config.departing_date,
+                    return_date: config.return_date,
+                    itinerary,
+                })
+                .collect()
+        }
+    };
 
     // Client-side sort — guarantees the requested order regardless of what
     // Google returns.  `Best` keeps Google's own ordering.
     match args.sort {
         SortOrder::Best => {}
         SortOrder::Price => {
-            flights.sort_by_key(|f| {
-                f.itinerary_cost
-                    .trip_cost
-                    .as_ref()
-                    .map(|c| c.price)
-                    .unwrap_or(i32::MAX)
-            });
+            flights.sort_by_key(|f| f.price().unwrap_or(i32::MAX));
         }
         SortOrder::Duration => {
-            flights.sort_by_key(|f| f.itinerary.total_time_minutes);
+            flights.sort_by_key(|f| f.itinerary.itinerary.total_time_minutes);
         }
         SortOrder::DepartureTime => {
             flights.sort_by_key(|f| {
                 f.itinerary
+                    .itinerary
                     .flight_details
                     .first()
                     .map(|d| d.departure_time.hour.unwrap_or(0) * 60 + d.departure_time.minute)
@@ -101,6 +172,7 @@ pub async fn cmd_search(args: SearchArgs, client: &ApiClient) -> Result<()> {
         SortOrder::ArrivalTime => {
             flights.sort_by_key(|f| {
                 f.itinerary
+                    .itinerary
                     .flight_details
                     .last()
                     .map(|d| d.arrival_time.hour.unwrap_or(0) * 60 + d.arrival_time.minute)
@@ -114,11 +186,18 @@ pub async fn cmd_search(args: SearchArgs, client: &ApiClient) -> Result<()> {
     }
 
     match args.common.format {
-        OutputFormat::Json => {
+        OutputFormat::Json if args.flex.is_some() => {
             println!("{}", serde_json::to_string_pretty(&flights)?);
         }
+        OutputFormat::Json => {
+            let itineraries: Vec<_> = flights.iter().map(|f| &f.itinerary).collect();
+            println!("{}", serde_json::to_string_pretty(&itineraries)?);
+        }
         OutputFormat::Table => {
             // Build header dynamically depending on flags.
+            if args.flex.is_some() {
+                print!("{:<22}  ", "DATES");
+            }
             if args.show_co2 {
                 println!(
                     "{:<8}  {:>6}  {:>5}  {:>5}  {:>7}  ROUTE",
@@ -130,9 +209,19 @@ pub async fn cmd_search(args: SearchArgs, client: &ApiClient) -> Result<()> {
                     "AIRLINE", "PRICE", "STOPS", "MINS"
                 );
             }
-            println!("{}", "-".repeat(if args.show_co2 { 70 } else { 60 }));
+            let width = if args.show_co2 { 70 } else { 60 };
+            let width = width + if args.flex.is_some() { 24 } else { 0 };
+            println!("{}", "-".repeat(width));
 
-            for f in &flights {
+            for dated in &flights {
+                let f = &dated.itinerary;
+                if args.flex.is_some() {
+                    let dates = match dated.return_date {
+                        Some(ret) => format!("{} → {}", dated.departure_date, ret),
+                        None => dated.departure_date.to_string(),
+                    };
+                    print!("{dates:<22}  ");
+                }
                 let price = f
                     .itinerary_cost
                     .trip_cost
@@ -114,7 +114,9 @@ async fn pick_leg(
     client: &ApiClient,
 ) -> Result<Option<ItineraryContainer>> {
     let result = client.request_flights(config).await?;
-    let flights = result.get_all_flights();
+    // Google's time filter is a single hour range; apply the windows of the
+    // leg being listed exactly.
+    let flights = result.get_flights_filtered(&[], config.listed_leg_times());
     if flights.is_empty() {
         eprintln!("No {label} flights found.");
         return Ok(None);
@@ -23,6 +23,8 @@ use anyhow::Result;
 use clap::Parser;
 use cli::{run_command, run_repl, Cli};
 use gflights::requests::api::ApiClient;
+use gflights::requests::frontend_version::VersionRefreshPolicy;
+use gflights::requests::proxy_pool::{ProxyPool, ProxyPoolConfig};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -36,15 +38,21 @@ async fn main() -> Result<()> {
         )
         .init();
 
-    // Create one shared ApiClient (fetches the frontend version once).
-    let mut client = match cli.proxy.clone() {
-        Some(proxy) => ApiClient::new_with_proxy(proxy).await?,
-        None => ApiClient::new().await,
+    // Create one shared ApiClient (probes the frontend version up front).
+    let mut client = match cli.proxy.as_slice() {
+        [] => ApiClient::new().await,
+        [proxy] => ApiClient::new_with_proxy(proxy.as_str()).await?,
+        proxies => {
+            let pool = ProxyPool::new(proxies, ProxyPoolConfig::default())?;
+            ApiClient::new_with_proxy_pool(pool).await
+        }
     };
     if let Some(ua) = cli.user_agent.clone() {
         client = client.with_user_agent(ua);
     }
     client = client.with_locale(cli.currency.clone(), cli.lang.clone(), cli.country.clone());
+    // The REPL and MCP server can run for days; keep the build label current.
+    client = client.with_frontend_version_refresh(VersionRefreshPolicy::default());
 
     match cli.command {
         Some(cmd) => run_command(cmd, &client).await,
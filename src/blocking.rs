@@ -0,0 +1,406 @@
+//! Synchronous facade over the async [`ApiClient`](crate::requests::api::ApiClient).
+//!
+//! [`ApiClient`] owns a single-threaded tokio runtime and blocks on it for
+//! every call, so sync programs (batch tools, build scripts) need no runtime
+//! of their own.  Clones share the runtime and the wrapped client.
+//!
+//! ```no_run
+//! use chrono::{Duration, Utc};
+//! use gflights::blocking::ApiClient;
+//! use gflights::requests::config::Config;
+//!
+//! fn main() -> anyhow::Result<()> {
+//!     let client = ApiClient::new()?;
+//!     let config = Config::builder()
+//!         .departure_blocking("LHR", &client)?
+//!         .destination_blocking("JFK", &client)?
+//!         .departing_date(Utc::now().date_naive() + Duration::days(14))
+//!         .build()?;
+//!
+//!     let results = client.request_flights(&config)?;
+//!     println!("{} flights", results.get_all_flights().len());
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Like `reqwest::blocking`, these methods panic if called from inside an
+//! async runtime; use the async client there.
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{Months, NaiveDate};
+use futures::stream::BoxStream;
+use futures::StreamExt as _;
+use governor::Quota;
+use tokio::runtime::Runtime;
+
+use crate::error::{Error, Result};
+use crate::parsers::calendar_graph_response::GraphRawResponseContainer;
+use crate::parsers::city_response::ResponseInnerBodyParsed;
+use crate::parsers::date_grid_response::{CheapDate, DateGridResponse};
+use crate::parsers::flight_response::{FlightResponseContainer, RawResponse};
+use crate::parsers::offer_response::OfferRawResponseContainer;
+use crate::requests::api::{self, RetryConfig};
+use crate::requests::batch::{BatchRequest, BatchResponse};
+use crate::requests::bulk::BulkOptions;
+use crate::requests::cache::{CacheConfig, CacheStore};
+use crate::requests::clock::{Clock, Rng};
+use crate::requests::config::deals::{DealConfig, DealResult};
+use crate::requests::config::explore::ExploreResult;
+use crate::requests::config::{
+    Config, ConfigBuilder, Currency, ExploreConfig, LegFilters, MultiCityConfig,
+    MultiCityConfigBuilder,
+};
+use crate::requests::frontend_version::{FrontendVersion, VersionRefreshPolicy};
+use crate::requests::metrics::MetricsObserver;
+use crate::requests::proxy_pool::ProxyPool;
+use crate::requests::rate_limit::CooldownPolicy;
+use crate::requests::transport::Transport;
+
+/// Blocking counterpart of [`api::ApiClient`]; see the [module docs](self).
+#[derive(Clone)]
+pub struct ApiClient {
+    inner: api::ApiClient,
+    runtime: Arc<Runtime>,
+}
+
+impl ApiClient {
+    /// See [`api::ApiClient::new`].
+    pub fn new() -> Result<Self> {
+        Self::build(api::ApiClient::new, Ok)
+    }
+
+    /// See [`api::ApiClient::new_with_ratelimit`].
+    pub fn new_with_ratelimit(rate_limiter_quota: Quota) -> Result<Self> {
+        Self::build(
+            || api::ApiClient::new_with_ratelimit(rate_limiter_quota),
+            Ok,
+        )
+    }
+
+    /// See [`api::ApiClient::new_with_proxy`].
+    pub fn new_with_proxy(proxy: impl Into<String>) -> Result<Self> {
+        let proxy = proxy.into();
+        Self::build(|| api::ApiClient::new_with_proxy(proxy), |r| r)
+    }
+
+    /// See [`api::ApiClient::new_with_proxy_pool`].
+    pub fn new_with_proxy_pool(pool: ProxyPool) -> Result<Self> {
+        Self::build(|| api::ApiClient::new_with_proxy_pool(pool), Ok)
+    }
+
+    /// See [`api::ApiClient::new_with_transport`].
+    pub fn new_with_transport(transport: impl Transport + 'static) -> Result<Self> {
+        Self::build(|| api::ApiClient::new_with_transport(transport), Ok)
+    }
+
+    /// See [`api::ApiClient::new_recording`].
+    pub fn new_recording(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        Self::build(|| api::ApiClient::new_recording(path), |r| r)
+    }
+
+    /// See [`api::ApiClient::new_replaying`].
+    pub fn new_replaying(path: impl AsRef<Path>) -> Result<Self> {
+        Self::build(|| api::ApiClient::new_replaying(path), |r| r)
+    }
+
+    /// Starts the runtime and runs an async constructor on it (the
+    /// frontend-version probe needs the runtime's I/O and timers).
+    fn build<F, Fut, T>(make: F, unwrap: impl FnOnce(T) -> Result<api::ApiClient>) -> Result<Self>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| {
+                Error::Transport(anyhow::Error::new(e).context("starting tokio runtime"))
+            })?;
+        let inner = unwrap(runtime.block_on(make()))?;
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// The wrapped async client.
+    pub fn inner(&self) -> &api::ApiClient {
+        &self.inner
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    pub fn with_retry_config(self, retry_config: RetryConfig) -> Self {
+        self.map(|c| c.with_retry_config(retry_config))
+    }
+
+    pub fn with_user_agent(self, user_agent: impl Into<String>) -> Self {
+        self.map(|c| c.with_user_agent(user_agent))
+    }
+
+    pub fn with_currency(self, currency: Currency) -> Self {
+        self.map(|c| c.with_currency(currency))
+    }
+
+    pub fn with_language(self, language: impl Into<String>) -> Self {
+        self.map(|c| c.with_language(language))
+    }
+
+    pub fn with_country(self, country: impl Into<String>) -> Self {
+        self.map(|c| c.with_country(country))
+    }
+
+    pub fn with_locale(
+        self,
+        currency: Currency,
+        language: impl Into<String>,
+        country: impl Into<String>,
+    ) -> Self {
+        self.map(|c| c.with_locale(currency, language, country))
+    }
+
+    pub fn with_cache(self, store: impl CacheStore + 'static, config: CacheConfig) -> Self {
+        self.map(|c| c.with_cache(store, config))
+    }
+
+    pub fn with_rate_limit_cooldown(self, policy: CooldownPolicy) -> Self {
+        self.map(|c| c.with_rate_limit_cooldown(policy))
+    }
+
+    pub fn with_frontend_version_refresh(self, policy: VersionRefreshPolicy) -> Self {
+        self.map(|c| c.with_frontend_version_refresh(policy))
+    }
+
+    pub fn with_metrics(self, observer: Arc<dyn MetricsObserver>) -> Self {
+        self.map(|c| c.with_metrics(observer))
+    }
+
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        self.map(|c| c.with_clock(clock))
+    }
+
+    pub fn with_rng(self, rng: Arc<dyn Rng>) -> Self {
+        self.map(|c| c.with_rng(rng))
+    }
+
+    fn map(mut self, f: impl FnOnce(api::ApiClient) -> api::ApiClient) -> Self {
+        self.inner = f(self.inner);
+        self
+    }
+
+    pub fn frontend_version(&self) -> FrontendVersion {
+        self.inner.frontend_version()
+    }
+
+    pub fn refresh_frontend_version(&self) -> FrontendVersion {
+        self.block_on(self.inner.refresh_frontend_version())
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        self.inner.is_rate_limited()
+    }
+
+    pub fn rate_limit_cooldown_remaining(&self) -> Option<Duration> {
+        self.inner.rate_limit_cooldown_remaining()
+    }
+
+    pub fn reset_rate_limit(&self) {
+        self.inner.reset_rate_limit()
+    }
+
+    /// See [`api::ApiClient::request_city`].
+    pub fn request_city(&self, city: &str) -> Result<ResponseInnerBodyParsed> {
+        self.block_on(self.inner.request_city(city))
+    }
+
+    /// See [`api::ApiClient::request_batch`].
+    pub fn request_batch(&self, batch: &BatchRequest) -> Result<BatchResponse> {
+        self.block_on(self.inner.request_batch(batch))
+    }
+
+    /// See [`api::ApiClient::request_graph`].
+    pub fn request_graph(
+        &self,
+        args: &Config,
+        months: Months,
+    ) -> Result<GraphRawResponseContainer> {
+        self.block_on(self.inner.request_graph(args, months))
+    }
+
+    /// See [`api::ApiClient::request_date_grid`].
+    pub fn request_date_grid(
+        &self,
+        args: &Config,
+        dep_start: NaiveDate,
+        dep_end: NaiveDate,
+        ret_start: NaiveDate,
+        ret_end: NaiveDate,
+    ) -> Result<DateGridResponse> {
+        self.block_on(
+            self.inner
+                .request_date_grid(args, dep_start, dep_end, ret_start, ret_end),
+        )
+    }
+
+    /// See [`api::ApiClient::request_flights`].
+    pub fn request_flights(&self, args: &Config) -> Result<FlightResponseContainer> {
+        self.block_on(self.inner.request_flights(args))
+    }
+
+    /// See [`api::ApiClient::request_flights_stream`]; each `next()` blocks
+    /// until the following frame has been read.
+    pub fn request_flights_stream(
+        &self,
+        args: &Config,
+    ) -> Result<BlockingIter<Result<RawResponse>>> {
+        let stream = self.block_on(self.inner.request_flights_stream(args))?;
+        Ok(self.iter(stream))
+    }
+
+    /// See [`api::ApiClient::request_offer`].
+    pub fn request_offer(&self, args: &Config) -> Result<OfferRawResponseContainer> {
+        self.block_on(self.inner.request_offer(args))
+    }
+
+    /// See [`api::ApiClient::request_multi_city_flights`].
+    pub fn request_multi_city_flights(
+        &self,
+        args: &MultiCityConfig,
+    ) -> Result<FlightResponseContainer> {
+        self.block_on(self.inner.request_multi_city_flights(args))
+    }
+
+    /// See [`api::ApiClient::search_many`]; each `next()` blocks until the
+    /// following search completes.
+    pub fn search_many(
+        &self,
+        configs: impl IntoIterator<Item = Config>,
+        concurrency: usize,
+    ) -> BlockingIter<(usize, Result<FlightResponseContainer>)> {
+        self.iter(self.inner.search_many(configs, concurrency))
+    }
+
+    /// See [`api::ApiClient::search_many_with`].
+    pub fn search_many_with(
+        &self,
+        configs: impl IntoIterator<Item = Config>,
+        options: BulkOptions,
+    ) -> BlockingIter<(usize, Result<FlightResponseContainer>)> {
+        self.iter(self.inner.search_many_with(configs, options))
+    }
+
+    /// See [`api::ApiClient::request_explore`].
+    pub fn request_explore(&self, config: &ExploreConfig) -> Result<Vec<ExploreResult>> {
+        self.block_on(self.inner.request_explore(config))
+    }
+
+    /// See [`api::ApiClient::request_deals`].
+    pub fn request_deals(&self, config: &DealConfig) -> Result<Vec<DealResult>> {
+        self.block_on(self.inner.request_deals(config))
+    }
+
+    /// See [`api::ApiClient::cheapest_dates`].
+    pub fn cheapest_dates(
+        &self,
+        config: &Config,
+        months: Months,
+        trip_duration_days: Option<u32>,
+    ) -> Result<Vec<CheapDate>> {
+        self.block_on(
+            self.inner
+                .cheapest_dates(config, months, trip_duration_days),
+        )
+    }
+
+    /// See [`api::ApiClient::resolve_booking_url`].
+    pub fn resolve_booking_url(&self, click_token: &str) -> Result<String> {
+        self.block_on(self.inner.resolve_booking_url(click_token))
+    }
+
+    fn iter<T>(&self, stream: BoxStream<'static, T>) -> BlockingIter<T> {
+        BlockingIter {
+            stream,
+            runtime: Arc::clone(&self.runtime),
+        }
+    }
+}
+
+/// Iterator over an async stream, driven on the blocking client's runtime.
+pub struct BlockingIter<T> {
+    stream: BoxStream<'static, T>,
+    runtime: Arc<Runtime>,
+}
+
+impl<T> Iterator for BlockingIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+/// Blocking counterparts of the [`ConfigBuilder`] methods that resolve a
+/// location over the network.
+impl ConfigBuilder {
+    /// See [`ConfigBuilder::departure`].
+    pub fn departure_blocking(self, location: &str, client: &ApiClient) -> anyhow::Result<Self> {
+        client.block_on(self.departure(location, client.inner()))
+    }
+
+    /// See [`ConfigBuilder::add_departure`].
+    pub fn add_departure_blocking(
+        self,
+        location: &str,
+        client: &ApiClient,
+    ) -> anyhow::Result<Self> {
+        client.block_on(self.add_departure(location, client.inner()))
+    }
+
+    /// See [`ConfigBuilder::destination`].
+    pub fn destination_blocking(self, location: &str, client: &ApiClient) -> anyhow::Result<Self> {
+        client.block_on(self.destination(location, client.inner()))
+    }
+
+    /// See [`ConfigBuilder::add_destination`].
+    pub fn add_destination_blocking(
+        self,
+        location: &str,
+        client: &ApiClient,
+    ) -> anyhow::Result<Self> {
+        client.block_on(self.add_destination(location, client.inner()))
+    }
+}
+
+/// Blocking counterparts of the [`MultiCityConfigBuilder`] methods that
+/// resolve locations over the network.
+impl MultiCityConfigBuilder {
+    /// See [`MultiCityConfigBuilder::add_leg`].
+    pub fn add_leg_blocking(
+        self,
+        from: &str,
+        to: &str,
+        date: NaiveDate,
+        client: &ApiClient,
+    ) -> anyhow::Result<Self> {
+        client.block_on(self.add_leg(from, to, date, client.inner()))
+    }
+
+    /// See [`MultiCityConfigBuilder::add_leg_with_filters`].
+    pub fn add_leg_with_filters_blocking(
+        self,
+        from: &str,
+        to: &str,
+        date: NaiveDate,
+        client: &ApiClient,
+        filters: LegFilters,
+    ) -> anyhow::Result<Self> {
+        client.block_on(self.add_leg_with_filters(from, to, date, client.inner(), filters))
+    }
+}
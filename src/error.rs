@@ -0,0 +1,236 @@
+//! Crate-wide error type returned by every [`ApiClient`](crate::requests::api::ApiClient)
+//! request method.
+//!
+//! Each variant corresponds to a distinct way a call can fail, so callers can
+//! decide between retrying, reporting a user error or alerting without
+//! inspecting error strings:
+//!
+//! ```no_run
+//! # async fn example(client: gflights::requests::api::ApiClient, config: gflights::requests::config::Config) {
+//! use gflights::Error;
+//!
+//! match client.request_flights(&config).await {
+//!     Ok(results) => println!("{} flights", results.get_all_flights().len()),
+//!     Err(Error::RateLimited) => match client.rate_limit_cooldown_remaining() {
+//!         Some(wait) => eprintln!("rate limited; retry in {}s", wait.as_secs()),
+//!         None => { /* no cooldown policy: back off, then client.reset_rate_limit() */ }
+//!     },
+//!     Err(Error::InvalidConfig(msg)) => eprintln!("bad search: {msg}"),
+//!     Err(e) if e.is_retryable() => { /* try again later */ }
+//!     Err(e) => eprintln!("search failed: {e}"),
+//! }
+//! # }
+//! ```
+
+use reqwest::StatusCode;
+
+use crate::parsers::common::Diagnostic;
+use crate::requests::endpoint::Endpoint;
+
+/// `Result` alias with [`enum@Error`] as the default error type.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors returned by [`ApiClient`](crate::requests::api::ApiClient).
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Google Flights answered HTTP 429, now or on an earlier request from
+    /// this client or one of its clones.  No further requests are sent until
+    /// the block lifts: on its own once the cooldown of a configured
+    /// [`CooldownPolicy`](crate::requests::rate_limit::CooldownPolicy) has
+    /// elapsed (see
+    /// [`ApiClient::rate_limit_cooldown_remaining`](crate::requests::api::ApiClient::rate_limit_cooldown_remaining)),
+    /// or when
+    /// [`ApiClient::reset_rate_limit`](crate::requests::api::ApiClient::reset_rate_limit)
+    /// is called.
+    RateLimited,
+    /// The backend accepted the request but reported errors in the response
+    /// envelope instead of returning data.
+    Backend { messages: Vec<String> },
+    /// The response could not be decoded.  `json_path` locates the failing
+    /// element (empty when the failure is not tied to a single element).
+    Parse {
+        endpoint: Endpoint,
+        json_path: String,
+        message: String,
+    },
+    /// The server kept answering with a non-success status after all retries.
+    Http {
+        endpoint: Endpoint,
+        status: StatusCode,
+    },
+    /// The request could not be sent or its response could not be read
+    /// (connection, TLS, timeout, cassette, …).
+    Transport(anyhow::Error),
+    /// The request could not be built from the supplied configuration.
+    InvalidConfig(String),
+    /// The search configuration failed validation; holds every error found.
+    /// See [`validate`](crate::requests::config::validate).
+    Validation(Vec<Diagnostic>),
+    /// The click-tracker response did not contain a redirect to the booking site.
+    BookingUrlNotFound,
+    /// [`CacheMode::Offline`](crate::requests::cache::CacheMode::Offline) is set
+    /// and the cache has no response for this request.
+    CacheMiss { endpoint: Endpoint },
+}
+
+impl Error {
+    /// Returns `true` for failures that may succeed if the same request is
+    /// sent again later: rate limiting, server errors and transport failures.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::RateLimited | Error::Transport(_) => true,
+            Error::Http { status, .. } => status.is_server_error(),
+            _ => false,
+        }
+    }
+
+    /// Lifts a parser error into [`Error::Parse`] for `endpoint`, keeping any
+    /// typed cause ([`Error::Backend`], a JSON path) the parser attached.
+    pub(crate) fn parse(endpoint: Endpoint, err: anyhow::Error) -> Self {
+        let err = match err.downcast::<Error>() {
+            Ok(typed) => return typed,
+            Err(err) => err,
+        };
+        let json_path = err
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<JsonPathError>())
+            .map(|e| e.json_path.clone())
+            .unwrap_or_default();
+        Error::Parse {
+            endpoint,
+            json_path,
+            message: format!("{err:#}"),
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::RateLimited => write!(f, "Google Flights returned HTTP 429 Too Many Requests — requests on this client are blocked until the cooldown policy lifts the block (see ApiClient::rate_limit_cooldown_remaining()) or ApiClient::reset_rate_limit() is called"),
+            Error::Backend { messages } => {
+                write!(f, "backend returned errors: {}", messages.join("; "))
+            }
+            Error::Parse {
+                endpoint,
+                json_path,
+                message,
+            } if json_path.is_empty() => write!(f, "failed to parse {endpoint} response: {message}"),
+            Error::Parse {
+                endpoint,
+                json_path,
+                message,
+            } => write!(
+                f,
+                "failed to parse {endpoint} response at {json_path}: {message}"
+            ),
+            Error::Http { endpoint, status } => {
+                write!(f, "{endpoint} request failed with HTTP {status}")
+            }
+            Error::Transport(e) => write!(f, "transport error: {e:#}"),
+            Error::InvalidConfig(msg) => write!(f, "invalid configuration: {msg}"),
+            Error::Validation(diagnostics) => {
+                let problems: Vec<String> = diagnostics
+                    .iter()
+                    .map(|d| format!("{}: {}", d.field, d.message))
+                    .collect();
+                write!(f, "invalid configuration: {}", problems.join("; "))
+            }
+            Error::BookingUrlNotFound => {
+                write!(f, "no redirect URL found in click-tracker response")
+            }
+            Error::CacheMiss { endpoint } => {
+                write!(f, "offline cache has no entry for this {endpoint} request")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Transport(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Cause attached by the response decoders when `serde_path_to_error`
+/// reports where deserialisation failed; read back by [`Error::parse`].
+#[derive(Debug)]
+pub(crate) struct JsonPathError {
+    pub(crate) json_path: String,
+    pub(crate) source: serde_json::Error,
+}
+
+impl std::fmt::Display for JsonPathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.source, self.json_path)
+    }
+}
+
+impl std::error::Error for JsonPathError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for JsonPathError {
+    fn from(err: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        Self {
+            json_path: err.path().to_string(),
+            source: err.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_keeps_typed_causes_and_json_path() {
+        let backend = anyhow::Error::new(Error::Backend {
+            messages: vec!["bad".into()],
+        });
+        assert!(matches!(
+            Error::parse(Endpoint::Shopping, backend),
+            Error::Backend { .. }
+        ));
+
+        let jd = &mut serde_json::Deserializer::from_str(r#"{"a":[1,"x"]}"#);
+        let path_err =
+            serde_path_to_error::deserialize::<_, std::collections::HashMap<String, Vec<i32>>>(jd)
+                .unwrap_err();
+        let err = anyhow::Error::new(JsonPathError::from(path_err));
+        match Error::parse(Endpoint::Shopping, err) {
+            Error::Parse {
+                endpoint,
+                json_path,
+                ..
+            } => {
+                assert_eq!(endpoint, Endpoint::Shopping);
+                assert_eq!(json_path, "a[1]");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn retryable_classification() {
+        assert!(Error::RateLimited.is_retryable());
+        assert!(Error::Http {
+            endpoint: Endpoint::Shopping,
+            status: StatusCode::SERVICE_UNAVAILABLE
+        }
+        .is_retryable());
+        assert!(!Error::Http {
+            endpoint: Endpoint::Shopping,
+            status: StatusCode::BAD_REQUEST
+        }
+        .is_retryable());
+        assert!(!Error::InvalidConfig("x".into()).is_retryable());
+        assert!(!Error::BookingUrlNotFound.is_retryable());
+    }
+}
@@ -64,13 +64,16 @@
 //! }
 //! ```
 
+pub mod airports;
+pub mod blocking;
+pub mod error;
 pub mod parsers;
 pub mod protos;
 pub mod requests;
 
+/// Error type returned by every [`requests::api::ApiClient`] request method.
+pub use error::{Error, Result};
 /// Result type from [`requests::api::ApiClient::cheapest_dates`].
 pub use parsers::response::date_grid_response::CheapDate;
-/// Re-exported for downcasting: `err.downcast_ref::<RateLimitedError>()`.
-pub use requests::api::RateLimitedError;
 /// Re-exported for configuring retry behaviour on [`requests::api::ApiClient`].
 pub use requests::api::RetryConfig;
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The search cannot return results as configured.
+    Error,
+    /// The search runs, but probably not the way it was meant to.
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// One problem found in a search configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// The offending field, using the serialised field names
+    /// (e.g. `"return_date"`, `"travellers.infant_on_lap"`, `"legs[1].date"`).
+    pub field: String,
+    pub severity: Severity,
+    /// Human-readable description of the problem.
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.field, self.message)
+    }
+}
+
+/// Splits `diagnostics` into a failure listing the errors, or the warnings
+/// when there are no errors.
+pub fn into_result(diagnostics: Vec<Diagnostic>) -> crate::Result<Vec<Diagnostic>> {
+    let (errors, warnings): (Vec<_>, Vec<_>) =
+        diagnostics.into_iter().partition(Diagnostic::is_error);
+    if errors.is_empty() {
+        Ok(warnings)
+    } else {
+        Err(Error::Validation(errors))
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+use super::time_window::{hour_span, TimeWindow};
 use super::SerializeToWeb;
 
 /// Stop over duration. It can be a number of minutes or unlimited, with default unlimited.
@@ -78,12 +79,43 @@ impl SerializeToWeb for TotalDuration {
 /// Flight times filters. It is the departure hours, and the arrival hours.
 ///
 /// Example: `[0,23,13,23]` → leave between 0:00 and 23:59, arrive between 13:00 and 23:59.
+///
+/// Instead of raw hours, [`depart_during`](Self::depart_during) and
+/// [`arrive_during`](Self::arrive_during) take named [`TimeOfDay`] presets or
+/// [`TimeWindow`]s, several per side:
+///
+/// ```rust
+/// use gflights::parsers::common::{FlightTimes, TimeOfDay, TimeWindow};
+///
+/// // Leave in the morning or the evening, land by 21:00.
+/// let times = FlightTimes::default()
+///     .depart_during(TimeOfDay::Morning)
+///     .depart_during(TimeOfDay::Evening)
+///     .arrive_during(TimeOfDay::Evening.by());
+/// assert!(times.allows_departure(9) && !times.allows_departure(14));
+/// assert_eq!(times.get_departure_hour_min(), Some(8));
+/// ```
+///
+/// Google's filter takes a single hour range per side, so the request
+/// carries the span of the windows and any window across midnight drops the
+/// server-side filter for that side.  [`Itinerary::within_times`] applies the
+/// windows exactly, but only to the flights a response lists: the outbound
+/// ones, or the return ones once the outbound flight is fixed (see
+/// `Config::listed_leg_times`).  Round-trip prices in an outbound listing can
+/// still include return flights outside the return windows.
+///
+/// [`TimeOfDay`]: super::TimeOfDay
+/// [`Itinerary::within_times`]: crate::parsers::flight_response::Itinerary::within_times
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct FlightTimes {
     departure_hour_min: Option<u32>,
     departure_hour_max: Option<u32>,
     arrival_hour_min: Option<u32>,
     arrival_hour_max: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    departure_windows: Vec<TimeWindow>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    arrival_windows: Vec<TimeWindow>,
 }
 
 impl FlightTimes {
@@ -115,9 +147,79 @@ impl FlightTimes {
             departure_hour_max: max_departure_hour,
             arrival_hour_min: min_hour_arrival,
             arrival_hour_max: max_hour_arrival,
+            departure_windows: Vec::new(),
+            arrival_windows: Vec::new(),
         }
     }
 
+    /// Adds a departure window.  Windows add up: a flight may leave in any
+    /// of them.  They replace the departure hours given to [`new`](Self::new).
+    pub fn depart_during(mut self, window: impl Into<TimeWindow>) -> Self {
+        self.departure_windows.push(window.into());
+        (self.departure_hour_min, self.departure_hour_max) = span_bounds(&self.departure_windows);
+        self
+    }
+
+    /// Adds an arrival window; see [`depart_during`](Self::depart_during).
+    pub fn arrive_during(mut self, window: impl Into<TimeWindow>) -> Self {
+        self.arrival_windows.push(window.into());
+        (self.arrival_hour_min, self.arrival_hour_max) = span_bounds(&self.arrival_windows);
+        self
+    }
+
+    pub fn departure_windows(&self) -> &[TimeWindow] {
+        &self.departure_windows
+    }
+
+    pub fn arrival_windows(&self) -> &[TimeWindow] {
+        &self.arrival_windows
+    }
+
+    /// Whether no departure or arrival time is restricted.
+    pub fn is_unrestricted(&self) -> bool {
+        [
+            self.departure_hour_min,
+            self.departure_hour_max,
+            self.arrival_hour_min,
+            self.arrival_hour_max,
+        ]
+        .iter()
+        .all(Option::is_none)
+            && self.departure_windows.is_empty()
+            && self.arrival_windows.is_empty()
+    }
+
+    /// Whether the hour range sent to Google (and carried by links) is
+    /// exactly the windows: at most one window per side, not across midnight.
+    pub fn fits_hour_range(&self) -> bool {
+        [&self.departure_windows, &self.arrival_windows]
+            .iter()
+            .all(|w| w.is_empty() || (w.len() == 1 && !w[0].wraps_midnight()))
+    }
+
+    /// Whether a flight leaving during `hour` (0–23) passes the filter:
+    /// inside one of the departure windows, or within the departure hours
+    /// when no window is set.
+    pub fn allows_departure(&self, hour: u32) -> bool {
+        allows(
+            &self.departure_windows,
+            self.departure_hour_min,
+            self.departure_hour_max,
+            hour,
+        )
+    }
+
+    /// Whether a flight landing during `hour` (0–23) passes the filter; see
+    /// [`allows_departure`](Self::allows_departure).
+    pub fn allows_arrival(&self, hour: u32) -> bool {
+        allows(
+            &self.arrival_windows,
+            self.arrival_hour_min,
+            self.arrival_hour_max,
+            hour,
+        )
+    }
+
     pub fn get_departure_hour_min(&self) -> Option<u32> {
         self.departure_hour_min
     }
@@ -135,6 +237,26 @@ impl FlightTimes {
     }
 }
 
+/// Server-side hour bounds for `windows`, dropping the ones Google treats as
+/// "no limit" the way [`FlightTimes::new`] does.
+fn span_bounds(windows: &[TimeWindow]) -> (Option<u32>, Option<u32>) {
+    match hour_span(windows) {
+        Some((first, last)) => (
+            Some(first).filter(|h| *h > 0),
+            Some(last).filter(|h| *h > 0),
+        ),
+        None => (None, None),
+    }
+}
+
+fn allows(windows: &[TimeWindow], min: Option<u32>, max: Option<u32>, hour: u32) -> bool {
+    if windows.is_empty() {
+        min.is_none_or(|m| hour >= m) && max.is_none_or(|m| hour <= m)
+    } else {
+        windows.iter().any(|w| w.contains(hour))
+    }
+}
+
 impl SerializeToWeb for FlightTimes {
     fn serialize_to_web(&self) -> Result<String> {
         if self.departure_hour_min.is_none()
@@ -278,4 +400,24 @@ mod tests {
         assert_eq!(ft.get_arrival_hour_min(), None);
         assert_eq!(ft.get_arrival_hour_max(), None);
     }
+
+    #[test]
+    fn flight_times_windows_set_the_span_and_check_exactly() {
+        use crate::parsers::common::TimeOfDay;
+
+        let ft = FlightTimes::new(6, 22, 0, 0)
+            .depart_during(TimeOfDay::EarlyMorning)
+            .depart_during(TimeOfDay::Afternoon);
+        assert_eq!(ft.serialize_to_web().unwrap(), "[5,16,0,23]");
+        assert!(ft.allows_departure(6) && ft.allows_departure(16));
+        assert!(!ft.allows_departure(9), "between the two windows");
+        assert!(ft.allows_arrival(3), "no arrival filter");
+
+        let red_eye = FlightTimes::default().arrive_during(TimeOfDay::RedEye);
+        assert_eq!(red_eye.serialize_to_web().unwrap(), "null");
+        assert!(red_eye.allows_arrival(1) && !red_eye.allows_arrival(12));
+
+        let hours = FlightTimes::new(8, 11, 0, 0);
+        assert!(hours.allows_departure(11) && !hours.allows_departure(12));
+    }
 }
@@ -1,19 +1,26 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
+use futures::stream::{BoxStream, Stream, StreamExt};
 use percent_encoding::{AsciiSet, CONTROLS};
 use serde::{Deserialize, Deserializer};
 
+use crate::error::{Error, JsonPathError};
+
 pub mod airline;
+pub mod diagnostic;
 pub mod duration;
 pub mod fixed_flights;
 pub mod location;
+pub mod time_window;
 pub mod travelers;
 pub mod types;
 
 pub use airline::{AirlineCode, AirlineFilter, Alliance};
+pub use diagnostic::{Diagnostic, Severity};
 pub use duration::{FlightTimes, StopoverDuration, TotalDuration};
 pub use fixed_flights::FixedFlights;
 pub use location::Location;
-pub use travelers::Travelers;
+pub use time_window::{TimeOfDay, TimeWindow};
+pub use travelers::{PassengerType, Travelers, TravelersBuilder};
 pub use types::{PlaceType, SortOrder, StopOptions, TravelClass};
 
 /// The set of characters that are percent-encoded in google flights requests.
@@ -99,11 +106,80 @@ pub(crate) fn get_idx<T: serde::de::DeserializeOwned>(
 /// numeric — so filtering all lines for that marker captures every frame
 /// regardless of its position, which a fixed stride (`skip(3).step_by(2)`)
 /// could silently miss.
-fn wrb_frames(body: &str) -> Vec<&str> {
-    body.lines()
-        .map(str::trim)
-        .filter(|line| line.starts_with(r#"[["wrb.fr""#))
-        .collect()
+pub(crate) fn wrb_frames(body: &str) -> Vec<&str> {
+    body.lines().filter_map(as_wrb_frame).collect()
+}
+
+/// Whether any `wrb.fr` frame in `body` is a backend error envelope: no
+/// payload, and an error block in the sixth slot.  Such a body still comes
+/// with a 200, so it must not be cached as if it were an answer.
+pub(crate) fn is_backend_error(body: &str) -> bool {
+    wrb_frames(body).into_iter().any(|line| {
+        serde_json::from_str::<Vec<Vec<serde_json::Value>>>(line).is_ok_and(|frames| {
+            frames.iter().any(|frame| {
+                frame.get(2).is_none_or(serde_json::Value::is_null)
+                    && frame.get(5).is_some_and(|e| !e.is_null())
+            })
+        })
+    })
+}
+
+fn as_wrb_frame(line: &str) -> Option<&str> {
+    let line = line.trim();
+    line.starts_with(r#"[["wrb.fr""#).then_some(line)
+}
+
+/// Streaming counterpart of [`wrb_frames`]: yields each `wrb.fr` frame as soon
+/// as its terminating newline (or the end of the body) has been read.
+///
+/// Chunks may split lines — and UTF-8 sequences — anywhere, so bytes are
+/// buffered until a full line is available.  A chunk error ends the stream
+/// after being yielded.
+pub(crate) fn stream_wrb_frames<E>(
+    chunks: BoxStream<'static, std::result::Result<Vec<u8>, E>>,
+) -> impl Stream<Item = std::result::Result<String, E>> + Send + 'static
+where
+    E: Send + 'static,
+{
+    struct Splitter<E> {
+        chunks: BoxStream<'static, std::result::Result<Vec<u8>, E>>,
+        buf: Vec<u8>,
+        done: bool,
+    }
+
+    fn frame(line: &[u8]) -> Option<String> {
+        as_wrb_frame(&String::from_utf8_lossy(line)).map(str::to_owned)
+    }
+
+    let state = Splitter {
+        chunks,
+        buf: Vec::new(),
+        done: false,
+    };
+    futures::stream::unfold(state, |mut st| async move {
+        loop {
+            if let Some(pos) = st.buf.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = st.buf.drain(..=pos).collect();
+                if let Some(frame) = frame(&line) {
+                    return Some((Ok(frame), st));
+                }
+                continue;
+            }
+            if st.done {
+                let rest = std::mem::take(&mut st.buf);
+                return frame(&rest).map(|frame| (Ok(frame), st));
+            }
+            match st.chunks.next().await {
+                Some(Ok(chunk)) => st.buf.extend_from_slice(&chunk),
+                Some(Err(e)) => {
+                    st.done = true;
+                    st.buf.clear();
+                    return Some((Err(e), st));
+                }
+                None => st.done = true,
+            }
+        }
+    })
 }
 
 pub(crate) fn decode_outer_object<T>(body: &str) -> Result<Vec<T>>
@@ -112,34 +188,43 @@ where
 {
     let lines = wrb_frames(body);
 
-    let results = lines
-        .iter()
-        .map(|f| {
-            let jd: &mut serde_json::Deserializer<serde_json::de::StrRead<'_>> =
-                &mut serde_json::Deserializer::from_str(f);
-            let result: Result<T, _> = serde_path_to_error::deserialize(jd);
-            match result {
-                Ok(x) => {
-                    let test = x.get_error_messages();
-                    match test {
-                        Some(err) => {
-                            let err_messages_joined = err.join("\n");
-                            tracing::error!(errors = ?err, "Error in processing outer object: errors returned from the backend");
-                            Err(anyhow!(err_messages_joined))
-                        }
-                        None => Ok(x),
-                    }
-                }
-                Err(err) => {
-                    let path = err.path().to_string();
-                    tracing::error!(path = %path, error = ?err, "Error deserializing outer object");
-                    Err(anyhow!(err))
+    let mut decoded = Vec::with_capacity(lines.len());
+    let mut backend_messages: Vec<String> = Vec::new();
+    let mut parse_err: Option<JsonPathError> = None;
+    for line in lines {
+        let jd: &mut serde_json::Deserializer<serde_json::de::StrRead<'_>> =
+            &mut serde_json::Deserializer::from_str(line);
+        let result: Result<T, _> = serde_path_to_error::deserialize(jd);
+        match result {
+            Ok(x) => match x.get_error_messages() {
+                Some(err) => {
+                    tracing::error!(errors = ?err, "Error in processing outer object: errors returned from the backend");
+                    backend_messages.extend(err);
                 }
+                None => decoded.push(x),
+            },
+            Err(err) => {
+                let path = err.path().to_string();
+                tracing::error!(path = %path, error = ?err, "Error deserializing outer object");
+                parse_err.get_or_insert_with(|| err.into());
             }
-        })
-        .filter(|f| f.is_ok())
-        .collect();
-    results
+        }
+    }
+
+    // Bad frames are skipped as long as at least one frame decodes; when none
+    // does, surface why (backend errors first, then the first parse error).
+    if decoded.is_empty() {
+        if !backend_messages.is_empty() {
+            return Err(Error::Backend {
+                messages: backend_messages,
+            }
+            .into());
+        }
+        if let Some(err) = parse_err {
+            return Err(err.into());
+        }
+    }
+    Ok(decoded)
 }
 
 /// Decode the inner object.
@@ -159,7 +244,7 @@ pub(crate) fn decode_inner_object<T: for<'a> Deserialize<'a>>(body: &str) -> Res
         Err(err) => {
             let path = err.path().to_string();
             tracing::error!(path = %path, error = ?err, "Error deserializing inner object");
-            Err(anyhow!(err))
+            Err(JsonPathError::from(err).into())
         }
     }
 }
@@ -269,8 +354,9 @@ where
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod frame_tests {
-    use super::wrb_frames;
+    use super::{is_backend_error, wrb_frames};
 
     #[test]
     fn collects_all_frames_at_irregular_positions() {
@@ -300,4 +386,47 @@ mod frame_tests {
         let body = ")]}'\n5\n[[\"wrb.fr\",\"x\",\"[]\"]]\n[\"di\",12]\n[\"e\",4,null,5]\n";
         assert_eq!(wrb_frames(body).len(), 1);
     }
+    #[tokio::test]
+    async fn streamed_frames_survive_arbitrary_chunk_boundaries() {
+        use futures::StreamExt;
+
+        let body = ")]}'\n\n26\n[[\"wrb.fr\",\"é\",\"[]\"]]\n31\n[[\"wrb.fr\",\"b\",\"[]\"]]";
+        let bytes = body.as_bytes();
+        for size in [1, 3, 7, bytes.len()] {
+            let chunks: Vec<Result<Vec<u8>, ()>> =
+                bytes.chunks(size).map(|c| Ok(c.to_vec())).collect();
+            let frames: Vec<String> =
+                super::stream_wrb_frames(futures::stream::iter(chunks).boxed())
+                    .map(|f| f.unwrap())
+                    .collect()
+                    .await;
+            assert_eq!(frames, wrb_frames(body), "chunk size {size}");
+        }
+    }
+
+    #[tokio::test]
+    async fn streamed_frames_stop_after_a_chunk_error() {
+        use futures::StreamExt;
+
+        let chunks = vec![
+            Ok(b"[[\"wrb.fr\",\"a\"]]\n".to_vec()),
+            Err("reset"),
+            Ok(b"[[\"wrb.fr\",\"b\"]]\n".to_vec()),
+        ];
+        let items: Vec<_> = super::stream_wrb_frames(futures::stream::iter(chunks).boxed())
+            .collect()
+            .await;
+        assert_eq!(items.len(), 2);
+        assert!(items[0].is_ok());
+        assert_eq!(items[1], Err("reset"));
+    }
+
+    #[test]
+    fn backend_error_envelopes_are_told_apart_from_answers() {
+        let error = r#"[["wrb.fr",null,null,null,null,[3,null,[["Invalid request",null]]]]]"#;
+        let answer = r#"[["wrb.fr","H028ib","[[]]",null,null,null,"generic"]]"#;
+        assert!(is_backend_error(&format!(")]}}'\n\n12\n{error}\n")));
+        assert!(!is_backend_error(answer));
+        assert!(!is_backend_error(""));
+    }
 }
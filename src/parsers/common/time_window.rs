@@ -0,0 +1,293 @@
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// Named part of the day, for departure and arrival filters.
+///
+/// | Preset | Hours |
+/// |---|---|
+/// | `EarlyMorning` | 05:00–08:00 |
+/// | `Morning` | 08:00–12:00 |
+/// | `Afternoon` | 12:00–17:00 |
+/// | `Evening` | 17:00–21:00 |
+/// | `RedEye` | 21:00–05:00, across midnight |
+///
+/// The presets cover the whole day without overlapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeOfDay {
+    EarlyMorning,
+    Morning,
+    Afternoon,
+    Evening,
+    /// Overnight flights.
+    RedEye,
+}
+
+impl TimeOfDay {
+    pub const ALL: [TimeOfDay; 5] = [
+        TimeOfDay::EarlyMorning,
+        TimeOfDay::Morning,
+        TimeOfDay::Afternoon,
+        TimeOfDay::Evening,
+        TimeOfDay::RedEye,
+    ];
+
+    /// The hours this preset covers.
+    pub fn window(self) -> TimeWindow {
+        let (start_hour, end_hour) = match self {
+            TimeOfDay::EarlyMorning => (5, 8),
+            TimeOfDay::Morning => (8, 12),
+            TimeOfDay::Afternoon => (12, 17),
+            TimeOfDay::Evening => (17, 21),
+            TimeOfDay::RedEye => (21, 5),
+        };
+        TimeWindow {
+            start_hour,
+            end_hour,
+        }
+    }
+
+    /// From midnight until this preset ends, e.g. "back by the evening" is
+    /// `TimeOfDay::Evening.by()`, 00:00–21:00.
+    pub fn by(self) -> TimeWindow {
+        TimeWindow::before(self.window().end_hour)
+    }
+
+    /// The name used by [`FromStr`](std::str::FromStr) and [`Display`](std::fmt::Display).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TimeOfDay::EarlyMorning => "early-morning",
+            TimeOfDay::Morning => "morning",
+            TimeOfDay::Afternoon => "afternoon",
+            TimeOfDay::Evening => "evening",
+            TimeOfDay::RedEye => "red-eye",
+        }
+    }
+}
+
+impl std::str::FromStr for TimeOfDay {
+    type Err = anyhow::Error;
+
+    /// Accepts the [`as_str`](Self::as_str) names, ignoring case, with `_`
+    /// or `-`; `overnight` and `redeye` also mean [`TimeOfDay::RedEye`].
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "early-morning" => Ok(TimeOfDay::EarlyMorning),
+            "morning" => Ok(TimeOfDay::Morning),
+            "afternoon" => Ok(TimeOfDay::Afternoon),
+            "evening" => Ok(TimeOfDay::Evening),
+            "red-eye" | "redeye" | "overnight" => Ok(TimeOfDay::RedEye),
+            _ => Err(anyhow!(
+                "unknown time of day {s:?}; expected early-morning, morning, afternoon, evening or red-eye"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A range of whole hours in the local time of the airport, from
+/// `start_hour` up to but not including `end_hour`.  A window whose start is
+/// after its end runs across midnight.
+///
+/// `TimeWindow` implements [`std::str::FromStr`], accepting a
+/// [`TimeOfDay`] name (`"morning"`), `"by-<name>"` for
+/// [`TimeOfDay::by`], or an hour range `"6-10"`.  Deserialising goes
+/// through [`TimeWindow::new`], so a saved window is checked like a new one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "RawTimeWindow")]
+pub struct TimeWindow {
+    start_hour: u32,
+    end_hour: u32,
+}
+
+/// The serialised form of a [`TimeWindow`], before its hours are checked.
+#[derive(Deserialize)]
+struct RawTimeWindow {
+    start_hour: u32,
+    end_hour: u32,
+}
+
+impl TryFrom<RawTimeWindow> for TimeWindow {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: RawTimeWindow) -> Result<Self> {
+        Self::new(raw.start_hour, raw.end_hour)
+    }
+}
+
+impl TimeWindow {
+    /// From `start_hour` until `end_hour` (exclusive, up to 24).
+    ///
+    /// # Errors
+    /// Returns an error if an hour is out of range or the two are equal.
+    pub fn new(start_hour: u32, end_hour: u32) -> Result<Self> {
+        if start_hour > 23 || end_hour > 24 {
+            bail!("time window {start_hour}-{end_hour} is outside 0-24");
+        }
+        if start_hour == end_hour % 24 {
+            bail!("time window {start_hour}-{end_hour} is empty or the whole day");
+        }
+        Ok(Self {
+            start_hour,
+            end_hour,
+        })
+    }
+
+    /// From midnight until `hour` (clamped to 1–23, so the window never
+    /// covers the whole day).
+    pub fn before(hour: u32) -> Self {
+        Self {
+            start_hour: 0,
+            end_hour: hour.clamp(1, 23),
+        }
+    }
+
+    /// From `hour` (clamped to 1–23) until midnight.
+    pub fn after(hour: u32) -> Self {
+        Self {
+            start_hour: hour.clamp(1, 23),
+            end_hour: 24,
+        }
+    }
+
+    pub fn start_hour(&self) -> u32 {
+        self.start_hour
+    }
+
+    pub fn end_hour(&self) -> u32 {
+        self.end_hour
+    }
+
+    /// Whether the window runs across midnight.
+    pub fn wraps_midnight(&self) -> bool {
+        self.start_hour > self.end_hour
+    }
+
+    /// Whether a time starting in `hour` (0–23) falls inside the window.
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.wraps_midnight() {
+            hour >= self.start_hour || hour < self.end_hour
+        } else {
+            (self.start_hour..self.end_hour).contains(&hour)
+        }
+    }
+}
+
+impl From<TimeOfDay> for TimeWindow {
+    fn from(preset: TimeOfDay) -> Self {
+        preset.window()
+    }
+}
+
+impl std::str::FromStr for TimeWindow {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Some(preset) = s.strip_prefix("by-") {
+            return Ok(preset.parse::<TimeOfDay>()?.by());
+        }
+        if let Some((start, end)) = s.split_once('-') {
+            if let (Ok(start), Ok(end)) = (start.trim().parse(), end.trim().parse()) {
+                return Self::new(start, end);
+            }
+        }
+        Ok(s.parse::<TimeOfDay>()?.window())
+    }
+}
+
+impl std::fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:00-{:02}:00", self.start_hour, self.end_hour)
+    }
+}
+
+/// The smallest `[first, last]` hour range containing every window, for the
+/// server-side filter, or `None` when the windows run across midnight and no
+/// single range fits.
+pub(crate) fn hour_span(windows: &[TimeWindow]) -> Option<(u32, u32)> {
+    if windows.iter().any(TimeWindow::wraps_midnight) {
+        return None;
+    }
+    let first = windows.iter().map(|w| w.start_hour).min()?;
+    let last = windows.iter().map(|w| w.end_hour - 1).max()?;
+    Some((first, last))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_cover_the_day_once() {
+        for hour in 0..24 {
+            let matching: Vec<_> = TimeOfDay::ALL
+                .into_iter()
+                .filter(|p| p.window().contains(hour))
+                .collect();
+            assert_eq!(matching.len(), 1, "{hour}:00 is in {matching:?}");
+        }
+        assert!(TimeOfDay::RedEye.window().contains(23));
+        assert!(TimeOfDay::RedEye.window().contains(2));
+        assert!(!TimeOfDay::RedEye.window().contains(5));
+    }
+
+    #[test]
+    fn windows_parse_from_names_ranges_and_deadlines() {
+        assert_eq!(
+            "Morning".parse::<TimeWindow>().unwrap(),
+            TimeOfDay::Morning.window()
+        );
+        assert_eq!(
+            "overnight".parse::<TimeWindow>().unwrap(),
+            TimeOfDay::RedEye.window()
+        );
+        assert_eq!(
+            "early_morning".parse::<TimeOfDay>().unwrap(),
+            TimeOfDay::EarlyMorning
+        );
+        assert_eq!(
+            "6-10".parse::<TimeWindow>().unwrap(),
+            TimeWindow::new(6, 10).unwrap()
+        );
+        assert_eq!(
+            "by-evening".parse::<TimeWindow>().unwrap(),
+            TimeWindow::before(21)
+        );
+        assert!("lunch".parse::<TimeWindow>().is_err());
+        assert!("7-7".parse::<TimeWindow>().is_err());
+        assert!("20-25".parse::<TimeWindow>().is_err());
+    }
+
+    #[test]
+    fn deserialising_checks_the_hours() {
+        let window: TimeWindow = serde_json::from_str(r#"{"start_hour":6,"end_hour":10}"#).unwrap();
+        assert_eq!(window, TimeWindow::new(6, 10).unwrap());
+        for bad in [
+            r#"{"start_hour":0,"end_hour":0}"#,
+            r#"{"start_hour":0,"end_hour":24}"#,
+            r#"{"start_hour":20,"end_hour":25}"#,
+        ] {
+            assert!(serde_json::from_str::<TimeWindow>(bad).is_err(), "{bad}");
+        }
+        for window in [TimeWindow::before(24), TimeWindow::after(0)] {
+            let json = serde_json::to_string(&window).unwrap();
+            assert_eq!(serde_json::from_str::<TimeWindow>(&json).unwrap(), window);
+        }
+    }
+
+    #[test]
+    fn hour_span_covers_windows_unless_one_wraps() {
+        let windows = [TimeOfDay::Morning.window(), TimeOfDay::Evening.window()];
+        assert_eq!(hour_span(&windows), Some((8, 20)));
+        assert_eq!(hour_span(&[TimeWindow::after(18)]), Some((18, 23)));
+        assert_eq!(hour_span(&[TimeOfDay::RedEye.window()]), None);
+        assert_eq!(hour_span(&[]), None);
+    }
+}
@@ -1,9 +1,27 @@
 use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
+use super::diagnostic::{self, Diagnostic, Severity};
 use super::SerializeToWeb;
 
+/// Youngest age priced as a child rather than an infant.
+pub const CHILD_MIN_AGE: u32 = 2;
+
+/// Youngest age priced as an adult.
+pub const ADULT_MIN_AGE: u32 = 12;
+
+/// Youngest age airlines accept as the adult holding a lap infant.
+pub const LAP_HOLDER_MIN_AGE: u32 = 16;
+
+/// Maximum number of passengers in one search.
+const MAX_PASSENGERS: i32 = 9;
+
 /// Travelers. It contains the number of adults, children, infants on lap and infants in seat.
+///
+/// Build it from counts with [`Travelers::new`], or from ages and birth dates
+/// with [`Travelers::builder`], which also applies the airlines' rules for
+/// infants and minors.
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Travelers {
     pub adults: i32,
@@ -60,6 +78,41 @@ impl Travelers {
         })
     }
 
+    /// Starts a [`TravelersBuilder`].
+    ///
+    /// ```rust
+    /// use chrono::NaiveDate;
+    /// use gflights::parsers::common::Travelers;
+    ///
+    /// let flying = NaiveDate::from_ymd_opt(2030, 7, 1).unwrap();
+    /// let family = Travelers::builder()
+    ///     .adults(2)
+    ///     .aged(9)
+    ///     .lap_infant_born(NaiveDate::from_ymd_opt(2029, 11, 3).unwrap())
+    ///     .travel_date(flying)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!((family.adults, family.children, family.infant_on_lap), (2, 1, 1));
+    /// ```
+    pub fn builder() -> TravelersBuilder {
+        TravelersBuilder::default()
+    }
+
+    /// Number of passengers of type `passenger_type`.
+    pub fn count(&self, passenger_type: PassengerType) -> i32 {
+        match passenger_type {
+            PassengerType::Adult => self.adults,
+            PassengerType::Child => self.children,
+            PassengerType::InfantInSeat => self.infant_in_seat,
+            PassengerType::InfantOnLap => self.infant_on_lap,
+        }
+    }
+
+    /// Total number of passengers.
+    pub fn total(&self) -> i32 {
+        self.adults + self.children + self.infant_on_lap + self.infant_in_seat
+    }
+
     /// Conversion to a vector of i32, used in protobuf generation.
     /// It returns a vector of 1, 2, 3, 4 repeated the number of times of the corresponding field.
     pub fn to_proto_vec(&self) -> Vec<i32> {
@@ -82,6 +135,294 @@ impl SerializeToWeb for Travelers {
     }
 }
 
+/// The fare category a passenger is priced under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PassengerType {
+    /// [`ADULT_MIN_AGE`] or older.
+    Adult,
+    /// From [`CHILD_MIN_AGE`] up to [`ADULT_MIN_AGE`].
+    Child,
+    /// Under [`CHILD_MIN_AGE`], in their own seat.
+    InfantInSeat,
+    /// Under [`CHILD_MIN_AGE`], on an adult's lap.
+    InfantOnLap,
+}
+
+impl PassengerType {
+    pub const ALL: [PassengerType; 4] = [
+        PassengerType::Adult,
+        PassengerType::Child,
+        PassengerType::InfantInSeat,
+        PassengerType::InfantOnLap,
+    ];
+
+    /// The category for someone `age` years old on the day they fly.
+    /// Infants get a seat unless `on_lap` is set.
+    pub fn from_age(age: u32, on_lap: bool) -> Self {
+        match age {
+            a if a >= ADULT_MIN_AGE => PassengerType::Adult,
+            a if a >= CHILD_MIN_AGE => PassengerType::Child,
+            _ if on_lap => PassengerType::InfantOnLap,
+            _ => PassengerType::InfantInSeat,
+        }
+    }
+
+    /// The category for an ITA passenger type code such as `"ADT"`, as found
+    /// in booking responses.  Child codes carrying an age (`"C05"`) count as
+    /// children.
+    pub fn from_fare_code(code: &str) -> Option<Self> {
+        match code {
+            "ADT" => Some(PassengerType::Adult),
+            "CNN" | "CHD" => Some(PassengerType::Child),
+            "INF" => Some(PassengerType::InfantOnLap),
+            "INS" => Some(PassengerType::InfantInSeat),
+            c if c.len() == 3
+                && c.starts_with('C')
+                && c[1..].bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                Some(PassengerType::Child)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A passenger added to a [`TravelersBuilder`].
+#[derive(Debug, Clone, Copy)]
+enum Passenger {
+    /// Added by category, age unstated.
+    Typed(PassengerType),
+    /// Age in years on the travel date.
+    Aged {
+        age: u32,
+        on_lap: bool,
+    },
+    Born {
+        date: NaiveDate,
+        on_lap: bool,
+    },
+}
+
+/// Builds [`Travelers`] from passengers given by category, age or birth
+/// date.  Created by [`Travelers::builder`].
+///
+/// Ages decide the fare category: under [`CHILD_MIN_AGE`] is an infant,
+/// under [`ADULT_MIN_AGE`] a child, anyone older an adult.  Birth dates are
+/// measured on the [`travel_date`](Self::travel_date); for a round trip pass
+/// the return date, since an infant who turns two before the flight home
+/// needs a seat for the whole trip.
+///
+/// [`build`](Self::build) refuses parties that Google Flights or the airlines
+/// would not sell: no passenger aged 12 or over (minors travelling alone),
+/// more lap infants than adults, a lap infant with nobody aged
+/// [`LAP_HOLDER_MIN_AGE`] or over, or more than nine passengers.
+#[derive(Debug, Clone, Default)]
+pub struct TravelersBuilder {
+    passengers: Vec<Passenger>,
+    travel_date: Option<NaiveDate>,
+}
+
+impl TravelersBuilder {
+    fn push(mut self, count: u32, passenger: Passenger) -> Self {
+        self.passengers
+            .extend(std::iter::repeat_n(passenger, count as usize));
+        self
+    }
+
+    /// Adds `count` adults of unstated age.
+    pub fn adults(self, count: u32) -> Self {
+        self.push(count, Passenger::Typed(PassengerType::Adult))
+    }
+
+    /// Adds `count` children of unstated age.
+    pub fn children(self, count: u32) -> Self {
+        self.push(count, Passenger::Typed(PassengerType::Child))
+    }
+
+    /// Adds `count` infants in their own seat, of unstated age.
+    pub fn infants_in_seat(self, count: u32) -> Self {
+        self.push(count, Passenger::Typed(PassengerType::InfantInSeat))
+    }
+
+    /// Adds `count` lap infants of unstated age.
+    pub fn infants_on_lap(self, count: u32) -> Self {
+        self.push(count, Passenger::Typed(PassengerType::InfantOnLap))
+    }
+
+    /// Adds a passenger who is `age` years old on the travel date.  An infant
+    /// gets their own seat; use [`lap_infant_aged`](Self::lap_infant_aged)
+    /// to hold them on a lap.
+    pub fn aged(self, age: u32) -> Self {
+        self.push(1, Passenger::Aged { age, on_lap: false })
+    }
+
+    /// Adds a passenger born on `date`, categorised by their age on the
+    /// travel date.
+    pub fn born(self, date: NaiveDate) -> Self {
+        self.push(
+            1,
+            Passenger::Born {
+                date,
+                on_lap: false,
+            },
+        )
+    }
+
+    /// Adds a lap infant who is `age` years old (0 or 1) on the travel date.
+    pub fn lap_infant_aged(self, age: u32) -> Self {
+        self.push(1, Passenger::Aged { age, on_lap: true })
+    }
+
+    /// Adds a lap infant born on `date`.
+    pub fn lap_infant_born(self, date: NaiveDate) -> Self {
+        self.push(1, Passenger::Born { date, on_lap: true })
+    }
+
+    /// The date ages are measured on.  Required when any passenger was added
+    /// by birth date.
+    pub fn travel_date(mut self, date: NaiveDate) -> Self {
+        self.travel_date = Some(date);
+        self
+    }
+
+    /// Categorises every passenger and checks the party.
+    ///
+    /// # Errors
+    /// [`Error::Validation`](crate::Error::Validation) listing every problem,
+    /// with fields such as `"travellers.passengers[2]"` or
+    /// `"travellers.infant_on_lap"`.
+    pub fn build(self) -> crate::Result<Travelers> {
+        let mut diagnostics = Vec::new();
+        let mut error = |field: String, message: String| {
+            diagnostics.push(Diagnostic {
+                field,
+                severity: Severity::Error,
+                message,
+            })
+        };
+        let mut travelers = Travelers {
+            adults: 0,
+            children: 0,
+            infant_on_lap: 0,
+            infant_in_seat: 0,
+        };
+        // Each passenger's category and age, `None` where unstated.
+        let mut categorised = Vec::new();
+        for (i, p) in self.passengers.iter().enumerate() {
+            let field = format!("travellers.passengers[{i}]");
+            let (age, on_lap) = match (*p, self.travel_date) {
+                (Passenger::Typed(t), _) => {
+                    categorised.push((t, None));
+                    continue;
+                }
+                (Passenger::Aged { age, on_lap }, _) => (age, on_lap),
+                (Passenger::Born { date: born, on_lap }, Some(date)) => {
+                    match date.years_since(born) {
+                        Some(years) => (years, on_lap),
+                        None => {
+                            error(field, format!("born {born}, after the travel date {date}"));
+                            continue;
+                        }
+                    }
+                }
+                (Passenger::Born { .. }, None) => {
+                    error(
+                        field,
+                        "a birth date needs a travel date to measure the age on".into(),
+                    );
+                    continue;
+                }
+            };
+            let passenger_type = PassengerType::from_age(age, on_lap);
+            if on_lap && passenger_type != PassengerType::InfantOnLap {
+                error(
+                    field,
+                    format!(
+                        "lap infants must be under {CHILD_MIN_AGE}; a passenger aged {age} needs a seat"
+                    ),
+                );
+                continue;
+            }
+            categorised.push((passenger_type, Some(age)));
+        }
+        let mut adult_ages = Vec::new();
+        for (passenger_type, age) in categorised {
+            match passenger_type {
+                PassengerType::Adult => {
+                    travelers.adults += 1;
+                    adult_ages.push(age);
+                }
+                PassengerType::Child => travelers.children += 1,
+                PassengerType::InfantInSeat => travelers.infant_in_seat += 1,
+                PassengerType::InfantOnLap => travelers.infant_on_lap += 1,
+            }
+        }
+        if self.passengers.is_empty() {
+            error("travellers".into(), "no passengers were added".into());
+        }
+        let holder = |age: &Option<u32>| age.is_none_or(|a| a >= LAP_HOLDER_MIN_AGE);
+        if travelers.infant_on_lap > 0 && !adult_ages.is_empty() && !adult_ages.iter().any(holder) {
+            error(
+                "travellers.infant_on_lap".into(),
+                format!(
+                    "lap infants must travel with a passenger aged {LAP_HOLDER_MIN_AGE} or over"
+                ),
+            );
+        }
+        if !self.passengers.is_empty() {
+            diagnostics.extend(check_travellers(&travelers));
+        }
+        diagnostic::into_result(diagnostics).map(|_| travelers)
+    }
+}
+
+/// The passenger-count checks shared by [`TravelersBuilder::build`] and
+/// search validation.
+pub(crate) fn check_travellers(travellers: &Travelers) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut error = |field: String, message: String| {
+        diagnostics.push(Diagnostic {
+            field,
+            severity: Severity::Error,
+            message,
+        })
+    };
+    for (name, count) in [
+        ("adults", travellers.adults),
+        ("children", travellers.children),
+        ("infant_on_lap", travellers.infant_on_lap),
+        ("infant_in_seat", travellers.infant_in_seat),
+    ] {
+        if count < 0 {
+            error(format!("travellers.{name}"), format!("{count} is negative"));
+        }
+    }
+    if travellers.adults < 1 {
+        error(
+            "travellers.adults".into(),
+            "at least one adult (12 or over) is required; children cannot fly unaccompanied on a Google Flights booking".into(),
+        );
+    }
+    let total = travellers.total();
+    if total > MAX_PASSENGERS {
+        error(
+            "travellers".into(),
+            format!("{total} passengers; at most {MAX_PASSENGERS} can travel together"),
+        );
+    }
+    if travellers.infant_on_lap > travellers.adults.max(0) {
+        error(
+            "travellers.infant_on_lap".into(),
+            format!(
+                "{} lap infants but {} adults; each lap infant needs its own adult",
+                travellers.infant_on_lap, travellers.adults
+            ),
+        );
+    }
+    diagnostics
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -135,4 +476,100 @@ mod tests {
         let t = Travelers::new(vec![2, 1, 0, 0]).unwrap();
         assert_eq!(t.serialize_to_web().unwrap(), "[2,1,0,0]");
     }
+
+    fn on(month: u32, day: u32, year: i32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn fields(err: crate::Error) -> Vec<String> {
+        match err {
+            crate::Error::Validation(d) => d.into_iter().map(|d| d.field).collect(),
+            other => panic!("expected a validation error, got {other}"),
+        }
+    }
+
+    #[test]
+    fn builder_derives_categories_from_ages_and_birth_dates() {
+        let t = Travelers::builder()
+            .aged(40)
+            .aged(12)
+            .aged(11)
+            .aged(1)
+            .lap_infant_aged(0)
+            // Turns two three days before flying: a child, not an infant.
+            .born(on(6, 28, 2028))
+            .travel_date(on(7, 1, 2030))
+            .build()
+            .unwrap();
+        assert_eq!(
+            (t.adults, t.children, t.infant_in_seat, t.infant_on_lap),
+            (2, 2, 1, 1)
+        );
+        assert_eq!(PassengerType::from_age(1, true), PassengerType::InfantOnLap);
+        assert_eq!(PassengerType::from_age(2, true), PassengerType::Child);
+    }
+
+    #[test]
+    fn fare_codes_map_to_passenger_types() {
+        for (code, expected) in [
+            ("ADT", Some(PassengerType::Adult)),
+            ("CNN", Some(PassengerType::Child)),
+            ("C07", Some(PassengerType::Child)),
+            ("INF", Some(PassengerType::InfantOnLap)),
+            ("INS", Some(PassengerType::InfantInSeat)),
+            ("CXX", None),
+            ("SRC", None),
+        ] {
+            assert_eq!(PassengerType::from_fare_code(code), expected, "{code}");
+        }
+    }
+
+    #[test]
+    fn builder_rejects_parties_airlines_would_not_sell() {
+        let flying = on(7, 1, 2030);
+
+        // Children alone.
+        let err = Travelers::builder().aged(10).aged(8).build().unwrap_err();
+        assert_eq!(fields(err), ["travellers.adults"]);
+
+        // Two lap infants for one adult, and a toddler too old for a lap.
+        let err = Travelers::builder()
+            .adults(1)
+            .infants_on_lap(2)
+            .lap_infant_born(on(1, 15, 2028))
+            .travel_date(flying)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            fields(err),
+            ["travellers.passengers[3]", "travellers.infant_on_lap"]
+        );
+
+        // A 14-year-old cannot hold a lap infant.
+        let err = Travelers::builder()
+            .aged(14)
+            .lap_infant_aged(0)
+            .build()
+            .unwrap_err();
+        assert_eq!(fields(err), ["travellers.infant_on_lap"]);
+
+        // Birth dates need a travel date and must precede it.
+        let err = Travelers::builder()
+            .adults(1)
+            .born(on(1, 1, 2020))
+            .born(on(1, 1, 2031))
+            .build()
+            .unwrap_err();
+        assert_eq!(fields(err).len(), 2);
+        let err = Travelers::builder()
+            .adults(1)
+            .born(on(1, 1, 2031))
+            .travel_date(flying)
+            .build()
+            .unwrap_err();
+        assert_eq!(fields(err), ["travellers.passengers[1]"]);
+
+        assert!(Travelers::builder().build().is_err());
+        assert!(Travelers::builder().adults(6).children(4).build().is_err());
+    }
 }
@@ -1,5 +1,4 @@
 use anyhow::Result;
-use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
@@ -44,7 +43,8 @@ impl From<i32> for PlaceType {
 }
 
 /// Travel class. It can be economy, premium economy, business or first class.
-#[derive(Debug, Deserialize, Serialize, Clone, Copy, ValueEnum, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 pub enum TravelClass {
     #[default]
     Economy = 1,
@@ -78,7 +78,8 @@ impl From<i32> for TravelClass {
 }
 
 /// Sort order for flight search results.
-#[derive(Debug, Deserialize, Serialize, Clone, Copy, ValueEnum, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 pub enum SortOrder {
     /// Google's default: best combination of price, duration, and convenience.
     #[default]
@@ -108,7 +109,8 @@ impl SortOrder {
 }
 
 /// Stop options. It can be all, no stop, one or less, two or less.
-#[derive(Debug, Deserialize, Serialize, Clone, Copy, ValueEnum, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 pub enum StopOptions {
     #[default]
     All = 0,
@@ -24,12 +24,14 @@ pub mod response;
 
 // Re-export sub-modules at the old flat path so external crates that already
 // use `gflights::parsers::flight_response::…` continue to compile unchanged.
+pub use request::batch_request;
 pub use request::calendar_graph_request;
 pub use request::city_request;
 pub use request::date_grid_request;
 pub use request::deals_request;
 pub use request::explore_request;
 pub use request::flight_request;
+pub use response::batch_response;
 pub use response::calendar_graph_response;
 pub use response::city_response;
 pub use response::date_grid_response;
@@ -0,0 +1,164 @@
+//! `batchexecute` request body: several independent RPCs packed into one POST.
+//!
+//! Each call becomes one `[rpc_id, payload, null, tag]` entry of `f.req`, and
+//! the URL lists every rpc id in `rpcids=`.  The backend echoes the tag in the
+//! matching `wrb.fr` frame, which is how
+//! [`demux_batch_frames`](crate::parsers::response::batch_response::demux_batch_frames)
+//! pairs answers with calls.
+
+use percent_encoding::utf8_percent_encode;
+use serde_json::{json, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::parsers::common::{RequestBody, ToRequestBody, CHARACTERS_TO_ENCODE};
+use crate::parsers::constants::BATCHEXECUTE;
+use crate::requests::transport::decoded_f_req;
+use anyhow::{bail, Context, Result};
+
+/// rpc id of the city / airport lookup.
+pub const CITY_RPC_ID: &str = "H028ib";
+
+/// One RPC inside a `batchexecute` request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchCall {
+    pub rpc_id: String,
+    /// The RPC's argument as JSON text; sent as a string inside `f.req`.
+    pub payload: String,
+}
+
+impl BatchCall {
+    /// City / airport lookup for `city` (same payload as
+    /// [`CityRequestOptions`](super::city_request::CityRequestOptions)).
+    pub fn city(city: &str) -> Self {
+        Self {
+            rpc_id: CITY_RPC_ID.to_owned(),
+            payload: json!([city, [1, 2, 3, 5, 4], null, [1, 1, 1], 1]).to_string(),
+        }
+    }
+
+    /// The call `rpc_id` carrying the payload of a dedicated
+    /// `FlightsFrontendService` request, whose `f.req` is `[null, payload]`.
+    pub fn from_request(rpc_id: impl Into<String>, request: &RequestBody) -> Result<Self> {
+        let f_req = decoded_f_req(&request.body).context("request has no f.req")?;
+        let outer: Value = serde_json::from_str(&f_req).context("f.req is not JSON")?;
+        let payload = outer
+            .get(1)
+            .and_then(Value::as_str)
+            .context("f.req has no payload string")?;
+        Ok(Self {
+            rpc_id: rpc_id.into(),
+            payload: payload.to_owned(),
+        })
+    }
+}
+
+/// Tag sent with call `index` of a `len`-call batch and echoed back in its
+/// `wrb.fr` frame: `"generic"` for a lone call, `"1"`, `"2"`, … otherwise.
+pub fn batch_tag(index: usize, len: usize) -> String {
+    if len == 1 {
+        "generic".to_owned()
+    } else {
+        (index + 1).to_string()
+    }
+}
+
+#[derive(Debug)]
+pub struct BatchRequestOptions<'a> {
+    pub calls: &'a [BatchCall],
+    pub frontend_version: String,
+}
+
+impl ToRequestBody for BatchRequestOptions<'_> {
+    fn to_request_body(&self) -> Result<RequestBody> {
+        self.try_into()
+    }
+}
+
+impl TryFrom<&BatchRequestOptions<'_>> for RequestBody {
+    type Error = anyhow::Error;
+    fn try_from(options: &BatchRequestOptions<'_>) -> Result<Self> {
+        if options.calls.is_empty() {
+            bail!("a batch needs at least one call");
+        }
+        let epoch_now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+
+        let len = options.calls.len();
+        let entries: Vec<Value> = options
+            .calls
+            .iter()
+            .enumerate()
+            .map(|(i, call)| json!([call.rpc_id, call.payload, null, batch_tag(i, len)]))
+            .collect();
+        let body = format!(
+            "f.req={}&at=AAuQa1qqZgn5F209lkOLZp20vq5d:{epoch_now}&",
+            json!([entries])
+        );
+
+        let mut rpc_ids: Vec<&str> = Vec::with_capacity(len);
+        for call in options.calls {
+            if !rpc_ids.contains(&call.rpc_id.as_str()) {
+                rpc_ids.push(&call.rpc_id);
+            }
+        }
+        let url = format!("{BATCHEXECUTE}?rpcids={}&source-path=/travel/flights&f.sid=-2414068248310847860&bl={}&hl=en-GB&soc-app=162&soc-platform=1&soc-device=1&_reqid=581503&rt=c", rpc_ids.join(","), options.frontend_version);
+        Ok(Self {
+            url,
+            body: utf8_percent_encode(&body, CHARACTERS_TO_ENCODE).to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::parsers::city_request::CityRequestOptions;
+
+    fn options(calls: &[BatchCall]) -> BatchRequestOptions<'_> {
+        BatchRequestOptions {
+            calls,
+            frontend_version: "boq_travel-frontend-ui_20240110.02_p0".to_owned(),
+        }
+    }
+
+    #[test]
+    fn single_city_call_matches_dedicated_city_body() -> Result<()> {
+        let calls = [BatchCall::city("london")];
+        let batch: RequestBody = (&options(&calls)).try_into()?;
+        let city: RequestBody = (&CityRequestOptions {
+            city: "london".to_owned(),
+            frontend_version: "boq_travel-frontend-ui_20240110.02_p0".to_owned(),
+        })
+            .try_into()?;
+        assert_eq!(batch.url, city.url);
+        assert_eq!(decoded_f_req(&batch.body), decoded_f_req(&city.body));
+        Ok(())
+    }
+
+    #[test]
+    fn packs_calls_with_indexed_tags_and_distinct_rpc_ids() -> Result<()> {
+        let calls = [
+            BatchCall::city("paris"),
+            BatchCall {
+                rpc_id: "abc123".to_owned(),
+                payload: "[1]".to_owned(),
+            },
+            BatchCall::city("rome"),
+        ];
+        let req: RequestBody = (&options(&calls)).try_into()?;
+        assert!(req.url.contains("?rpcids=H028ib,abc123&"));
+
+        let f_req: Value = serde_json::from_str(&decoded_f_req(&req.body).unwrap())?;
+        let entries = f_req[0].as_array().unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1], json!(["abc123", "[1]", null, "2"]));
+        assert_eq!(entries[2][0], "H028ib");
+        assert_eq!(entries[2][3], "3");
+        Ok(())
+    }
+
+    #[test]
+    fn empty_batch_is_rejected() {
+        assert!(RequestBody::try_from(&options(&[])).is_err());
+    }
+}
@@ -9,6 +9,7 @@ use crate::parsers::common::{
 };
 use crate::parsers::constants::CALENDAR_GRAPH;
 use crate::parsers::request::flight_request::ItineraryRequest;
+use crate::requests::config::multi_city::LegFilters;
 
 use anyhow::Result;
 
@@ -34,6 +35,9 @@ pub struct GraphRequestOptions<'a> {
     pub country: &'a str,
     /// Result sort order sent to Google Flights.
     pub sort_order: &'a SortOrder,
+    /// Stop, layover and duration limits for the return leg instead of the
+    /// shared ones.
+    pub return_filters: Option<&'a LegFilters>,
 }
 
 impl ToRequestBody for GraphRequestOptions<'_> {
@@ -65,7 +69,8 @@ impl TryFrom<&GraphRequestOptions<'_>> for RequestBody {
             *options.sort_order,
             None,
             None,
-        );
+        )
+        .with_return_filters(options.return_filters);
         let graph_req = GraphRequest {
             itinerary,
             date_start_graph: &options.date_start.to_string(),
@@ -167,6 +172,7 @@ mod tests {
             language: "en",
             country: "GB",
             sort_order: &SortOrder::Best,
+            return_filters: None,
         };
 
         let req: RequestBody = (&search_settings).try_into()?;
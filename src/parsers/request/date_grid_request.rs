@@ -9,6 +9,7 @@ use crate::parsers::common::{
 };
 use crate::parsers::constants::CALENDAR_GRID;
 use crate::parsers::request::flight_request::ItineraryRequest;
+use crate::requests::config::multi_city::LegFilters;
 
 use anyhow::Result;
 
@@ -42,6 +43,7 @@ pub struct DateGridRequestOptions<'a> {
     stopover_max: &'a StopoverDuration,
     duration_max: &'a TotalDuration,
     frontend_version: &'a String,
+    return_filters: Option<&'a LegFilters>,
 }
 
 impl<'a> DateGridRequestOptions<'a> {
@@ -81,8 +83,16 @@ impl<'a> DateGridRequestOptions<'a> {
             stopover_max,
             duration_max,
             frontend_version,
+            return_filters: None,
         }
     }
+
+    /// Stop, layover and duration limits for the return leg instead of the
+    /// shared ones.
+    pub fn with_return_filters(mut self, filters: Option<&'a LegFilters>) -> Self {
+        self.return_filters = filters;
+        self
+    }
 }
 
 impl ToRequestBody for DateGridRequestOptions<'_> {
@@ -117,7 +127,8 @@ impl TryFrom<&DateGridRequestOptions<'_>> for RequestBody {
                 SortOrder::Best,
                 None,
                 None,
-            ),
+            )
+            .with_return_filters(options.return_filters),
             dep_start: &options.dep_start.to_string(),
             dep_end: &options.dep_end.to_string(),
             ret_start: &options.ret_start.to_string(),
@@ -237,6 +248,21 @@ mod tests {
             "itinerary should not contain null,null,2"
         );
 
+        let return_filters = LegFilters {
+            stop_options: StopOptions::NoStop,
+            ..LegFilters::default()
+        };
+        let req: RequestBody = (&opts.with_return_filters(Some(&return_filters))).try_into()?;
+        let body = percent_encoding::percent_decode_str(&req.body).decode_utf8()?;
+        assert!(
+            body.contains(r#",0,null,null,\"2026-06-10\""#),
+            "outbound leg should keep the shared stops: {body}"
+        );
+        assert!(
+            body.contains(r#",1,null,null,\"2026-06-18\""#),
+            "return leg should be non-stop: {body}"
+        );
+
         Ok(())
     }
 }
@@ -1,4 +1,4 @@
-﻿use std::{
+use std::{
     time::{SystemTime, UNIX_EPOCH},
     vec,
 };
@@ -12,7 +12,7 @@ use crate::parsers::common::{
 };
 use crate::parsers::constants::{BOOKING_REQUEST, FLIGHT_REQUEST};
 use crate::parsers::response::flight_response::FlightInfo;
-use crate::requests::config::multi_city::{leg_tail, MultiCityConfig};
+use crate::requests::config::multi_city::{leg_tail, LegFilters, MultiCityConfig};
 use anyhow::Result;
 
 pub struct FlightRequestOptions<'a> {
@@ -53,6 +53,9 @@ pub struct FlightRequestOptions<'a> {
     pub max_price: Option<i32>,
     /// Baggage allowance `(carry_on_count, checked_count)`. `None` = no restriction.
     pub baggage: Option<(u8, u8)>,
+    /// Per-leg filters for the return leg, replacing the shared ones above
+    /// (all but `return_times`).  `None` = same filters both ways.
+    pub return_filters: Option<&'a LegFilters>,
 }
 
 impl ToRequestBody for FlightRequestOptions<'_> {
@@ -83,21 +86,40 @@ impl TryFrom<&FlightRequestOptions<'_>> for RequestBody {
             connecting_airports: options.connecting_airports,
             lower_emissions: options.lower_emissions,
         };
-        let leg2 = options.date_return.map(|date_return| SingleLegStruct {
-            departure: arrival,
-            arrival: departure,
-            stop_options: options.stop_option,
-            date: date_return,
-            times: options.return_times,
-            stopover_max: options.stopover_max,
-            stopover_min: options.stopover_min,
-            duration_max: options.duration_max,
-            chosen_itinerary: itinerary_return.as_ref(),
-            airlines_include: options.airlines_include,
-            airlines_exclude: options.airlines_exclude,
-            connecting_airports: options.connecting_airports,
-            lower_emissions: options.lower_emissions,
-        });
+        let leg2 = options
+            .date_return
+            .map(|date_return| match options.return_filters {
+                Some(filters) => SingleLegStruct {
+                    departure: arrival,
+                    arrival: departure,
+                    stop_options: &filters.stop_options,
+                    date: date_return,
+                    times: options.return_times,
+                    stopover_max: &filters.stopover_max,
+                    stopover_min: &filters.stopover_min,
+                    duration_max: &filters.duration_max,
+                    chosen_itinerary: itinerary_return.as_ref(),
+                    airlines_include: &filters.airlines_include,
+                    airlines_exclude: &filters.airlines_exclude,
+                    connecting_airports: &filters.connecting_airports,
+                    lower_emissions: filters.lower_emissions,
+                },
+                None => SingleLegStruct {
+                    departure: arrival,
+                    arrival: departure,
+                    stop_options: options.stop_option,
+                    date: date_return,
+                    times: options.return_times,
+                    stopover_max: options.stopover_max,
+                    stopover_min: options.stopover_min,
+                    duration_max: options.duration_max,
+                    chosen_itinerary: itinerary_return.as_ref(),
+                    airlines_include: options.airlines_include,
+                    airlines_exclude: options.airlines_exclude,
+                    connecting_airports: options.connecting_airports,
+                    lower_emissions: options.lower_emissions,
+                },
+            });
         let legs: Vec<SingleLegStruct<'_>> = if let Some(leg_2) = leg2 {
             vec![leg1, leg_2]
         } else {
@@ -349,6 +371,20 @@ impl<'a> ItineraryRequest<'a> {
     }
 }
 
+impl<'a> ItineraryRequest<'a> {
+    /// Gives the return leg, if any, the stop, layover and duration limits
+    /// of `filters` instead of the shared ones.
+    pub fn with_return_filters(mut self, filters: Option<&'a LegFilters>) -> Self {
+        if let (Some(filters), Some(leg)) = (filters, self.legs.get_mut(1)) {
+            leg.stop_options = &filters.stop_options;
+            leg.stopover_max = &filters.stopover_max;
+            leg.stopover_min = &filters.stopover_min;
+            leg.duration_max = &filters.duration_max;
+        }
+        self
+    }
+}
+
 impl SerializeToWeb for ItineraryRequest<'_> {
     fn serialize_to_web(&self) -> Result<String> {
         let graph = if self.is_graph { ",1" } else { "" };
@@ -582,6 +618,7 @@ mod tests {
             lower_emissions: false,
             max_price: None,
             baggage: None,
+            return_filters: None,
         };
 
         let req: RequestBody = (&search_settings).try_into()?;
@@ -634,6 +671,7 @@ mod tests {
             lower_emissions: false,
             max_price: None,
             baggage: None,
+            return_filters: None,
         };
 
         let req: RequestBody = (&search_settings).try_into()?;
@@ -642,6 +680,65 @@ mod tests {
         Ok(())
     }
 
+    /// `return_filters` replaces the shared filters on the return leg only.
+    #[test]
+    fn return_filters_apply_to_the_return_leg_only() -> Result<()> {
+        let mxp = Location {
+            loc_identifier: "MXP".to_owned(),
+            loc_type: PlaceType::Airport,
+            location_name: None,
+        };
+        let syd = Location {
+            loc_identifier: "SYD".to_owned(),
+            loc_type: PlaceType::Airport,
+            location_name: None,
+        };
+        let flight_times = FlightTimes::default();
+        let frontend_version = "boq_travel-frontend-ui_20240110.02_p0".to_string();
+        let return_filters = LegFilters {
+            airlines_exclude: vec!["BA".parse()?],
+            ..LegFilters::default()
+        };
+        let options = FlightRequestOptions {
+            departing_city: core::slice::from_ref(&mxp),
+            arriving_city: core::slice::from_ref(&syd),
+            date_start: "2024-02-02",
+            date_return: Some("2024-03-02"),
+            travellers: Travelers::new(vec![1, 0, 0, 0])?,
+            travel_class: &TravelClass::Economy,
+            stop_option: &StopOptions::NoStop,
+            departing_times: &flight_times,
+            return_times: &flight_times,
+            stopover_max: &StopoverDuration::UNLIMITED,
+            stopover_min: &StopoverDuration::UNLIMITED,
+            duration_max: &TotalDuration::UNLIMITED,
+            frontend_version: &frontend_version,
+            fixed_flights: &FixedFlights::new(2_usize),
+            language: "en",
+            country: "GB",
+            sort_order: &SortOrder::Best,
+            airlines_include: &[],
+            airlines_exclude: &[],
+            connecting_airports: &[],
+            lower_emissions: false,
+            max_price: None,
+            baggage: None,
+            return_filters: Some(&return_filters),
+        };
+
+        let req: RequestBody = (&options).try_into()?;
+        let body = percent_encoding::percent_decode_str(&req.body).decode_utf8()?;
+        assert!(
+            body.contains(r#",1,null,null,\"2024-02-02\""#),
+            "outbound leg should be non-stop with no airline filter: {body}"
+        );
+        assert!(
+            body.contains(r#",0,null,[\"BA\"],\"2024-03-02\""#),
+            "return leg should allow any stops and exclude BA: {body}"
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_result() -> Result<()> {
         let a = Location {
@@ -1134,6 +1231,7 @@ mod tests {
             lower_emissions: false,
             max_price: None,
             baggage: None,
+            return_filters: None,
         };
         let req: RequestBody = (&opts).try_into()?;
         // Both LHR and LGW must appear in the body; JFK as the single arrival.
@@ -0,0 +1,119 @@
+//! Demultiplexing of a `batchexecute` response into per-call payloads.
+//!
+//! Each answered RPC arrives as a `["wrb.fr", rpc_id, payload, …, tag]` entry;
+//! several entries may share one line.  `payload` is `null` when that RPC
+//! failed on its own, which does not fail the rest of the batch.
+
+use serde_json::Value;
+
+use crate::parsers::common::wrb_frames;
+use crate::parsers::request::batch_request::{batch_tag, BatchCall};
+
+/// One `wrb.fr` entry of a `batchexecute` response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchFrame {
+    pub rpc_id: String,
+    /// Inner JSON text, or `None` if the backend returned no data for the RPC.
+    pub payload: Option<String>,
+    /// Tag echoed from the request entry (index 6), when present.
+    pub tag: Option<String>,
+}
+
+/// Every `wrb.fr` entry in `body`, in order of appearance.  Lines that are not
+/// valid JSON are skipped.
+pub fn batch_frames(body: &str) -> Vec<BatchFrame> {
+    wrb_frames(body)
+        .into_iter()
+        .filter_map(|line| serde_json::from_str::<Vec<Vec<Value>>>(line).ok())
+        .flatten()
+        .filter(|entry| entry.first().and_then(Value::as_str) == Some("wrb.fr"))
+        .map(|entry| BatchFrame {
+            rpc_id: entry
+                .get(1)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_owned(),
+            payload: entry.get(2).and_then(Value::as_str).map(str::to_owned),
+            tag: entry.get(6).and_then(Value::as_str).map(str::to_owned),
+        })
+        .collect()
+}
+
+/// Pairs each of `calls` with its frame in `body`; `None` where the response
+/// has no frame for that call.
+///
+/// A frame matches on rpc id and echoed tag.  Frames without a tag are handed
+/// out to calls with the same rpc id in request order.
+pub fn demux_batch_frames(body: &str, calls: &[BatchCall]) -> Vec<Option<BatchFrame>> {
+    let mut frames: Vec<Option<BatchFrame>> = batch_frames(body).into_iter().map(Some).collect();
+    let mut take = |pred: &dyn Fn(&BatchFrame) -> bool| {
+        frames
+            .iter_mut()
+            .find(|f| f.as_ref().is_some_and(pred))
+            .and_then(Option::take)
+    };
+    calls
+        .iter()
+        .enumerate()
+        .map(|(i, call)| {
+            let tag = batch_tag(i, calls.len());
+            take(&|f| f.rpc_id == call.rpc_id && f.tag.as_deref() == Some(tag.as_str()))
+                .or_else(|| take(&|f| f.rpc_id == call.rpc_id && f.tag.is_none()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn call(rpc_id: &str) -> BatchCall {
+        BatchCall {
+            rpc_id: rpc_id.to_owned(),
+            payload: "[]".to_owned(),
+        }
+    }
+
+    #[test]
+    fn matches_frames_by_tag_regardless_of_order() {
+        let body = concat!(
+            ")]}'\n\n",
+            "120\n",
+            r#"[["wrb.fr","B","[\"b\"]",null,null,null,"2"],["wrb.fr","A","[\"a3\"]",null,null,null,"3"]]"#,
+            "\n60\n",
+            r#"[["wrb.fr","A","[\"a1\"]",null,null,null,"1"]]"#,
+            "\n",
+            r#"[["di",42],["af.httprm",41,"1",3]]"#,
+            "\n",
+        );
+        let calls = [call("A"), call("B"), call("A")];
+        let payloads: Vec<_> = demux_batch_frames(body, &calls)
+            .into_iter()
+            .map(|f| f.unwrap().payload.unwrap())
+            .collect();
+        assert_eq!(payloads, [r#"["a1"]"#, r#"["b"]"#, r#"["a3"]"#]);
+    }
+
+    #[test]
+    fn missing_and_failed_calls_are_reported_per_call() {
+        let body = concat!(
+            ")]}'\n\n",
+            r#"[["wrb.fr","A",null,null,null,[3],"1"]]"#,
+            "\n",
+        );
+        let frames = demux_batch_frames(body, &[call("A"), call("B")]);
+        let failed = frames[0].as_ref().unwrap();
+        assert_eq!(failed.rpc_id, "A");
+        assert_eq!(failed.payload, None);
+        assert!(frames[1].is_none());
+    }
+
+    #[test]
+    fn untagged_frames_are_assigned_in_order() {
+        let body = ")]}'\n\n[[\"wrb.fr\",\"A\",\"1\"]]\n[[\"wrb.fr\",\"A\",\"2\"]]\n";
+        let frames = demux_batch_frames(body, &[call("A"), call("A")]);
+        assert_eq!(frames[0].as_ref().unwrap().payload.as_deref(), Some("1"));
+        assert_eq!(frames[1].as_ref().unwrap().payload.as_deref(), Some("2"));
+    }
+}
@@ -81,6 +81,43 @@ impl ResponseInnerBodyParsed {
     }
 }
 
+impl ResponseInnerBodyParsed {
+    /// Airports Google lists around the best-matching place, with their
+    /// distance from it in kilometres.  The place itself is included at
+    /// distance zero when it is an airport; rail stations are skipped.
+    pub fn nearby_airports(&self) -> Vec<(Location, f64)> {
+        let Some(bulk) = self.result_container.first() else {
+            return Vec::new();
+        };
+        let own = bulk.city.airport_code.as_ref().map(|code| {
+            (
+                Location {
+                    loc_identifier: code.clone(),
+                    loc_type: PlaceType::Airport,
+                    location_name: Some(bulk.city.city_name.clone()),
+                },
+                0.0,
+            )
+        });
+        let nearby = bulk.airport.iter().flatten().filter_map(|a| {
+            let info = &a.airport_info;
+            if !matches!(info.place_type, PlaceType::Airport) {
+                return None;
+            }
+            let code = info.airport_code.clone()?;
+            Some((
+                Location {
+                    loc_identifier: code,
+                    loc_type: PlaceType::Airport,
+                    location_name: Some(info.city_name.clone()),
+                },
+                a.distance_km()?,
+            ))
+        });
+        own.into_iter().chain(nearby).collect()
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ResultContainer {
     pub city: PlaceDetails,
@@ -115,6 +152,20 @@ pub struct AirportsNames {
     distance: String,
 }
 
+impl AirportsNames {
+    /// Distance from the looked-up place, parsed from Google's display
+    /// string (`"98 km"`, `"1,204 km"`, `"61 mi"`).
+    pub fn distance_km(&self) -> Option<f64> {
+        let (number, unit) = self.distance.trim().split_once(' ')?;
+        let value: f64 = number.replace(',', "").parse().ok()?;
+        match unit {
+            "km" => Some(value),
+            "mi" => Some(value * 1.609_344),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -148,6 +199,25 @@ a
         )
     }
 
+    #[test]
+    fn nearby_airports_keep_distances_and_skip_stations() {
+        let raw = r#"[[[[3,"Bedřichov, Czechia","Bedřichov",null,"/m/05b0wjm",null,null,null,null,null,null,3],[[[1,"Václav Havel Airport Prague","Prague",null,"/m/05ywg","PRG",null,null,"PRG",null,null,1],"98 km"],[[1,"Pardubice Airport","Pardubice",null,"/m/0ch54","PED",null,null,"PED",null,null,1],"1,096 km"],[[5,"Prague Main Station","Prague",null,"/m/05ywg","XYG",null,null,"Prague Main Station",null,null,5],"92 km"],[[1,"Dresden Airport","Dresden",null,"/m/02pg3","DRS",null,null,"DRS",null,null,1],"70 mi"]]]]]"#;
+        let parsed: ResponseInnerBodyParsed = decode_inner_object(raw).unwrap();
+        let nearby: Vec<_> = parsed
+            .nearby_airports()
+            .into_iter()
+            .map(|(loc, km)| (loc.loc_identifier, km.round()))
+            .collect();
+        assert_eq!(
+            nearby,
+            [
+                ("PRG".to_owned(), 98.0),
+                ("PED".to_owned(), 1096.0),
+                ("DRS".to_owned(), 113.0)
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_weird_city() {
         let raw = r#"a
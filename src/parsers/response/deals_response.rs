@@ -103,8 +103,7 @@ pub fn parse_deals_response(raw: &str) -> Result<Vec<DealResult>> {
                 None => continue,
             };
             let deal = parse_deal_entry(e, out_date);
-            let key = (deal.destination_iata.clone(), date_key(deal.outbound_date));
-            if seen.insert(key) {
+            if seen.insert(deal_key(&deal)) {
                 deals.push(deal);
             }
         }
@@ -248,6 +247,11 @@ fn date_key(d: Option<NaiveDate>) -> String {
     d.map(|x| x.to_string()).unwrap_or_default()
 }
 
+/// Identity used to drop the same deal repeated across chunks.
+pub(crate) fn deal_key(deal: &DealResult) -> (String, String) {
+    (deal.destination_iata.clone(), date_key(deal.outbound_date))
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
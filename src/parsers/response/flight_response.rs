@@ -3,6 +3,7 @@ use std::fmt::Display;
 use std::fmt::Formatter;
 
 use crate::parsers::common::get_idx;
+use crate::parsers::common::FlightTimes;
 use crate::parsers::common::GetOuterErrorMessages;
 use crate::parsers::common::SerializeToWeb;
 
@@ -329,6 +330,25 @@ impl Itinerary {
             .iter()
             .any(|want| layovers.iter().any(|got| got.eq_ignore_ascii_case(want)))
     }
+
+    /// Whether the first flight leaves and the last one lands inside the
+    /// windows (or hours) of `times`.
+    ///
+    /// Google's time filter is a single hour range per side, so results for
+    /// several windows, or a window across midnight, include flights outside
+    /// them; this applies the exact filter client-side.
+    pub fn within_times(&self, times: &FlightTimes) -> bool {
+        let hour = |h: &Hour| h.hour.unwrap_or(0).clamp(0, 23) as u32;
+        let departs = self
+            .flight_details
+            .first()
+            .is_none_or(|f| times.allows_departure(hour(&f.departure_time)));
+        let arrives = self
+            .flight_details
+            .last()
+            .is_none_or(|f| times.allows_arrival(hour(&f.arrival_time)));
+        departs && arrives
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -560,12 +580,23 @@ impl FlightResponseContainer {
     /// merged here, this client-side pass is what makes "via" strict — see
     /// [`Itinerary::connects_via`].
     pub fn get_all_flights_via(&self, connecting_airports: &[String]) -> Vec<ItineraryContainer> {
+        self.get_flights_filtered(connecting_airports, &FlightTimes::default())
+    }
+
+    /// Like [`Self::get_all_flights_via`], also keeping only itineraries that
+    /// leave and land inside `times` — see [`Itinerary::within_times`].
+    pub fn get_flights_filtered(
+        &self,
+        connecting_airports: &[String],
+        times: &FlightTimes,
+    ) -> Vec<ItineraryContainer> {
         let mut seen = std::collections::HashSet::new();
         self.responses
             .iter()
             .filter_map(|r| r.maybe_get_all_flights())
             .flatten()
             .filter(|f| f.itinerary.connects_via(connecting_airports))
+            .filter(|f| f.itinerary.within_times(times))
             .filter(|f| seen.insert(f.itinerary_cost.departure_token.clone()))
             .collect()
     }
@@ -753,6 +784,26 @@ mod tests {
         assert!(!it.connects_via(&["DXB".to_string()]));
     }
 
+    #[test]
+    fn test_within_times_checks_first_departure_and_last_arrival() {
+        use crate::parsers::common::{TimeOfDay, TimeWindow};
+
+        // LUX 10:50 → ZRH → MXP 14:05.
+        let mystr = r#"["LX", ["SWISS"], [[null, null, null, "LUX", "Luxembourg Airport", "Zurich Airport", "ZRH", null, [10, 50], null, [11, 55], 65, [], 1, "76 cm", null, 1, "Airbus A220-100 Passenger", null, false, [2024, 1, 27], [2024, 1, 27], ["LX", "751", null, "SWISS"], null, null, 1, null, null, null, null, "76 centimetres", 40497], [null, null, "Helvetic", "ZRH", "Zurich Airport", "Milan Malpensa Airport", "MXP", null, [13, 10], null, [14, 5], 55, [null, null, null, null, null, true], 2, "74 cm", null, 1, "Embraer 195 E2", [null, true], false, [2024, 1, 27], [2024, 1, 27], ["LX", "1628", null, "SWISS"], null, null, 1, null, null, null, null, "74 centimetres", 37467]], "LUX", [2024, 1, 27], [10, 50], "MXP", [2024, 1, 27], [14, 5], 195, null, null, false, [[75, "ZRH", "ZRH", null, "Zurich Airport", "ZÃ¼rich", "Zurich Airport", "ZÃ¼rich"]], null, null, null, "G3nUPe", [[1705070296848121, 139803069, 858572], null, null, null, null, [[2]]], 1, null, null, [null, null, 1, -9, null, true, true, 78000, 86000, null, 119000, 1, false], [1], [["LX", "SWISS", "https://www.swiss.com/gb/en/prepare/special-care"]]]"#;
+        let it: Itinerary = serde_json::from_str(mystr).unwrap();
+
+        assert!(it.within_times(&FlightTimes::default()));
+        let morning_out = FlightTimes::default().depart_during(TimeOfDay::Morning);
+        assert!(it.within_times(&morning_out));
+        // Lands at 14:05, inside the afternoon and before the evening ends.
+        assert!(it.within_times(&morning_out.clone().arrive_during(TimeOfDay::Evening.by())));
+        assert!(!it.within_times(&morning_out.arrive_during(TimeOfDay::Evening)));
+        let early_or_late = FlightTimes::default()
+            .depart_during(TimeOfDay::EarlyMorning)
+            .depart_during(TimeWindow::after(18));
+        assert!(!it.within_times(&early_or_late));
+    }
+
     #[test]
     fn test_connects_via_nonstop_excluded() {
         // LG direct LUX→MXP, no connection_info → never matches a via filter.
@@ -3,12 +3,14 @@
 //!
 //! | Module | Purpose |
 //! |---|---|
+//! | [`batch_response`] | `batchexecute` response split back into per-call frames |
 //! | [`calendar_graph_response`] | Price-graph response |
 //! | [`city_response`] | City / location lookup response |
 //! | [`date_grid_response`] | Date-grid (departure × return matrix) response |
 //! | [`flight_response`] | Flight-search response (itineraries, prices, CO2) |
 //! | [`offer_response`] | Booking-offer response (per-OTA prices and URLs) |
 
+pub mod batch_response;
 pub mod calendar_graph_response;
 pub mod city_response;
 pub mod date_grid_response;
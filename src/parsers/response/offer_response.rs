@@ -1,4 +1,5 @@
-use crate::parsers::common::{decode_inner_object, decode_outer_object, get_idx};
+use crate::parsers::common::{decode_inner_object, decode_outer_object, get_idx, PassengerType};
+use crate::protos::urls::{ClickToken, Money};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -120,6 +121,9 @@ pub struct OfferGroup {
     /// Present for single-airline / OTA-style entries; `None` for multi-airline
     /// combined groups (use sub-options in that case).
     pub click_token: Option<String>,
+    /// Price per passenger type, read from the click token.  Empty when the
+    /// group has no click token.
+    pub passenger_prices: Vec<PassengerPrice>,
 }
 
 impl<'de> Deserialize<'de> for OfferGroup {
@@ -172,12 +176,14 @@ impl<'de> Deserialize<'de> for OfferGroup {
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
 
+        let passenger_prices = passenger_prices(click_token.as_deref());
         Ok(OfferGroup {
             airline_names,
             price,
             booking_token,
             sub_options,
             click_token,
+            passenger_prices,
         })
     }
 }
@@ -206,6 +212,8 @@ pub struct BookingSubOption {
     /// Click-tracking token (`u=` parameter) for `POST /travel/clk/f`.
     /// Pass to `ApiClient::resolve_booking_url` to get the final booking URL.
     pub click_token: Option<String>,
+    /// Price per passenger type, read from the click token.
+    pub passenger_prices: Vec<PassengerPrice>,
 }
 
 impl<'de> Deserialize<'de> for BookingSubOption {
@@ -246,15 +254,73 @@ impl<'de> Deserialize<'de> for BookingSubOption {
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
 
+        let passenger_prices = passenger_prices(click_token.as_deref());
         Ok(BookingSubOption {
             partner_names,
             price,
             booking_token,
             click_token,
+            passenger_prices,
         })
     }
 }
 
+// ---------------------------------------------------------------------------
+// PassengerPrice — what one passenger of a given type pays
+// ---------------------------------------------------------------------------
+
+/// The fare engine's price for one passenger of a given type.
+///
+/// Parsed from the booking solution encoded in a click token, which prices
+/// each passenger type in the party separately.  Amounts are in `currency`,
+/// the currency the fare was filed in, which may differ from the currency of
+/// [`OfferGroup::price`].
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct PassengerPrice {
+    /// `None` when there is no `fare_code` or it is not one this crate knows.
+    pub passenger_type: Option<PassengerType>,
+    /// ITA passenger type code, e.g. `"ADT"`, `"CNN"`, `"INF"`.  Some
+    /// fares do not name one.
+    pub fare_code: Option<String>,
+    /// Base fare plus taxes.
+    pub total: Option<f64>,
+    pub base_fare: Option<f64>,
+    pub taxes: Option<f64>,
+    pub currency: String,
+}
+
+/// Per-passenger-type prices in `click_token`; empty when there is no token
+/// or it does not decode.
+fn passenger_prices(click_token: Option<&str>) -> Vec<PassengerPrice> {
+    let Some(token) = click_token.and_then(|t| ClickToken::from_encoded(t).ok()) else {
+        return Vec::new();
+    };
+    let amount = |money: &Option<Money>| money.as_ref().and_then(|m| m.amount.parse().ok());
+    token
+        .solution
+        .and_then(|s| s.quote)
+        .map(|q| q.pricings)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|p| {
+            let fare_code = p
+                .fares
+                .iter()
+                .map(|f| f.passenger_type.as_str())
+                .find(|code| !code.is_empty())
+                .map(str::to_owned);
+            PassengerPrice {
+                passenger_type: fare_code.as_deref().and_then(PassengerType::from_fare_code),
+                total: amount(&p.total),
+                base_fare: amount(&p.base_fare),
+                taxes: amount(&p.taxes),
+                currency: p.total.map(|m| m.currency).unwrap_or_default(),
+                fare_code,
+            }
+        })
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -292,6 +358,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn passenger_prices_are_read_from_the_click_token() -> Result<()> {
+        let body = fs::read_to_string("test_files/offers_full.txt")?;
+        let container = create_raw_response_offer_vec(body)?;
+        let offers = &container.response[1].offers;
+
+        // Korean Air, €1130 for one adult, filed in yuan.
+        assert_eq!(offers[0].price, Some(1130));
+        assert_eq!(
+            offers[0].passenger_prices,
+            vec![PassengerPrice {
+                passenger_type: Some(PassengerType::Adult),
+                fare_code: Some("ADT".into()),
+                total: Some(8642.0),
+                base_fare: Some(5300.0),
+                taxes: Some(3342.0),
+                currency: "CNY".into(),
+            }]
+        );
+        // Delta's fares name no passenger type; the price is kept regardless.
+        let delta = &offers[2].passenger_prices;
+        assert_eq!(delta.len(), 1);
+        assert_eq!(delta[0].passenger_type, None);
+        assert_eq!(delta[0].total, Some(16698.0));
+        // Groups without a click token have nothing to read.
+        assert!(offers[3].click_token.is_none());
+        assert!(offers[3].passenger_prices.is_empty());
+        Ok(())
+    }
+
     /// Parses a minimal synthetic `GetBookingResults` inner payload and
     /// verifies that the combined €951 offer (Avianca outbound + American
     /// return) is extracted correctly.
@@ -2,13 +2,15 @@ pub mod urls {
     include!(concat!(env!("OUT_DIR"), "/urls.rs"));
 }
 use crate::parsers::constants::FLIGHTS_MAIN_PAGE;
+use anyhow::{anyhow, bail, Context, Result};
 use base64::{
     alphabet,
     engine::{self, general_purpose},
     Engine as _,
 };
+use percent_encoding::percent_decode_str;
 use prost::Message;
-use urls::ItineraryUrl;
+use urls::{ClickToken, ItineraryUrl};
 use urls::{Leg, LocType, Location};
 
 const CUSTOM_ENGINE: engine::GeneralPurpose =
@@ -47,6 +49,64 @@ impl ItineraryUrl {
 
         format!("{FLIGHTS_MAIN_PAGE}?tfs={}", encoded)
     }
+
+    /// Decodes a `tfs` value as produced by [`to_encoded`](Self::to_encoded).
+    ///
+    /// Percent-encoding, `=` padding and the standard base64 alphabet (`+`,
+    /// `/`) are accepted as well, since links copied from a browser or a chat
+    /// client sometimes carry them.
+    pub fn from_encoded(tfs: &str) -> Result<Self> {
+        let bytes = decode_web_base64(tfs, "tfs value")?;
+        Self::decode(bytes.as_slice()).context("tfs value is not a flight search")
+    }
+
+    /// Decodes the `tfs` parameter of a Google Flights link such as
+    /// `https://www.google.com/travel/flights?tfs=…&hl=en`.  A bare `tfs` value
+    /// is accepted too.
+    pub fn from_flight_url(url: &str) -> Result<Self> {
+        let url = url.trim();
+        let Some((_, query)) = url.split_once('?') else {
+            if url.contains('/') && url.contains("://") {
+                bail!("link has no tfs parameter");
+            }
+            return Self::from_encoded(url);
+        };
+        let query = query.split('#').next().unwrap_or_default();
+        let tfs = query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("tfs="))
+            .ok_or_else(|| anyhow!("link has no tfs parameter"))?;
+        Self::from_encoded(tfs)
+    }
+}
+
+impl ClickToken {
+    /// Decodes the `u` parameter an offer carries for `/travel/clk/f`.
+    pub fn from_encoded(token: &str) -> Result<Self> {
+        let bytes = decode_web_base64(token, "click token")?;
+        Self::decode(bytes.as_slice()).context("click token is not a booking solution")
+    }
+}
+
+/// Decodes URL-safe base64 as Google writes it, also accepting
+/// percent-encoding, `=` padding and the standard alphabet (`+`, `/`).
+/// `what` names the value in errors.
+fn decode_web_base64(value: &str, what: &str) -> Result<Vec<u8>> {
+    let value = percent_decode_str(value.trim())
+        .decode_utf8()
+        .with_context(|| format!("{what} is not valid UTF-8"))?;
+    let normalised: String = value
+        .trim_end_matches('=')
+        .chars()
+        .map(|c| match c {
+            '+' => '-',
+            '/' => '_',
+            c => c,
+        })
+        .collect();
+    CUSTOM_ENGINE
+        .decode(normalised)
+        .with_context(|| format!("{what} is not valid base64"))
 }
 
 #[cfg(test)]
@@ -67,12 +127,14 @@ mod tests {
                 r#type: LocType::Airport.into(),
             }],
             stops: None,
+            airlines: vec![],
             min_hour_departure: None,
             max_hour_departure: None,
             min_hour_arrival: None,
             max_hour_arrival: None,
             max_stopover_minutes: None,
             max_duration_minutes: None,
+            ..Default::default()
         };
 
         let leg2 = Leg {
@@ -86,12 +148,14 @@ mod tests {
                 r#type: LocType::Airport.into(),
             }],
             stops: None,
+            airlines: vec![],
             min_hour_departure: None,
             max_hour_departure: None,
             min_hour_arrival: None,
             max_hour_arrival: None,
             max_stopover_minutes: None,
             max_duration_minutes: None,
+            ..Default::default()
         };
 
         let x = ItineraryUrl {
@@ -99,6 +163,7 @@ mod tests {
             travellers: vec![Traveler::Adult.into()],
             class: TravelClass::Economy.into(),
             trip_type: TripType::RoundTrip.into(),
+            ..Default::default()
         };
 
         let base64_encoded_message = x.to_encoded();
@@ -121,12 +186,14 @@ mod tests {
                 r#type: LocType::City.into(),
             }],
             stops: Some(StopOptions::NoStop.into()),
+            airlines: vec![],
             min_hour_departure: None,
             max_hour_departure: None,
             min_hour_arrival: None,
             max_hour_arrival: None,
             max_stopover_minutes: None,
             max_duration_minutes: None,
+            ..Default::default()
         };
 
         let leg2 = Leg {
@@ -140,12 +207,14 @@ mod tests {
                 r#type: LocType::City.into(),
             }],
             stops: Some(StopOptions::NoStop.into()),
+            airlines: vec![],
             min_hour_departure: None,
             max_hour_departure: None,
             min_hour_arrival: None,
             max_hour_arrival: None,
             max_stopover_minutes: None,
             max_duration_minutes: None,
+            ..Default::default()
         };
 
         let x = ItineraryUrl {
@@ -159,6 +228,7 @@ mod tests {
             ],
             class: TravelClass::First.into(),
             trip_type: TripType::RoundTrip.into(),
+            ..Default::default()
         };
 
         let base64_encoded_message = x.to_encoded();
@@ -180,12 +250,14 @@ mod tests {
                 r#type: LocType::Airport.into(),
             }],
             stops: None,
+            airlines: vec![],
             min_hour_departure: Some(7),
             max_hour_departure: Some(23),
             min_hour_arrival: Some(0),
             max_hour_arrival: Some(23),
             max_stopover_minutes: Some(600),
             max_duration_minutes: Some(600),
+            ..Default::default()
         };
 
         let leg2 = Leg {
@@ -199,12 +271,14 @@ mod tests {
                 r#type: LocType::Airport.into(),
             }],
             stops: None,
+            airlines: vec![],
             min_hour_departure: Some(19),
             max_hour_departure: Some(23),
             min_hour_arrival: Some(0),
             max_hour_arrival: Some(23),
             max_stopover_minutes: Some(600),
             max_duration_minutes: Some(600),
+            ..Default::default()
         };
 
         let x = ItineraryUrl {
@@ -212,9 +286,36 @@ mod tests {
             travellers: vec![Traveler::Adult.into()],
             class: TravelClass::Economy.into(),
             trip_type: TripType::RoundTrip.into(),
+            ..Default::default()
         };
         let base64_encoded_message = x.to_encoded();
         let expected = "Gi0SCjIwMjQtMTEtMDZAB0gXUABYF2DYBGoHCAESA0NER3IHCAESA01YUJAB2AQaLRIKMjAyNC0xMS0xM0ATSBdQAFgXYNgEagcIARIDTVhQcgcIARIDQ0RHkAHYBEIBAUgBmAEB";
         assert_eq!(base64_encoded_message, expected);
     }
+
+    #[test]
+    fn decodes_links_and_bare_values() {
+        let tfs = "GisSCjIwMjQtMTEtMDYoAGoNCAISCS9tLzAyXzI4NnIMCAISCC9tLzA0anBsGisSCjIwMjQtMTEtMTMoAGoMCAISCC9tLzA0anBscg0IAhIJL20vMDJfMjg2QgUBAQIDBEgEmAEB";
+        let direct = ItineraryUrl::from_encoded(tfs).unwrap();
+        assert_eq!(direct.legs.len(), 2);
+        assert_eq!(direct.legs[0].departure[0].place_name, "/m/02_286");
+        assert_eq!(direct.class, TravelClass::First as i32);
+        assert_eq!(direct.to_encoded(), tfs);
+
+        let link = format!("https://www.google.com/travel/flights?hl=en&tfs={tfs}&curr=EUR#flt");
+        assert_eq!(ItineraryUrl::from_flight_url(&link).unwrap(), direct);
+        assert_eq!(ItineraryUrl::from_flight_url(tfs).unwrap(), direct);
+
+        let padded = format!("{}%3D%3D", tfs.replace('_', "/"));
+        assert_eq!(ItineraryUrl::from_encoded(&padded).unwrap(), direct);
+    }
+
+    #[test]
+    fn rejects_links_without_a_search() {
+        assert!(
+            ItineraryUrl::from_flight_url("https://www.google.com/travel/flights?hl=en").is_err()
+        );
+        assert!(ItineraryUrl::from_flight_url("https://www.google.com/travel/flights").is_err());
+        assert!(ItineraryUrl::from_encoded("not base64!").is_err());
+    }
 }
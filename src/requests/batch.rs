@@ -0,0 +1,281 @@
+//! Several independent RPCs sent as one `batchexecute` request.
+//!
+//! Adding a call to a [`BatchRequest`] returns a typed [`BatchHandle`]; after
+//! [`ApiClient::request_batch`](super::api::ApiClient::request_batch) the
+//! handle reads that call's result back out of the [`BatchResponse`].  Each
+//! call succeeds or fails on its own.
+//!
+//! ```no_run
+//! # async fn example(client: gflights::requests::api::ApiClient) -> gflights::Result<()> {
+//! use gflights::requests::batch::BatchRequest;
+//!
+//! let mut batch = BatchRequest::new();
+//! let london = batch.city("london");
+//! let paris = batch.city("paris");
+//! let raw = batch.rpc::<serde_json::Value>("abc123", "[null,1]");
+//!
+//! let res = client.request_batch(&batch).await?;
+//! println!("{:?}", res.get(london)?.to_city_list());
+//! println!("{:?}", res.get(paris)?.to_city_list());
+//! println!("{:?}", res.get(raw));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The city lookup (`H028ib`), the calendar graph and the shopping search
+//! have typed helpers that decode into the same parsers as
+//! [`ApiClient::request_city`](super::api::ApiClient::request_city),
+//! [`ApiClient::request_graph`](super::api::ApiClient::request_graph) and
+//! [`ApiClient::request_flights`](super::api::ApiClient::request_flights).
+//! The web app sends the graph and the search to their own
+//! `FlightsFrontendService` URLs, so their `batchexecute` rpc ids are not
+//! built in: [`BatchRequest::graph`] and [`BatchRequest::shopping`] take the
+//! id, read from a browser capture of a batched page load, alongside the
+//! search.  [`BatchRequest::rpc`] covers any other id and payload.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use chrono::Months;
+use serde::de::DeserializeOwned;
+
+use crate::error::{Error, Result};
+use crate::parsers::batch_request::BatchCall;
+use crate::parsers::batch_response::{demux_batch_frames, BatchFrame};
+use crate::parsers::calendar_graph_response::GraphRawResponse;
+use crate::parsers::city_response::ResponseInnerBodyParsed;
+use crate::parsers::common::{decode_inner_object, RequestBody};
+use crate::parsers::flight_response::RawResponse;
+use crate::requests::api::{flight_request, graph_request};
+use crate::requests::config::Config;
+use crate::requests::endpoint::Endpoint;
+use crate::requests::metrics::{self, MetricsObserver};
+
+/// Calls to pack into one `batchexecute` request.
+#[derive(Debug, Clone, Default)]
+pub struct BatchRequest {
+    calls: Vec<BatchCall>,
+}
+
+impl BatchRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a city / airport lookup, answered like
+    /// [`ApiClient::request_city`](super::api::ApiClient::request_city).
+    pub fn city(&mut self, city: &str) -> BatchHandle<ResponseInnerBodyParsed> {
+        self.push(BatchCall::city(city))
+    }
+
+    /// Adds a calendar-graph call for `config` over `months`, answered like
+    /// [`ApiClient::request_graph`](super::api::ApiClient::request_graph).
+    /// `rpc_id` is the `batchexecute` id of `GetCalendarGraph`; see the
+    /// module docs.
+    ///
+    /// # Errors
+    /// [`Error::InvalidConfig`] if the request cannot be built, as for
+    /// `request_graph`.
+    pub fn graph(
+        &mut self,
+        rpc_id: impl Into<String>,
+        config: &Config,
+        months: Months,
+    ) -> Result<BatchHandle<GraphRawResponse>> {
+        let request = graph_request(config, months, &String::new(), "en", "GB")?;
+        Ok(self.push(call_for(rpc_id, &request)?))
+    }
+
+    /// Adds a shopping search for `config`, answered like one response of
+    /// [`ApiClient::request_flights`](super::api::ApiClient::request_flights)
+    /// (wrap it in a
+    /// [`FlightResponseContainer`](crate::parsers::flight_response::FlightResponseContainer)
+    /// to list the flights).  `rpc_id` is the `batchexecute` id of
+    /// `GetShoppingResults`; see the module docs.
+    ///
+    /// # Errors
+    /// [`Error::InvalidConfig`] if the request cannot be built.
+    pub fn shopping(
+        &mut self,
+        rpc_id: impl Into<String>,
+        config: &Config,
+    ) -> Result<BatchHandle<RawResponse>> {
+        let request = flight_request(config, &String::new(), "en", "GB")?;
+        Ok(self.push(call_for(rpc_id, &request)?))
+    }
+
+    /// Adds an arbitrary RPC.  `payload` is its argument as JSON text; the
+    /// answer is deserialised into `T` (use `serde_json::Value` to keep it raw).
+    pub fn rpc<T: DeserializeOwned>(
+        &mut self,
+        rpc_id: impl Into<String>,
+        payload: impl Into<String>,
+    ) -> BatchHandle<T> {
+        self.push(BatchCall {
+            rpc_id: rpc_id.into(),
+            payload: payload.into(),
+        })
+    }
+
+    /// The calls added so far, in order.
+    pub fn calls(&self) -> &[BatchCall] {
+        &self.calls
+    }
+
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    fn push<T>(&mut self, call: BatchCall) -> BatchHandle<T> {
+        self.calls.push(call);
+        BatchHandle {
+            index: self.calls.len() - 1,
+            _result: PhantomData,
+        }
+    }
+}
+
+/// The batch call carrying `request`'s payload.  Only the payload is used, so
+/// the label and locale `request` was built with do not matter.
+fn call_for(rpc_id: impl Into<String>, request: &RequestBody) -> Result<BatchCall> {
+    BatchCall::from_request(rpc_id, request).map_err(|e| Error::InvalidConfig(format!("{e:#}")))
+}
+
+/// Refers to one call of a [`BatchRequest`] and the type its result decodes to.
+pub struct BatchHandle<T> {
+    index: usize,
+    _result: PhantomData<fn() -> T>,
+}
+
+impl<T> BatchHandle<T> {
+    /// Position of the call in its batch.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<T> Clone for BatchHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for BatchHandle<T> {}
+
+impl<T> fmt::Debug for BatchHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchHandle")
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+/// Answer to a [`BatchRequest`], split back into per-call frames.
+#[derive(Clone)]
+pub struct BatchResponse {
+    endpoint: Endpoint,
+    calls: Vec<BatchCall>,
+    frames: Vec<Option<BatchFrame>>,
+    metrics: Arc<dyn MetricsObserver>,
+}
+
+impl fmt::Debug for BatchResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchResponse")
+            .field("endpoint", &self.endpoint)
+            .field("calls", &self.calls)
+            .field("frames", &self.frames)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BatchResponse {
+    pub(crate) fn new(
+        endpoint: Endpoint,
+        calls: Vec<BatchCall>,
+        body: &str,
+        metrics: Arc<dyn MetricsObserver>,
+    ) -> Self {
+        let frames = demux_batch_frames(body, &calls);
+        Self {
+            endpoint,
+            calls,
+            frames,
+            metrics,
+        }
+    }
+
+    /// Decodes the result of the call behind `handle`.
+    ///
+    /// # Errors
+    /// [`Error::Backend`] if the backend answered that call with no data, and
+    /// [`Error::Parse`] if the response has no frame for it or its payload does
+    /// not decode into `T`.
+    pub fn get<T: DeserializeOwned>(&self, handle: BatchHandle<T>) -> Result<T> {
+        let payload = self.payload(handle.index)?;
+        decode_inner_object(payload)
+            .map_err(|e| metrics::parse_error(self.metrics.as_ref(), self.endpoint, e))
+    }
+
+    /// Raw frame for call `index`, if the response contained one.
+    pub fn frame(&self, index: usize) -> Option<&BatchFrame> {
+        self.frames.get(index)?.as_ref()
+    }
+
+    fn payload(&self, index: usize) -> Result<&str> {
+        let rpc_id = self
+            .calls
+            .get(index)
+            .map_or("?", |call| call.rpc_id.as_str());
+        let frame = self.frame(index).ok_or_else(|| Error::Parse {
+            endpoint: self.endpoint,
+            json_path: String::new(),
+            message: format!("no wrb.fr frame for batch call {index} (rpc {rpc_id})"),
+        })?;
+        frame.payload.as_deref().ok_or_else(|| Error::Backend {
+            messages: vec![format!(
+                "rpc {rpc_id} (batch call {index}) returned no data"
+            )],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handles_decode_their_own_frame() {
+        let mut batch = BatchRequest::new();
+        let london = batch.city("london");
+        let raw = batch.rpc::<Vec<u32>>("abc123", "[]");
+        let failed = batch.rpc::<Vec<u32>>("abc123", "[]");
+        let missing = batch.rpc::<Vec<u32>>("zzz", "[]");
+
+        let body = concat!(
+            ")]}'\n\n",
+            r#"[["wrb.fr","abc123","[1,2]",null,null,null,"2"],["wrb.fr","abc123",null,null,null,[3],"3"]]"#,
+            "\n",
+            r#"[["wrb.fr","H028ib","[[[[3,null,\"London\",null,\"/m/04jpl\",null]]]]",null,null,null,"1"]]"#,
+            "\n",
+        );
+        let res = BatchResponse::new(
+            Endpoint::BatchExecute,
+            batch.calls().to_vec(),
+            body,
+            Arc::new(metrics::NoMetrics),
+        );
+
+        let city = res.get(london).expect("city frame decodes");
+        assert_eq!(city.result_container[0].city.city_name, "London");
+        assert_eq!(res.get(raw).expect("raw frame decodes"), [1, 2]);
+        assert!(matches!(res.get(failed), Err(Error::Backend { .. })));
+        assert!(matches!(res.get(missing), Err(Error::Parse { .. })));
+        assert!(res.frame(3).is_none());
+    }
+}
@@ -0,0 +1,238 @@
+//! Many independent searches run with bounded concurrency, used by
+//! [`ApiClient::search_many`](super::api::ApiClient::search_many).
+//!
+//! Results are yielded as they complete, tagged with the index of the input
+//! they answer, so the output order is not the input order.  Every request
+//! still goes through the client's rate limiter; `concurrency` only caps how
+//! many responses are awaited at once.
+
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+use futures::stream::{self, BoxStream};
+use futures::StreamExt as _;
+
+use crate::error::Result;
+
+/// What to do when one search fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BulkMode {
+    /// Keep going and yield every result, failures included.
+    #[default]
+    CollectAll,
+    /// Yield the first failure, then end the stream; searches still in
+    /// flight are cancelled and the rest are never sent.
+    FailFast,
+}
+
+/// Counts reported after each completed search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BulkProgress {
+    /// Searches that succeeded.
+    pub done: usize,
+    /// Searches that failed.
+    pub failed: usize,
+    /// Searches not finished yet (including those never started after a
+    /// fail-fast stop).
+    pub remaining: usize,
+}
+
+impl BulkProgress {
+    pub fn total(&self) -> usize {
+        self.done + self.failed + self.remaining
+    }
+}
+
+type ProgressFn = Arc<dyn Fn(BulkProgress) + Send + Sync>;
+
+/// Scheduling options for [`ApiClient::search_many_with`](super::api::ApiClient::search_many_with).
+///
+/// ```rust
+/// use gflights::requests::bulk::{BulkMode, BulkOptions};
+///
+/// let (tx, rx) = std::sync::mpsc::channel();
+/// let options = BulkOptions::new(8)
+///     .with_mode(BulkMode::FailFast)
+///     .with_progress(move |p| {
+///         let _ = tx.send(p);
+///     });
+/// # drop((options, rx));
+/// ```
+#[derive(Clone)]
+pub struct BulkOptions {
+    /// Searches awaited at once; `0` is treated as `1`.
+    pub concurrency: usize,
+    pub mode: BulkMode,
+    progress: Option<ProgressFn>,
+}
+
+impl BulkOptions {
+    pub fn new(concurrency: usize) -> Self {
+        Self {
+            concurrency,
+            mode: BulkMode::default(),
+            progress: None,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: BulkMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Calls `progress` after every completed search, before its result is
+    /// yielded.  Forward to a channel to observe progress from another task.
+    pub fn with_progress(
+        mut self,
+        progress: impl Fn(BulkProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+}
+
+impl fmt::Debug for BulkOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BulkOptions")
+            .field("concurrency", &self.concurrency)
+            .field("mode", &self.mode)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+/// Runs `run` over `inputs` as described by `options`, yielding
+/// `(input index, result)` in completion order.
+pub(crate) fn run_bulk<I, T, F, Fut>(
+    inputs: Vec<I>,
+    options: BulkOptions,
+    run: F,
+) -> BoxStream<'static, (usize, Result<T>)>
+where
+    I: Send + 'static,
+    T: Send + 'static,
+    F: Fn(I) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+{
+    let progress = BulkProgress {
+        remaining: inputs.len(),
+        ..BulkProgress::default()
+    };
+    let BulkOptions {
+        concurrency,
+        mode,
+        progress: report,
+    } = options;
+    let pending = stream::iter(inputs.into_iter().enumerate())
+        .map(move |(index, input)| {
+            let fut = run(input);
+            async move { (index, fut.await) }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .boxed();
+
+    // `unfold` rather than a combinator so that a fail-fast stop ends the
+    // stream at once, dropping whatever is still in flight.
+    stream::unfold(
+        (pending, progress, false),
+        move |(mut pending, mut progress, stopped)| {
+            let report = report.clone();
+            async move {
+                if stopped {
+                    return None;
+                }
+                let (index, result) = pending.next().await?;
+                progress.remaining -= 1;
+                let mut stop = false;
+                match &result {
+                    Ok(_) => progress.done += 1,
+                    Err(e) => {
+                        progress.failed += 1;
+                        if mode == BulkMode::FailFast {
+                            tracing::warn!(index, error = %e, "bulk search stopped on first failure");
+                            stop = true;
+                        }
+                    }
+                }
+                if let Some(report) = &report {
+                    report(progress);
+                }
+                Some(((index, result), (pending, progress, stop)))
+            }
+        },
+    )
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use futures::future;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    fn fail_odd(n: usize) -> impl Future<Output = Result<usize>> {
+        future::ready(if n % 2 == 1 {
+            Err(Error::InvalidConfig(format!("odd {n}")))
+        } else {
+            Ok(n * 10)
+        })
+    }
+
+    #[tokio::test]
+    async fn collect_all_yields_every_result_with_its_index() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let options = BulkOptions::new(3).with_progress(move |p| sink.lock().unwrap().push(p));
+
+        let mut results: Vec<_> = run_bulk((0..5).collect(), options, fail_odd)
+            .collect()
+            .await;
+        results.sort_by_key(|(i, _)| *i);
+
+        assert_eq!(results.len(), 5);
+        for (i, result) in &results {
+            assert_eq!(result.as_ref().ok(), (i % 2 == 0).then_some(&(i * 10)));
+        }
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 5);
+        assert!(seen.iter().all(|p| p.total() == 5));
+        assert_eq!(
+            seen.last(),
+            Some(&BulkProgress {
+                done: 3,
+                failed: 2,
+                remaining: 0
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn fail_fast_stops_after_first_failure() {
+        let started = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&started);
+        let options = BulkOptions::new(1).with_mode(BulkMode::FailFast);
+
+        let results: Vec<_> = run_bulk((0..10).collect(), options, move |n| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            fail_odd(n)
+        })
+        .collect()
+        .await;
+
+        let indices: Vec<usize> = results.iter().map(|(i, _)| *i).collect();
+        assert_eq!(indices, [0, 1]);
+        assert!(results[1].1.is_err());
+        assert!(started.load(Ordering::SeqCst) <= 3, "later inputs not sent");
+    }
+
+    #[tokio::test]
+    async fn zero_concurrency_still_makes_progress() {
+        let results: Vec<_> = run_bulk(vec![0, 2], BulkOptions::new(0), fail_odd)
+            .collect()
+            .await;
+        assert_eq!(results.len(), 2);
+    }
+}
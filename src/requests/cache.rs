@@ -0,0 +1,374 @@
+//! Optional response cache consulted by `ApiClient::do_request`.
+//!
+//! Entries are keyed by endpoint, decoded `f.req` payload, currency, language
+//! and country, and expire after a per-[`Endpoint`] TTL (see [`CacheConfig`]).
+//! Only `200 OK` responses are stored.  Cache hits never touch the network,
+//! the rate limiter, or the sticky 429 flag.
+//!
+//! Two [`CacheStore`] backends are bundled: [`MemoryCache`] (per process) and
+//! [`DiskCache`] (one JSON file per entry, shared across runs).  Store reads
+//! and writes run on tokio's blocking pool, off the async executor.
+//!
+//! In [`CacheMode::Offline`] nothing reaches the network: build the client
+//! with `ApiClient::new_offline`, which skips the frontend-version probe, and
+//! booking-URL resolution fails with [`Error::CacheMiss`] like any other miss.
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! use gflights::requests::api::ApiClient;
+//! use gflights::requests::cache::{CacheConfig, DiskCache};
+//!
+//! let client = ApiClient::new()
+//!     .await
+//!     .with_cache(DiskCache::new(".gflights-cache")?, CacheConfig::default());
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::endpoint::Endpoint;
+use crate::error::Error;
+
+/// A stored `200 OK` response body.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    /// Full cache key, kept alongside the body to detect hash collisions on disk.
+    pub key: String,
+    pub body: String,
+    /// Unix timestamp (seconds) at which the response was stored.
+    pub stored_at: u64,
+}
+
+/// Storage backend for the response cache.
+///
+/// `ApiClient` calls these on tokio's blocking pool, so an implementation may
+/// do blocking I/O.
+pub trait CacheStore: Send + Sync {
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+    fn put(&self, entry: CachedResponse);
+}
+
+/// In-memory [`CacheStore`]; contents are lost when the process exits.
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    entries: Mutex<HashMap<String, CachedResponse>>,
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CacheStore for MemoryCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.entries.lock().ok()?.get(key).cloned()
+    }
+
+    fn put(&self, entry: CachedResponse) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(entry.key.clone(), entry);
+        }
+    }
+}
+
+/// On-disk [`CacheStore`]: one JSON file per entry inside a directory.
+///
+/// Read and write failures are logged and treated as cache misses, so a
+/// broken cache directory never fails a request.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    /// Uses `dir` as the cache directory, creating it if needed.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("cannot create cache directory {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.json", fnv1a(key.as_bytes())))
+    }
+}
+
+impl CacheStore for DiskCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let data = std::fs::read_to_string(self.path_for(key)).ok()?;
+        let entry: CachedResponse = serde_json::from_str(&data)
+            .map_err(|e| tracing::warn!(error = %e, "ignoring malformed cache entry"))
+            .ok()?;
+        (entry.key == key).then_some(entry)
+    }
+
+    fn put(&self, entry: CachedResponse) {
+        let path = self.path_for(&entry.key);
+        let written = serde_json::to_string(&entry)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| std::fs::write(&path, data).map_err(anyhow::Error::from));
+        if let Err(e) = written {
+            tracing::warn!(path = %path.display(), error = %e, "failed to write cache entry");
+        }
+    }
+}
+
+/// How the cache interacts with the network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheMode {
+    /// Serve fresh entries, fetch and store everything else.
+    #[default]
+    ReadWrite,
+    /// Never touch the network: serve any stored entry regardless of age and
+    /// fail with [`Error::CacheMiss`] otherwise.
+    Offline,
+}
+
+/// Per-endpoint TTLs and [`CacheMode`].
+///
+/// Endpoints without a TTL are never cached.  The defaults keep city
+/// lookups for a week, price graphs and grids for an hour and live shopping
+/// results (search, offers, explore, deals) for ten minutes; booking-URL
+/// resolution is never cached.
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    pub ttls: HashMap<Endpoint, Duration>,
+    pub mode: CacheMode,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        const MINUTE: u64 = 60;
+        let ttls = [
+            (Endpoint::City, 7 * 24 * 60 * MINUTE),
+            (Endpoint::CalendarGraph, 60 * MINUTE),
+            (Endpoint::CalendarGrid, 60 * MINUTE),
+            (Endpoint::Shopping, 10 * MINUTE),
+            (Endpoint::Booking, 10 * MINUTE),
+            (Endpoint::Explore, 10 * MINUTE),
+            (Endpoint::Deals, 10 * MINUTE),
+        ]
+        .into_iter()
+        .map(|(endpoint, secs)| (endpoint, Duration::from_secs(secs)))
+        .collect();
+        Self {
+            ttls,
+            mode: CacheMode::default(),
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Overrides the TTL for one endpoint.
+    pub fn with_ttl(mut self, endpoint: Endpoint, ttl: Duration) -> Self {
+        self.ttls.insert(endpoint, ttl);
+        self
+    }
+
+    /// Disables caching for one endpoint.
+    pub fn without(mut self, endpoint: Endpoint) -> Self {
+        self.ttls.remove(&endpoint);
+        self
+    }
+
+    pub fn with_mode(mut self, mode: CacheMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+/// A [`CacheStore`] together with its [`CacheConfig`], as held by `ApiClient`.
+pub(crate) struct ResponseCache {
+    pub(crate) store: Arc<dyn CacheStore>,
+    pub(crate) config: CacheConfig,
+}
+
+/// Result of a cache lookup.
+pub(crate) enum Lookup {
+    Hit(String),
+    /// Not cached (or stale); fetch and store under this key.
+    Miss(String),
+    /// The endpoint is not cached at all.
+    Bypass,
+}
+
+impl ResponseCache {
+    pub(crate) fn is_offline(&self) -> bool {
+        self.config.mode == CacheMode::Offline
+    }
+
+    pub(crate) async fn lookup(
+        &self,
+        endpoint: Endpoint,
+        f_req: &str,
+        currency: &str,
+        language: &str,
+        country: &str,
+    ) -> Result<Lookup, Error> {
+        let offline = self.is_offline();
+        let Some(ttl) = self.config.ttls.get(&endpoint) else {
+            return if offline {
+                Err(Error::CacheMiss { endpoint })
+            } else {
+                Ok(Lookup::Bypass)
+            };
+        };
+        let key = cache_key(endpoint, f_req, currency, language, country);
+        let store = Arc::clone(&self.store);
+        let stored = {
+            let key = key.clone();
+            tokio::task::spawn_blocking(move || store.get(&key))
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!(error = %e, "cache read panicked");
+                    None
+                })
+        };
+        match stored {
+            Some(entry)
+                if offline || now_secs().saturating_sub(entry.stored_at) < ttl.as_secs() =>
+            {
+                tracing::debug!(%endpoint, "response cache hit");
+                Ok(Lookup::Hit(entry.body))
+            }
+            _ if offline => Err(Error::CacheMiss { endpoint }),
+            _ => Ok(Lookup::Miss(key)),
+        }
+    }
+
+    pub(crate) async fn store(&self, key: String, body: &str) {
+        let store = Arc::clone(&self.store);
+        let entry = CachedResponse {
+            key,
+            body: body.to_owned(),
+            stored_at: now_secs(),
+        };
+        if let Err(e) = tokio::task::spawn_blocking(move || store.put(entry)).await {
+            tracing::warn!(error = %e, "cache write panicked");
+        }
+    }
+}
+
+fn cache_key(
+    endpoint: Endpoint,
+    f_req: &str,
+    currency: &str,
+    language: &str,
+    country: &str,
+) -> String {
+    format!("{endpoint}|{currency}|{language}-{country}|{f_req}")
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 64-bit FNV-1a — a stable (cross-version) hash for on-disk file names.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn cache(config: CacheConfig) -> ResponseCache {
+        ResponseCache {
+            store: Arc::new(MemoryCache::new()),
+            config,
+        }
+    }
+
+    async fn lookup(cache: &ResponseCache, endpoint: Endpoint) -> Result<Lookup, Error> {
+        cache.lookup(endpoint, "[null]", "USD", "en", "GB").await
+    }
+
+    #[tokio::test]
+    async fn miss_then_hit_after_store() {
+        let cache = cache(CacheConfig::default());
+        let Ok(Lookup::Miss(key)) = lookup(&cache, Endpoint::City).await else {
+            panic!("expected miss");
+        };
+        cache.store(key, "body").await;
+        assert!(matches!(lookup(&cache, Endpoint::City).await, Ok(Lookup::Hit(b)) if b == "body"));
+    }
+
+    #[tokio::test]
+    async fn locale_is_part_of_the_key() {
+        let cache = cache(CacheConfig::default());
+        let Ok(Lookup::Miss(key)) = lookup(&cache, Endpoint::Shopping).await else {
+            panic!("expected miss");
+        };
+        cache.store(key, "body").await;
+        let other = cache
+            .lookup(Endpoint::Shopping, "[null]", "EUR", "en", "GB")
+            .await;
+        assert!(matches!(other, Ok(Lookup::Miss(_))));
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_misses_online_and_hits_offline() {
+        let config = CacheConfig::default().with_ttl(Endpoint::Shopping, Duration::ZERO);
+        let cache = cache(config);
+        let Ok(Lookup::Miss(key)) = lookup(&cache, Endpoint::Shopping).await else {
+            panic!("expected miss");
+        };
+        cache.store(key, "stale").await;
+        assert!(matches!(
+            lookup(&cache, Endpoint::Shopping).await,
+            Ok(Lookup::Miss(_))
+        ));
+
+        let offline = ResponseCache {
+            config: cache.config.clone().with_mode(CacheMode::Offline),
+            ..cache
+        };
+        assert!(
+            matches!(lookup(&offline, Endpoint::Shopping).await, Ok(Lookup::Hit(b)) if b == "stale")
+        );
+        assert!(lookup(&offline, Endpoint::Deals).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn uncached_endpoints_bypass() {
+        let cache = cache(CacheConfig::default().without(Endpoint::City));
+        assert!(matches!(
+            lookup(&cache, Endpoint::City).await,
+            Ok(Lookup::Bypass)
+        ));
+        assert!(matches!(
+            lookup(&cache, Endpoint::BookingUrl).await,
+            Ok(Lookup::Bypass)
+        ));
+    }
+
+    #[test]
+    fn disk_cache_round_trips() {
+        let dir = std::env::temp_dir().join(format!("gflights-cache-{}", std::process::id()));
+        let store = DiskCache::new(&dir).unwrap();
+        let entry = CachedResponse {
+            key: "k".into(),
+            body: "b".into(),
+            stored_at: 1,
+        };
+        store.put(entry.clone());
+        assert_eq!(store.get("k"), Some(entry));
+        assert_eq!(store.get("missing"), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,294 @@
+//! Record/replay ("cassette") transports.
+//!
+//! [`RecordingTransport`] forwards every request to an inner [`Transport`] and
+//! appends the request (method, URL, decoded `f.req`) together with the
+//! response status, headers and raw body to a JSON cassette file.
+//! [`ReplayTransport`] serves matching requests from that file without any
+//! network access, turning a single live session into a deterministic
+//! regression fixture.
+//!
+//! Requests are matched on method, endpoint (the URL without its query
+//! string) and the decoded `f.req` payload.  The query string carries the
+//! frontend build label and the body carries an `at=…:<epoch_ms>` token, both
+//! of which change between sessions, so neither takes part in matching.
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! use gflights::requests::api::ApiClient;
+//!
+//! // Live session, saved to disk.
+//! let client = ApiClient::new_recording("session.cassette.json").await?;
+//! // … run searches …
+//!
+//! // Later: the same calls, served offline.
+//! let client = ApiClient::new_replaying("session.cassette.json").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use futures::future::BoxFuture;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use super::transport::{decoded_f_req, Transport, TransportRequest, TransportResponse};
+
+/// One recorded request/response pair.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CassetteEntry {
+    pub method: String,
+    pub url: String,
+    /// Percent-decoded `f.req` form value; `None` for requests without one
+    /// (the frontend-version `GET`, the booking-URL click tracker).
+    pub f_req: Option<String>,
+    pub status: u16,
+    /// Response headers with text values, so a replayed 429 keeps its
+    /// `Retry-After`.  Absent in cassettes recorded before headers were kept.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl CassetteEntry {
+    fn matches(&self, method: &str, endpoint: &str, f_req: Option<&str>) -> bool {
+        self.method == method
+            && strip_query(&self.url) == endpoint
+            && self.f_req.as_deref() == f_req
+    }
+}
+
+/// Contents of a cassette file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub entries: Vec<CassetteEntry>,
+}
+
+impl Cassette {
+    /// Reads a cassette from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read cassette {}", path.display()))?;
+        serde_json::from_str(&data)
+            .with_context(|| format!("malformed cassette {}", path.display()))
+    }
+
+    /// Writes the cassette to a JSON file, replacing any existing content.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let data = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, data)
+            .with_context(|| format!("cannot write cassette {}", path.display()))
+    }
+}
+
+/// Forwards requests to an inner transport and records each exchange.
+///
+/// The cassette file is rewritten after every response, so it is complete
+/// even if the process exits without dropping the client.  Writes run on the
+/// blocking pool, one at a time, and a snapshot older than the file on disk
+/// is skipped, so concurrent responses never lose entries.
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+    /// Entry count of the last snapshot written; held while writing.
+    saved: Arc<Mutex<usize>>,
+}
+
+impl RecordingTransport {
+    /// Records every exchange made through `inner` to `path`, truncating any
+    /// existing cassette there.
+    pub fn new(inner: impl Transport + 'static, path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            path: path.into(),
+            cassette: Mutex::new(Cassette::default()),
+            saved: Arc::new(Mutex::new(0)),
+        }
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>> {
+        Box::pin(async move {
+            let method = request.method.to_string();
+            let url = request.url.clone();
+            let f_req = request.body.as_deref().and_then(decoded_f_req);
+            let response = self.inner.send(request).await?;
+
+            let snapshot = {
+                let mut cassette = self
+                    .cassette
+                    .lock()
+                    .map_err(|_| anyhow!("cassette lock poisoned"))?;
+                cassette.entries.push(CassetteEntry {
+                    method,
+                    url,
+                    f_req,
+                    status: response.status.as_u16(),
+                    headers: header_strings(&response.headers),
+                    body: response.body.clone(),
+                });
+                cassette.clone()
+            };
+            let path = self.path.clone();
+            let saved = Arc::clone(&self.saved);
+            tokio::task::spawn_blocking(move || -> Result<()> {
+                let mut saved = saved
+                    .lock()
+                    .map_err(|_| anyhow!("cassette lock poisoned"))?;
+                // Entries only grow, so a shorter snapshot is already on disk.
+                if snapshot.entries.len() > *saved {
+                    snapshot.save(&path)?;
+                    *saved = snapshot.entries.len();
+                    tracing::debug!(path = %path.display(), entries = *saved, "cassette updated");
+                }
+                Ok(())
+            })
+            .await
+            .context("cassette writer panicked")??;
+            Ok(response)
+        })
+    }
+}
+
+/// Serves requests from a recorded [`Cassette`] without touching the network.
+///
+/// When several entries match the same request they are served in recorded
+/// order; once all have been used the last one is repeated.  A request with
+/// no matching entry fails with an error naming the endpoint.
+pub struct ReplayTransport {
+    entries: Vec<CassetteEntry>,
+    used: Mutex<Vec<bool>>,
+}
+
+impl ReplayTransport {
+    pub fn new(cassette: Cassette) -> Self {
+        let used = vec![false; cassette.entries.len()];
+        Self {
+            entries: cassette.entries,
+            used: Mutex::new(used),
+        }
+    }
+
+    /// Loads the cassette at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    fn lookup(&self, method: &str, endpoint: &str, f_req: Option<&str>) -> Option<&CassetteEntry> {
+        let mut used = self.used.lock().ok()?;
+        let mut last = None;
+        for (i, entry) in self.entries.iter().enumerate() {
+            if !entry.matches(method, endpoint, f_req) {
+                continue;
+            }
+            if !used[i] {
+                used[i] = true;
+                return Some(entry);
+            }
+            last = Some(entry);
+        }
+        last
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>> {
+        Box::pin(async move {
+            let method = request.method.to_string();
+            let endpoint = strip_query(&request.url);
+            let f_req = request.body.as_deref().and_then(decoded_f_req);
+            let entry = self
+                .lookup(&method, endpoint, f_req.as_deref())
+                .ok_or_else(|| anyhow!("no cassette entry for {method} {endpoint}"))?;
+            Ok(TransportResponse {
+                status: StatusCode::from_u16(entry.status)?,
+                url: entry.url.clone(),
+                headers: header_map(&entry.headers),
+                body: entry.body.clone(),
+            })
+        })
+    }
+}
+
+/// The headers whose values are text, keyed by lower-case name; a repeated
+/// header keeps its last value.
+fn header_strings(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            Some((name.as_str().to_owned(), value.to_str().ok()?.to_owned()))
+        })
+        .collect()
+}
+
+/// Rebuilds a [`HeaderMap`] from recorded headers, skipping any that no
+/// longer parse.
+fn header_map(headers: &BTreeMap<String, String>) -> HeaderMap {
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            Some((
+                HeaderName::from_bytes(name.as_bytes()).ok()?,
+                HeaderValue::from_str(value).ok()?,
+            ))
+        })
+        .collect()
+}
+
+/// Returns `url` without its query string.
+fn strip_query(url: &str) -> &str {
+    url.split_once('?').map_or(url, |(base, _)| base)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn entry(url: &str, f_req: Option<&str>, body: &str) -> CassetteEntry {
+        CassetteEntry {
+            method: "POST".into(),
+            url: url.into(),
+            f_req: f_req.map(str::to_owned),
+            status: 200,
+            headers: BTreeMap::new(),
+            body: body.into(),
+        }
+    }
+
+    #[test]
+    fn decoded_f_req_ignores_at_token() {
+        let body = "f.req=%5Bnull%2C%22x%22%5D&at=AAuQa%3A1700000000000&";
+        assert_eq!(decoded_f_req(body).as_deref(), Some(r#"[null,"x"]"#));
+        assert_eq!(decoded_f_req("u=token"), None);
+    }
+
+    #[test]
+    fn lookup_ignores_query_string_and_serves_duplicates_in_order() {
+        let replay = ReplayTransport::new(Cassette {
+            entries: vec![
+                entry("https://x/Rpc?bl=old", Some("[1]"), "first"),
+                entry("https://x/Rpc?bl=old", Some("[1]"), "second"),
+                entry("https://x/Rpc?bl=old", Some("[2]"), "other"),
+            ],
+        });
+        let hit = |f: &str| {
+            replay
+                .lookup("POST", "https://x/Rpc", Some(f))
+                .map(|e| e.body.clone())
+        };
+        assert_eq!(hit("[1]").as_deref(), Some("first"));
+        assert_eq!(hit("[1]").as_deref(), Some("second"));
+        // Exhausted: the last match repeats.
+        assert_eq!(hit("[1]").as_deref(), Some("second"));
+        assert_eq!(hit("[2]").as_deref(), Some("other"));
+        assert!(hit("[3]").is_none());
+    }
+}
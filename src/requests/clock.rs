@@ -0,0 +1,156 @@
+//! Time and randomness used by [`ApiClient`](super::api::ApiClient), behind
+//! traits so tests can substitute them.
+//!
+//! * [`Clock`] supplies `now()` for cooldowns and refresh intervals, the
+//!   `sleep()` between retries and the `today()` flexible-date searches start
+//!   from.  [`TokioClock`] (the default) follows tokio's
+//!   clock, so `#[tokio::test(start_paused = true)]` fast-forwards back-off.
+//! * [`Rng`] draws the retry jitter and the User-Agent.  [`DefaultRng`] is
+//!   seeded per client, so clients started together do not retry in lockstep;
+//!   [`SeededRng`] repeats the same sequence for a given seed.
+//!
+//! Retries use "full jitter": the delay before retry *n* is uniform in
+//! `[0, min(cap, base · 2ⁿ⁻¹)]`.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDate, Utc};
+use futures::future::BoxFuture;
+
+/// Source of the current time and of delays.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    /// Today's date (UTC), which bounds flexible-date windows.
+    fn today(&self) -> NaiveDate {
+        Utc::now().date_naive()
+    }
+}
+
+/// [`Clock`] backed by `tokio::time`, including its paused test clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioClock;
+
+impl Clock for TokioClock {
+    fn now(&self) -> Instant {
+        tokio::time::Instant::now().into_std()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Source of uniformly distributed random numbers.
+pub trait Rng: Send + Sync {
+    fn next_u64(&self) -> u64;
+}
+
+/// SplitMix64: tiny, fast and good enough for jitter; not for cryptography.
+#[derive(Debug)]
+struct SplitMix64(AtomicU64);
+
+impl SplitMix64 {
+    fn next(&self) -> u64 {
+        let mut z = self
+            .0
+            .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+            .wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// Default [`Rng`], seeded from the process's hash-map randomness, which
+/// differs per instance.
+#[derive(Debug)]
+pub struct DefaultRng(SplitMix64);
+
+impl DefaultRng {
+    pub fn new() -> Self {
+        let seed = RandomState::new().build_hasher().finish();
+        Self(SplitMix64(AtomicU64::new(seed)))
+    }
+}
+
+impl Default for DefaultRng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rng for DefaultRng {
+    fn next_u64(&self) -> u64 {
+        self.0.next()
+    }
+}
+
+/// Deterministic [`Rng`]: the same seed always yields the same sequence.
+#[derive(Debug)]
+pub struct SeededRng(SplitMix64);
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self(SplitMix64(AtomicU64::new(seed)))
+    }
+}
+
+impl Rng for SeededRng {
+    fn next_u64(&self) -> u64 {
+        self.0.next()
+    }
+}
+
+/// Uniform draw from `0..len` (`len` must be non-zero).
+pub(crate) fn below(rng: &dyn Rng, len: usize) -> usize {
+    (rng.next_u64() % len as u64) as usize
+}
+
+/// Full-jitter delay before retry `attempt` (1 for the first retry).
+pub(crate) fn backoff_delay(rng: &dyn Rng, attempt: u32, base_ms: u64, cap_ms: u64) -> Duration {
+    let ceiling = base_ms
+        .saturating_mul(1u64 << attempt.saturating_sub(1).min(30))
+        .min(cap_ms);
+    Duration::from_millis(rng.next_u64() % (ceiling + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_rng_is_reproducible_and_default_rngs_differ() {
+        let a: Vec<u64> = (0..4).map(|_| SeededRng::new(7).next_u64()).collect();
+        assert!(a.windows(2).all(|w| w[0] == w[1]));
+        let rng = SeededRng::new(7);
+        assert_eq!(rng.next_u64(), a[0]);
+        assert_ne!(rng.next_u64(), a[0]);
+
+        let (x, y) = (DefaultRng::new(), DefaultRng::new());
+        assert_ne!((x.next_u64(), x.next_u64()), (y.next_u64(), y.next_u64()));
+    }
+
+    #[test]
+    fn backoff_is_bounded_by_capped_exponential() {
+        let rng = SeededRng::new(1);
+        for attempt in 1..40 {
+            let ceiling = (100u64 << (attempt - 1).min(30)).min(5_000);
+            for _ in 0..20 {
+                let d = backoff_delay(&rng, attempt, 100, 5_000);
+                assert!(d <= Duration::from_millis(ceiling), "{attempt}: {d:?}");
+            }
+        }
+        assert_eq!(backoff_delay(&rng, 3, 0, 0), Duration::ZERO);
+    }
+
+    #[test]
+    fn below_stays_in_range() {
+        let rng = SeededRng::new(3);
+        assert!((0..100).all(|_| below(&rng, 5) < 5));
+    }
+}
@@ -1,21 +1,23 @@
 use anyhow::{anyhow, Result};
 use chrono::NaiveDate;
 
+use crate::airports;
+use crate::parsers::city_response::ResponseInnerBodyParsed;
 use crate::parsers::common::{
     AirlineFilter, FixedFlights, FlightTimes, Location, PlaceType, SortOrder, StopOptions,
-    StopoverDuration, TotalDuration, TravelClass, Travelers,
+    StopoverDuration, TimeWindow, TotalDuration, TravelClass, Travelers,
 };
 use crate::requests::api::ApiClient;
 
-use super::{Config, TripType};
-
-/// Maximum number of airports Google Flights accepts per origin/destination side.
-/// Exceeding this makes the API silently return zero results, so the builder
-/// rejects the overflow up front.
-const MAX_AIRPORTS_PER_SIDE: usize = 7;
+use super::validate::{self, MAX_AIRPORTS_PER_SIDE};
+use super::{Config, LegFilters, TripType};
 
 /// Builder for [`Config`].  Obtain one via [`Config::builder()`].
-#[derive(Default)]
+///
+/// Also the deserialised form of a [`Config`]: every field is optional except
+/// the ones [`build`](Self::build) requires, and unknown fields are rejected.
+#[derive(Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct ConfigBuilder {
     pub(super) departing_date: Option<NaiveDate>,
     pub(super) departure: Vec<Location>,
@@ -23,6 +25,7 @@ pub struct ConfigBuilder {
     pub(super) stop_options: StopOptions,
     pub(super) travel_class: TravelClass,
     pub(super) return_date: Option<NaiveDate>,
+    #[serde(rename = "travellers")]
     pub(super) travelers: Travelers,
     pub(super) departing_times: FlightTimes,
     pub(super) return_times: FlightTimes,
@@ -44,6 +47,8 @@ pub struct ConfigBuilder {
     pub(super) max_price: Option<i32>,
     /// Baggage filter `(carry_on_count, checked_count)`. `None` = no restriction.
     pub(super) baggage: Option<(u8, u8)>,
+    /// Return-leg filters. `None` = same as the outbound leg.
+    pub(super) return_filters: Option<LegFilters>,
 }
 
 impl ConfigBuilder {
@@ -52,11 +57,15 @@ impl ConfigBuilder {
         self
     }
 
-    /// Set the departure to a single airport/city from a string, clearing any previously
-    /// added departure airports.  Resolves IATA codes and city names via the network.
+    /// Set the departure to an airport/city from a string, clearing any previously
+    /// added departure airports.
+    ///
+    /// IATA codes, metropolitan-area codes (`"LON"`, `"NYC"`) and exact city
+    /// names are resolved from the embedded [`airports`] database without a
+    /// network call; a metro code or a city name becomes all of its bundled
+    /// airports (up to 7).  Anything else is looked up via the network.
     pub async fn departure(mut self, location: &str, client: &ApiClient) -> Result<Self> {
-        let loc = get_location(location, client).await?;
-        self.departure = vec![loc];
+        self.departure = get_locations(location, client).await?;
         Ok(self)
     }
 
@@ -69,20 +78,21 @@ impl ConfigBuilder {
         self
     }
 
-    /// Add an additional departure airport/city (up to 7 total).
+    /// Add an additional departure airport/city (up to 7 total), resolved as
+    /// in [`departure`](Self::departure).
     /// Google Flights will search "any of these airports" as the origin.
     pub async fn add_departure(mut self, location: &str, client: &ApiClient) -> Result<Self> {
-        ensure_airport_capacity(self.departure.len(), "departure")?;
-        let loc = get_location(location, client).await?;
-        self.departure.push(loc);
+        ensure_airport_capacity(self.departure.len(), 1, "departure")?;
+        let locs = get_locations(location, client).await?;
+        ensure_airport_capacity(self.departure.len(), locs.len(), "departure")?;
+        self.departure.extend(locs);
         Ok(self)
     }
 
-    /// Set the destination to a single airport/city from a string, clearing any previously
-    /// added destination airports.  Resolves IATA codes and city names via the network.
+    /// Set the destination to an airport/city from a string, clearing any previously
+    /// added destination airports.  Resolved as in [`departure`](Self::departure).
     pub async fn destination(mut self, location: &str, client: &ApiClient) -> Result<Self> {
-        let loc = get_location(location, client).await?;
-        self.destination = vec![loc];
+        self.destination = get_locations(location, client).await?;
         Ok(self)
     }
 
@@ -95,12 +105,65 @@ impl ConfigBuilder {
         self
     }
 
-    /// Add an additional destination airport/city (up to 7 total).
+    /// Add an additional destination airport/city (up to 7 total), resolved
+    /// as in [`departure`](Self::departure).
     /// Google Flights will search "any of these airports" as the destination.
     pub async fn add_destination(mut self, location: &str, client: &ApiClient) -> Result<Self> {
-        ensure_airport_capacity(self.destination.len(), "destination")?;
-        let loc = get_location(location, client).await?;
-        self.destination.push(loc);
+        ensure_airport_capacity(self.destination.len(), 1, "destination")?;
+        let locs = get_locations(location, client).await?;
+        ensure_airport_capacity(self.destination.len(), locs.len(), "destination")?;
+        self.destination.extend(locs);
+        Ok(self)
+    }
+
+    /// Set the departure to every airport within `radius_km` of `location`
+    /// (up to 7, nearest first), clearing any previously added departure
+    /// airports.
+    ///
+    /// `location` is anything [`departure`](Self::departure) accepts.  A
+    /// place the embedded [`airports`] database knows is measured from the
+    /// centre of its bundled airports, and the regional airports Google lists
+    /// around it are added, re-measured from that centre, when the lookup
+    /// succeeds.  Any other place uses Google's nearby-airport list.  Fails if
+    /// no airport is within the radius.
+    pub async fn departure_within(
+        mut self,
+        location: &str,
+        radius_km: f64,
+        client: &ApiClient,
+    ) -> Result<Self> {
+        self.departure = get_locations_within(location, radius_km, client).await?;
+        Ok(self)
+    }
+
+    /// Set the departure to every bundled airport within `radius_km` of a
+    /// point (up to 7, nearest first), without a network lookup.
+    pub fn departure_near(mut self, latitude: f64, longitude: f64, radius_km: f64) -> Result<Self> {
+        self.departure = locations_near(latitude, longitude, radius_km)?;
+        Ok(self)
+    }
+
+    /// Set the destination to every airport within `radius_km` of `location`,
+    /// as in [`departure_within`](Self::departure_within).
+    pub async fn destination_within(
+        mut self,
+        location: &str,
+        radius_km: f64,
+        client: &ApiClient,
+    ) -> Result<Self> {
+        self.destination = get_locations_within(location, radius_km, client).await?;
+        Ok(self)
+    }
+
+    /// Set the destination to every bundled airport within `radius_km` of a
+    /// point, as in [`departure_near`](Self::departure_near).
+    pub fn destination_near(
+        mut self,
+        latitude: f64,
+        longitude: f64,
+        radius_km: f64,
+    ) -> Result<Self> {
+        self.destination = locations_near(latitude, longitude, radius_km)?;
         Ok(self)
     }
 
@@ -134,6 +197,59 @@ impl ConfigBuilder {
         self
     }
 
+    /// Adds an outbound departure window: a [`TimeOfDay`] preset or any
+    /// [`TimeWindow`].  Repeat to allow several; see
+    /// [`FlightTimes::depart_during`].
+    ///
+    /// [`TimeOfDay`]: crate::parsers::common::TimeOfDay
+    pub fn depart_during(mut self, window: impl Into<TimeWindow>) -> Self {
+        self.departing_times = self.departing_times.depart_during(window);
+        self
+    }
+
+    /// Adds an outbound arrival window; see [`depart_during`](Self::depart_during).
+    pub fn arrive_during(mut self, window: impl Into<TimeWindow>) -> Self {
+        self.departing_times = self.departing_times.arrive_during(window);
+        self
+    }
+
+    /// Adds a departure window for the return leg.
+    pub fn return_depart_during(mut self, window: impl Into<TimeWindow>) -> Self {
+        self.return_times = self.return_times.depart_during(window);
+        self
+    }
+
+    /// Adds an arrival window for the return leg.
+    ///
+    /// ```rust
+    /// use gflights::parsers::common::{Location, PlaceType, TimeOfDay};
+    /// use gflights::requests::config::Config;
+    /// # use chrono::NaiveDate;
+    /// # let airport = |code: &str| Location {
+    /// #     loc_identifier: code.to_owned(),
+    /// #     loc_type: PlaceType::Airport,
+    /// #     location_name: None,
+    /// # };
+    ///
+    /// // Out on Monday morning, back by Friday evening.
+    /// let config = Config::builder()
+    ///     .departure_location(airport("LHR"))
+    ///     .destination_location(airport("FRA"))
+    ///     .departing_date(NaiveDate::from_ymd_opt(2030, 5, 6).unwrap())
+    ///     .return_date(NaiveDate::from_ymd_opt(2030, 5, 10).unwrap())
+    ///     .depart_during(TimeOfDay::EarlyMorning)
+    ///     .depart_during(TimeOfDay::Morning)
+    ///     .return_arrive_during(TimeOfDay::Evening.by())
+    ///     .build()?;
+    /// assert!(config.return_times.allows_arrival(20));
+    /// assert!(!config.return_times.allows_arrival(22));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn return_arrive_during(mut self, window: impl Into<TimeWindow>) -> Self {
+        self.return_times = self.return_times.arrive_during(window);
+        self
+    }
+
     pub fn stopover_max(mut self, duration: StopoverDuration) -> Self {
         self.stopover_max = duration;
         self
@@ -227,6 +343,68 @@ impl ConfigBuilder {
         self
     }
 
+    /// Set every per-leg filter of the outbound leg (and of the return leg,
+    /// unless [`return_filters`](Self::return_filters) is also set) from one
+    /// [`LegFilters`], whose `departing_times` becomes the outbound time
+    /// window.
+    pub fn outbound_filters(mut self, filters: LegFilters) -> Self {
+        self.stop_options = filters.stop_options;
+        self.airlines_include = filters.airlines_include;
+        self.airlines_exclude = filters.airlines_exclude;
+        self.connecting_airports = filters.connecting_airports;
+        self.stopover_min = filters.stopover_min;
+        self.stopover_max = filters.stopover_max;
+        self.duration_max = filters.duration_max;
+        self.lower_emissions = filters.lower_emissions;
+        self.departing_times = filters.departing_times;
+        self
+    }
+
+    /// Give the return leg its own filters instead of the outbound ones,
+    /// e.g. non-stop out and any return, or an airline excluded only on the
+    /// way back.  The return leg is priced with these filters by flight
+    /// searches, the calendar graph and the date grid alike.  A restricted
+    /// `filters.departing_times` becomes the return time window (see
+    /// [`return_times`](Self::return_times)); an unrestricted one keeps the
+    /// window already set.
+    ///
+    /// ```rust
+    /// use gflights::parsers::common::{Location, PlaceType, StopOptions};
+    /// use gflights::requests::config::{Config, Direction, LegFilters};
+    /// # use chrono::NaiveDate;
+    /// # let airport = |code: &str| Location {
+    /// #     loc_identifier: code.to_owned(),
+    /// #     loc_type: PlaceType::Airport,
+    /// #     location_name: None,
+    /// # };
+    ///
+    /// let config = Config::builder()
+    ///     .departure_location(airport("LHR"))
+    ///     .destination_location(airport("JFK"))
+    ///     .departing_date(NaiveDate::from_ymd_opt(2030, 5, 1).unwrap())
+    ///     .return_date(NaiveDate::from_ymd_opt(2030, 5, 8).unwrap())
+    ///     .stop_options(StopOptions::NoStop)
+    ///     .return_filters(LegFilters {
+    ///         airlines_exclude: vec!["BA".parse()?],
+    ///         ..LegFilters::default()
+    ///     })
+    ///     .build()?;
+    /// assert!(matches!(config.leg_filters(Direction::Outbound).stop_options, StopOptions::NoStop));
+    /// assert!(matches!(config.leg_filters(Direction::Return).stop_options, StopOptions::All));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn return_filters(mut self, filters: LegFilters) -> Self {
+        if !filters.departing_times.is_unrestricted() {
+            self.return_times = filters.departing_times.clone();
+        }
+        self.return_filters = Some(filters);
+        self
+    }
+
+    /// Builds the [`Config`], failing with
+    /// [`Error::Validation`](crate::Error::Validation) on every problem that
+    /// does not depend on today's date.  Call [`Config::validate`] for the
+    /// full check, dates included.
     pub fn build(self) -> Result<Config> {
         let departing_date = self
             .departing_date
@@ -235,13 +413,7 @@ impl ConfigBuilder {
             Some(_) => TripType::Return,
             None => TripType::OneWay,
         };
-        if self.departure.is_empty() {
-            return Err(anyhow!("At least one departure airport is required"));
-        }
-        if self.destination.is_empty() {
-            return Err(anyhow!("At least one destination airport is required"));
-        }
-        Ok(Config {
+        let config = Config {
             departing_date,
             departure: self.departure,
             destination: self.destination,
@@ -269,19 +441,50 @@ impl ConfigBuilder {
             lower_emissions: self.lower_emissions,
             max_price: self.max_price,
             baggage: self.baggage,
-        })
+            return_filters: self.return_filters,
+        };
+        validate::into_result(validate::check_config(&config, None))?;
+        Ok(config)
+    }
+}
+
+impl TryFrom<ConfigBuilder> for Config {
+    type Error = anyhow::Error;
+
+    fn try_from(builder: ConfigBuilder) -> Result<Self> {
+        builder.build()
     }
 }
 
-pub(super) async fn get_location_pub(
+pub(super) async fn get_locations_pub(
     location: &str,
     client: &ApiClient,
-) -> Result<Location, anyhow::Error> {
-    get_location(location, client).await
+) -> Result<Vec<Location>, anyhow::Error> {
+    get_locations(location, client).await
 }
 
-async fn get_location(location: &str, client: &ApiClient) -> Result<Location, anyhow::Error> {
-    let departure = if location.len() == 3 && location.chars().all(char::is_uppercase) {
+async fn get_locations(location: &str, client: &ApiClient) -> Result<Vec<Location>, anyhow::Error> {
+    if let Some(found) = resolve_offline(location) {
+        return Ok(found);
+    }
+    Ok(vec![client.request_city(location).await?.to_city_list()])
+}
+
+/// Resolves `location` without a network call: an IATA airport code or
+/// metropolitan-area code (three upper-case letters), or the exact name of a
+/// city whose bundled airports are all in one country.  Metro codes and
+/// cities with a metro code expand into every airport of the metro area (up
+/// to [`MAX_AIRPORTS_PER_SIDE`]).  `None` means the input needs Google's city
+/// lookup.
+pub(super) fn resolve_offline(location: &str) -> Option<Vec<Location>> {
+    if location.len() == 3 && location.chars().all(|c| c.is_ascii_uppercase()) {
+        if let Some(airport) = airports::by_iata(location) {
+            return Some(vec![airport.location()]);
+        }
+        let metro = airports::in_metro(location);
+        if !metro.is_empty() {
+            return Some(airport_locations(&metro));
+        }
         if location.starts_with('X') {
             eprintln!(
                 "Warning: '{}' looks like a rail station code. \
@@ -289,21 +492,169 @@ async fn get_location(location: &str, client: &ApiClient) -> Result<Location, an
                 location
             );
         }
-        Location {
+        return Some(vec![Location {
             loc_identifier: location.to_owned(),
             loc_type: PlaceType::Airport,
             location_name: Some(location.to_string()),
+        }]);
+    }
+    let matches = airports::in_city(location);
+    let first = matches.first()?;
+    if !matches.iter().all(|a| a.country == first.country) {
+        return None;
+    }
+    match first.metro {
+        Some(metro) if matches.iter().all(|a| a.metro == Some(metro)) => {
+            Some(airport_locations(&airports::in_metro(metro)))
         }
-    } else {
-        client.request_city(location).await?.to_city_list()
+        _ => Some(airport_locations(&matches)),
+    }
+}
+
+/// Airports within `radius_km` of `location`, nearest first.
+///
+/// A place that resolves offline is measured from the centre of its bundled
+/// airports, and the airports Google lists around it are re-measured from
+/// that centre (see [`remeasured_nearby`]); the lookup is best-effort and a
+/// failure keeps the bundled result.  Any other place is measured from the
+/// place Google's city lookup matched.
+async fn get_locations_within(
+    location: &str,
+    radius_km: f64,
+    client: &ApiClient,
+) -> Result<Vec<Location>> {
+    ensure_radius(radius_km)?;
+    let Some(centre) = offline_centre(location) else {
+        let response = client.request_city(location).await?;
+        let found = response
+            .nearby_airports()
+            .into_iter()
+            .filter(|(_, km)| *km <= radius_km)
+            .collect();
+        return nearest_airports(found)
+            .ok_or_else(|| anyhow!("No airports within {radius_km} km of {location:?}"));
     };
-    Ok(departure)
+    let mut found: Vec<(Location, f64)> = airports::within(centre.0, centre.1, radius_km)
+        .into_iter()
+        .map(|(a, km)| (a.location(), km))
+        .collect();
+    match client.request_city(location).await {
+        Ok(response) => found.extend(
+            remeasured_nearby(&response, centre)
+                .into_iter()
+                .filter(|(_, km)| *km <= radius_km),
+        ),
+        Err(e) => {
+            tracing::warn!(error = %e, %location, "nearby-airport lookup failed; using bundled airports only");
+        }
+    }
+    nearest_airports(found)
+        .ok_or_else(|| anyhow!("No airports within {radius_km} km of {location:?}"))
+}
+
+/// The airports Google lists in `response`, with their distance from
+/// `centre`.
+///
+/// Bundled airports are measured directly.  For the others Google only gives
+/// the distance from the place it matched, so that place is located — the
+/// bundled airport it names, or else the point that fits the distances of
+/// the bundled airports in the list — and its distance from `centre` is
+/// added.  That is an upper bound, so no airport is let in beyond the
+/// radius.  Without a located place the others are left out.
+fn remeasured_nearby(
+    response: &ResponseInnerBodyParsed,
+    centre: (f64, f64),
+) -> Vec<(Location, f64)> {
+    let nearby = response.nearby_airports();
+    let matched = response
+        .result_container
+        .first()
+        .and_then(|r| r.city.airport_code.as_deref())
+        .and_then(airports::by_iata)
+        .map(|a| (a.latitude, a.longitude));
+    let place = matched.or_else(|| {
+        let references: Vec<_> = nearby
+            .iter()
+            .filter_map(|(loc, km)| loc.airport().map(|a| ((a.latitude, a.longitude), *km)))
+            .collect();
+        airports::locate(&references, centre)
+    });
+    let offset = place.map(|p| airports::distance_km(p, centre));
+    nearby
+        .into_iter()
+        .filter_map(|(loc, km)| match loc.airport() {
+            Some(a) => Some((loc, a.distance_km(centre.0, centre.1))),
+            None => offset.map(|offset| (loc, km + offset)),
+        })
+        .collect()
+}
+
+fn locations_near(latitude: f64, longitude: f64, radius_km: f64) -> Result<Vec<Location>> {
+    ensure_radius(radius_km)?;
+    let found = airports::within(latitude, longitude, radius_km)
+        .into_iter()
+        .map(|(a, km)| (a.location(), km))
+        .collect();
+    nearest_airports(found).ok_or_else(|| {
+        anyhow!("No bundled airports within {radius_km} km of ({latitude}, {longitude})")
+    })
+}
+
+/// Centre of the bundled airports `location` resolves to offline.
+fn offline_centre(location: &str) -> Option<(f64, f64)> {
+    let found = resolve_offline(location)?;
+    let airports: Vec<_> = found.iter().filter_map(Location::airport).collect();
+    if airports.is_empty() {
+        return None;
+    }
+    let n = airports.len() as f64;
+    Some((
+        airports.iter().map(|a| a.latitude).sum::<f64>() / n,
+        airports.iter().map(|a| a.longitude).sum::<f64>() / n,
+    ))
+}
+
+/// Sorts candidates by distance, drops repeated airports (keeping the
+/// nearest sighting) and caps the list at [`MAX_AIRPORTS_PER_SIDE`].  `None`
+/// when there are no candidates.
+fn nearest_airports(mut found: Vec<(Location, f64)>) -> Option<Vec<Location>> {
+    found.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let mut nearest: Vec<Location> = Vec::new();
+    for (loc, _) in found {
+        if nearest.len() == MAX_AIRPORTS_PER_SIDE {
+            break;
+        }
+        if !nearest
+            .iter()
+            .any(|n| n.loc_identifier == loc.loc_identifier)
+        {
+            nearest.push(loc);
+        }
+    }
+    (!nearest.is_empty()).then_some(nearest)
+}
+
+fn ensure_radius(radius_km: f64) -> Result<()> {
+    if !(radius_km.is_finite() && radius_km > 0.0) {
+        return Err(anyhow!(
+            "Search radius must be a positive number of kilometres, got {radius_km}"
+        ));
+    }
+    Ok(())
 }
 
-/// Returns an error if a side already holds [`MAX_AIRPORTS_PER_SIDE`] airports,
-/// i.e. adding one more would exceed what Google Flights accepts.
-fn ensure_airport_capacity(current: usize, side: &str) -> Result<()> {
-    if current >= MAX_AIRPORTS_PER_SIDE {
+fn airport_locations(airports: &[&airports::Airport]) -> Vec<Location> {
+    airports
+        .iter()
+        .take(MAX_AIRPORTS_PER_SIDE)
+        .map(|a| a.location())
+        .collect()
+}
+
+/// Returns an error if adding `adding` airports to a side already holding
+/// `current` would exceed the [`MAX_AIRPORTS_PER_SIDE`] Google Flights accepts.
+fn ensure_airport_capacity(current: usize, adding: usize, side: &str) -> Result<()> {
+    if current + adding > MAX_AIRPORTS_PER_SIDE {
         return Err(anyhow!(
             "A maximum of {MAX_AIRPORTS_PER_SIDE} {side} airports is supported"
         ));
@@ -328,11 +679,11 @@ mod tests {
     fn airport_capacity_allows_seven_rejects_eighth() {
         for occupied in 0..MAX_AIRPORTS_PER_SIDE {
             assert!(
-                ensure_airport_capacity(occupied, "departure").is_ok(),
+                ensure_airport_capacity(occupied, 1, "departure").is_ok(),
                 "{occupied} occupied airports should still allow another"
             );
         }
-        let err = ensure_airport_capacity(MAX_AIRPORTS_PER_SIDE, "destination")
+        let err = ensure_airport_capacity(MAX_AIRPORTS_PER_SIDE, 1, "destination")
             .unwrap_err()
             .to_string();
         assert!(err.contains("maximum of 7"), "got: {err}");
@@ -465,4 +816,191 @@ mod tests {
         assert_eq!(cfg.departure[0].loc_identifier, "XRJ");
         assert_eq!(cfg.destination[0].loc_identifier, "XVQ");
     }
+
+    /// IATA codes, metro codes and exact city names resolve from the embedded database;
+    /// unknown codes are kept as typed; fuzzy or ambiguous names are left to
+    /// the network lookup.
+    #[test]
+    fn resolve_offline_uses_embedded_airports() {
+        let lhr = resolve_offline("LHR").unwrap();
+        assert_eq!(lhr[0].location_name.as_deref(), Some("LHR"));
+
+        let london: Vec<_> = resolve_offline("london")
+            .unwrap()
+            .into_iter()
+            .map(|l| l.loc_identifier)
+            .collect();
+        assert_eq!(london, ["LHR", "LGW", "STN", "LTN", "LCY", "SEN"]);
+
+        let nyc: Vec<_> = resolve_offline("NYC")
+            .unwrap()
+            .into_iter()
+            .map(|l| l.loc_identifier)
+            .collect();
+        assert_eq!(nyc, ["JFK", "LGA", "EWR"]);
+        // A city with a metro code expands to the whole metro area, which
+        // for Milan includes Bergamo.
+        assert_eq!(resolve_offline("Milan").unwrap().len(), 3);
+        // A metro code that is also an airport code means the airport.
+        assert_eq!(resolve_offline("BKK").unwrap().len(), 1);
+
+        let unknown = resolve_offline("QQQ").unwrap();
+        assert_eq!(unknown[0].loc_identifier, "QQQ");
+        assert!(matches!(unknown[0].loc_type, PlaceType::Airport));
+
+        // San Jose (US) and San José (Costa Rica) are both bundled.
+        assert!(resolve_offline("San Jose").is_none());
+        assert!(resolve_offline("Londn").is_none());
+    }
+
+    /// Radius candidates are ranked by distance, de-duplicated and capped at
+    /// seven; the point variant uses the embedded database only.
+    #[test]
+    fn nearby_airports_are_ranked_and_capped() {
+        let loc = |code: &str| airports::by_iata(code).unwrap().location();
+        let ranked: Vec<_> = nearest_airports(vec![
+            (loc("STN"), 64.0),
+            (loc("LHR"), 0.0),
+            (loc("LGW"), 41.0),
+            (loc("STN"), 60.0),
+        ])
+        .unwrap()
+        .into_iter()
+        .map(|l| l.loc_identifier)
+        .collect();
+        assert_eq!(ranked, ["LHR", "LGW", "STN"]);
+        assert!(nearest_airports(Vec::new()).is_none());
+
+        let (lat, lon) = offline_centre("London").unwrap();
+        let cfg = Config::builder()
+            .departing_date(future_date(30))
+            .departure_near(lat, lon, 1000.0)
+            .unwrap()
+            .destination_near(40.64, -73.78, 30.0)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(cfg.departure.len(), MAX_AIRPORTS_PER_SIDE);
+        assert_eq!(cfg.destination[0].loc_identifier, "JFK");
+
+        assert!(Config::builder().departure_near(0.0, -30.0, 100.0).is_err());
+        let err = Config::builder()
+            .departure_near(lat, lon, -5.0)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("positive"), "got: {err}");
+    }
+
+    /// Return filters without times keep the return window already set;
+    /// ones with times replace it.
+    #[test]
+    fn return_filters_keep_return_times_unless_they_set_times() {
+        use crate::parsers::common::TimeOfDay;
+
+        let evening = FlightTimes::default().depart_during(TimeOfDay::Evening);
+        let builder = Config::builder()
+            .return_times(evening)
+            .return_filters(LegFilters::default());
+        assert!(builder.return_times.allows_departure(19));
+        assert!(!builder.return_times.allows_departure(9));
+
+        let builder = builder.return_filters(LegFilters {
+            departing_times: FlightTimes::default().depart_during(TimeOfDay::Morning),
+            ..LegFilters::default()
+        });
+        assert!(builder.return_times.allows_departure(9));
+        assert!(!builder.return_times.allows_departure(19));
+    }
+
+    #[tokio::test]
+    async fn radius_search_survives_a_failed_lookup() {
+        use crate::requests::cache::{CacheConfig, MemoryCache};
+
+        // An offline client with an empty cache fails every city lookup.
+        let client = ApiClient::new_offline(MemoryCache::new(), CacheConfig::default());
+        for place in ["London", "LHR"] {
+            let builder = Config::builder()
+                .departure_within(place, 50.0, &client)
+                .await
+                .unwrap();
+            let codes: Vec<_> = builder
+                .departure
+                .iter()
+                .map(|l| l.loc_identifier.as_str())
+                .collect();
+            assert!(codes.contains(&"LHR"), "{place}: {codes:?}");
+        }
+        assert!(Config::builder()
+            .departure_within("Bedřichov", 50.0, &client)
+            .await
+            .is_err());
+    }
+
+    /// Google measures its nearby airports from the place it matched; they
+    /// come back measured from the bundled centre.  The bundled ones are
+    /// measured exactly, the others through the located place.
+    #[test]
+    fn nearby_airports_are_remeasured_from_the_centre() {
+        let centre = offline_centre("London").unwrap();
+        let charing_cross = (51.5080, -0.1281);
+        let southampton = (50.9503, -1.3568);
+        let google_km = |code: &str| {
+            airports::by_iata(code)
+                .unwrap()
+                .distance_km(charing_cross.0, charing_cross.1)
+        };
+        let entry = |code: &str, km: f64| {
+            format!(
+                r#"[[1,"{code} Airport","{code}",null,"/m/{code}","{code}",null,null,"{code}",null,null,1],"{km:.0} km"]"#
+            )
+        };
+        let response = |own: &str| {
+            let listed = [
+                entry("LHR", google_km("LHR")),
+                entry("LGW", google_km("LGW")),
+                entry("STN", google_km("STN")),
+                entry("SOU", airports::distance_km(charing_cross, southampton)),
+            ];
+            let raw = format!(
+                r#"[[[[3,"London, UK","London",null,"/m/04jpl",{own},null,null,null,null,null,3],[{}]]]]"#,
+                listed.join(",")
+            );
+            crate::parsers::common::decode_inner_object::<ResponseInnerBodyParsed>(&raw).unwrap()
+        };
+
+        let found = remeasured_nearby(&response("null"), centre);
+        let km = |code: &str| {
+            found
+                .iter()
+                .find(|(l, _)| l.loc_identifier == code)
+                .map(|(_, km)| *km)
+                .unwrap()
+        };
+        let lgw = airports::by_iata("LGW").unwrap();
+        assert_eq!(km("LGW"), lgw.distance_km(centre.0, centre.1));
+        // Never nearer than it really is, and off by little more than the
+        // rounding of Google's distances.
+        let sou = airports::distance_km(southampton, centre);
+        let bound = airports::distance_km(charing_cross, southampton)
+            + airports::distance_km(charing_cross, centre);
+        assert!((sou - 1.0..=bound + 1.0).contains(&km("SOU")), "{found:?}");
+
+        // A matched airport is placed by its code; without references the
+        // others can't be placed and are left out.
+        let lhr = airports::by_iata("LHR").unwrap();
+        let own = remeasured_nearby(&response(r#""LHR""#), centre);
+        assert!(
+            own.iter()
+                .any(|(l, km)| l.loc_identifier == "LHR"
+                    && *km == lhr.distance_km(centre.0, centre.1))
+        );
+        let bare = format!(
+            r#"[[[[3,"London, UK","London",null,"/m/04jpl",null,null,null,null,null,null,3],[{}]]]]"#,
+            entry("SOU", 100.0)
+        );
+        let bare =
+            crate::parsers::common::decode_inner_object::<ResponseInnerBodyParsed>(&bare).unwrap();
+        assert!(remeasured_nearby(&bare, centre).is_empty());
+    }
 }
@@ -1,9 +1,8 @@
 use std::fmt;
 
-use clap::ValueEnum;
-
 /// Result currency for flight search.
-#[derive(Debug, Clone, Default, ValueEnum)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 pub enum Currency {
     AlbanianLek,
     AlgerianDinar,
@@ -159,6 +158,82 @@ impl fmt::Display for Currency {
 }
 
 impl Currency {
+    /// Every supported currency, in declaration order.  A test keeps this in
+    /// step with the enum (via `clap::ValueEnum` when the `cli` feature is on).
+    pub const ALL: [Currency; 71] = [
+        Currency::AlbanianLek,
+        Currency::AlgerianDinar,
+        Currency::ArgentinePeso,
+        Currency::ArmenianDram,
+        Currency::ArubanFlorin,
+        Currency::AustralianDollar,
+        Currency::AzerbaijaniManat,
+        Currency::BahamianDollar,
+        Currency::BahrainiDinar,
+        Currency::BelarusianRouble,
+        Currency::BermudianDollar,
+        Currency::BosniaHerzegovinaMark,
+        Currency::BrazilianReal,
+        Currency::BritishPound,
+        Currency::BulgarianLev,
+        Currency::CanadianDollar,
+        Currency::CFPFranc,
+        Currency::ChileanPeso,
+        Currency::ChineseYuan,
+        Currency::ColombianPeso,
+        Currency::CostaRicanColon,
+        Currency::CubanPeso,
+        Currency::CzechKoruna,
+        Currency::DanishKrone,
+        Currency::DominicanPeso,
+        Currency::EgyptianPound,
+        Currency::Euro,
+        Currency::GeorgianLari,
+        Currency::HongKongDollar,
+        Currency::HungarianForint,
+        Currency::IcelandicKrona,
+        Currency::IndianRupee,
+        Currency::IndonesianRupiah,
+        Currency::IranianRial,
+        Currency::IsraeliNewShekel,
+        Currency::JamaicanDollar,
+        Currency::JapaneseYen,
+        Currency::JordanianDinar,
+        Currency::KazakhstaniTenge,
+        Currency::KuwaitiDinar,
+        Currency::LebanesePound,
+        Currency::MacedonianDenar,
+        Currency::MalaysianRinggit,
+        Currency::MexicanPeso,
+        Currency::MoldovanLeu,
+        Currency::MoroccanDirham,
+        Currency::NewTaiwanDollar,
+        Currency::NewZealandDollar,
+        Currency::NorwegianKrone,
+        Currency::OmaniRial,
+        Currency::PakistaniRupee,
+        Currency::PanamanianBalboa,
+        Currency::PeruvianSol,
+        Currency::PhilippinePeso,
+        Currency::PolishZloty,
+        Currency::QatariRiyal,
+        Currency::RomanianLeu,
+        Currency::RussianRouble,
+        Currency::SaudiRiyal,
+        Currency::SerbianDinar,
+        Currency::SingaporeDollar,
+        Currency::SouthAfricanRand,
+        Currency::SouthKoreanWon,
+        Currency::SwedishKrona,
+        Currency::SwissFranc,
+        Currency::ThaiBaht,
+        Currency::TurkishLira,
+        Currency::UkrainianHryvnia,
+        Currency::UnitedArabEmiratesDirham,
+        Currency::USDollar,
+        Currency::VietnameseDong,
+    ];
+
     /// Parse a currency from its ISO-4217 code (case-insensitive), e.g. `"USD"`.
     ///
     /// This is the reverse of [`Display`](std::fmt::Display): every variant
@@ -166,10 +241,7 @@ impl Currency {
     /// rendered code.
     pub fn from_code(code: &str) -> Option<Self> {
         let up = code.trim().to_uppercase();
-        <Self as ValueEnum>::value_variants()
-            .iter()
-            .find(|c| c.to_string() == up)
-            .cloned()
+        Self::ALL.iter().find(|c| c.to_string() == up).cloned()
     }
 }
 
@@ -218,6 +290,22 @@ mod tests {
         assert!(Currency::from_code("ZZZ").is_none());
     }
 
+    #[test]
+    fn from_code_round_trips_every_currency() {
+        for currency in Currency::ALL {
+            let code = currency.to_string();
+            assert_eq!(Currency::from_code(&code).unwrap().to_string(), code);
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn all_lists_every_variant() {
+        use clap::ValueEnum;
+        let codes = |list: &[Currency]| list.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(codes(&Currency::ALL), codes(Currency::value_variants()));
+    }
+
     #[test]
     fn from_str_errors_on_unknown() {
         assert!(<Currency as FromStr>::from_str("USD").is_ok());
@@ -16,7 +16,8 @@ use chrono::NaiveDate;
 ///
 /// All fields except `origin`, `outbound_date`, and `return_date` have sensible
 /// defaults via [`Default`].
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct DealConfig {
     /// Origin airport(s) or city. Airports use `PlaceType::Airport`; cities use
     /// `PlaceType::City`.
@@ -7,7 +7,7 @@ use crate::parsers::common::{Location, PlaceType, TravelClass, Travelers};
 // ---------------------------------------------------------------------------
 
 /// How long the trip should be.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum ExploreDuration {
     /// A weekend trip (Saturday + Sunday).
     Weekend,
@@ -29,7 +29,7 @@ impl ExploreDuration {
 }
 
 /// A calendar month (1–12) for filtering explore results.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct ExploreDate {
     /// Month number, 1 = January … 12 = December.
     pub month: u8,
@@ -40,7 +40,7 @@ pub struct ExploreDate {
 // ---------------------------------------------------------------------------
 
 /// Geographic bounding box for the explore map view.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct MapBounds {
     /// South-west corner `(lat, lng)`.
     pub sw: (f64, f64),
@@ -271,7 +271,8 @@ pub fn resolve_destination(raw: &str) -> anyhow::Result<Location> {
 ///
 /// Build directly or use struct-literal syntax; all fields except `origin`
 /// and `travellers` have sensible defaults via `Default`.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct ExploreConfig {
     /// One or more origin airports / cities.
     pub origin: Vec<Location>,
@@ -0,0 +1,517 @@
+//! Google Flights links decoded back into search configs — the inverse of
+//! [`Config::to_flight_url`].
+//!
+//! The link's `tfs` parameter is a base64 [`ItineraryUrl`] protobuf.  It
+//! carries the legs (airports or cities, dates, per-leg stop, airline,
+//! connection, layover, duration, emissions and time-of-day limits), the
+//! travellers, the cabin class, the max price, baggage and sort order, and
+//! the trip type.  A return link whose legs have different filters decodes
+//! with [`Config::return_filters`] set; the other query parameters (`hl`,
+//! `curr`, …) are ignored.  Time windows come back as the single hour range
+//! the link carries (see [`Config::filters_missing_from_url`]).
+//!
+//! ```rust
+//! use gflights::requests::config::{parse_flight_url, Config, UrlSearch};
+//!
+//! let link = "https://www.google.com/travel/flights?tfs=Gh4SCjIwMjQtMDUtMDFqBwgBEgNNWFByBwgBEgNDREdCAQFIAZgBAg&hl=en";
+//! let config = Config::from_flight_url(link)?;
+//! assert_eq!(config.departure[0].loc_identifier, "MXP");
+//! assert_eq!(config.to_flight_url(), link.trim_end_matches("&hl=en"));
+//!
+//! match parse_flight_url(link)? {
+//!     UrlSearch::Flights(config) => assert!(config.return_date.is_none()),
+//!     UrlSearch::MultiCity(_) => unreachable!(),
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::NaiveDate;
+
+use super::{Config, LegFilters, MultiCityConfig};
+use crate::parsers::common::{
+    AirlineFilter, FlightTimes, Location, PlaceType, SortOrder, StopOptions, StopoverDuration,
+    TotalDuration, TravelClass, Travelers,
+};
+use crate::protos::urls::{
+    ItineraryUrl, Leg, LocType, SortOrder as SortOrderProto, TripType as TripTypeProto,
+};
+
+/// A search decoded from a Google Flights link.
+// Built once per link, so the size difference between variants is not worth
+// a `Box` in every caller's match.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum UrlSearch {
+    /// A one-way or return search.
+    Flights(Config),
+    MultiCity(MultiCityConfig),
+}
+
+/// Decodes a Google Flights link (or a bare `tfs` value) into the search it
+/// describes, whatever its trip type.
+///
+/// # Errors
+/// If the link has no `tfs` parameter, the value does not decode, or the
+/// decoded search fails the builder's checks.
+pub fn parse_flight_url(url: &str) -> Result<UrlSearch> {
+    let itinerary = ItineraryUrl::from_flight_url(url)?;
+    if is_multi_city(&itinerary) {
+        multi_city_from_itinerary(&itinerary).map(UrlSearch::MultiCity)
+    } else {
+        config_from_itinerary(&itinerary).map(UrlSearch::Flights)
+    }
+}
+
+impl Config {
+    /// Decodes a one-way or return search from a Google Flights link (or a
+    /// bare `tfs` value).  See [`parse_flight_url`] for links that may hold a
+    /// multi-city search.
+    pub fn from_flight_url(url: &str) -> Result<Self> {
+        match parse_flight_url(url)? {
+            UrlSearch::Flights(config) => Ok(config),
+            UrlSearch::MultiCity(_) => {
+                bail!("link holds a multi-city search; use MultiCityConfig::from_flight_url")
+            }
+        }
+    }
+}
+
+impl MultiCityConfig {
+    /// Decodes a multi-city search from a Google Flights link (or a bare `tfs`
+    /// value).
+    pub fn from_flight_url(url: &str) -> Result<Self> {
+        match parse_flight_url(url)? {
+            UrlSearch::MultiCity(config) => Ok(config),
+            UrlSearch::Flights(_) => {
+                bail!("link holds a one-way or return search; use Config::from_flight_url")
+            }
+        }
+    }
+}
+
+/// Links from the browser always set the trip type.  Two legs are read as a
+/// return trip only if they mirror each other, so an open-jaw link marked
+/// round trip (LHR→JFK, BOS→LHR) decodes as the multi-city search it is,
+/// not as a JFK→LHR return.
+fn is_multi_city(itinerary: &ItineraryUrl) -> bool {
+    match TripTypeProto::try_from(itinerary.trip_type) {
+        Ok(TripTypeProto::MultiCity) => true,
+        Ok(TripTypeProto::OneWay) => false,
+        _ => match itinerary.legs.as_slice() {
+            [_] => false,
+            [out, back] => out.departure != back.arrival || out.arrival != back.departure,
+            _ => true,
+        },
+    }
+}
+
+fn config_from_itinerary(itinerary: &ItineraryUrl) -> Result<Config> {
+    let (outbound, inbound) = match itinerary.legs.as_slice() {
+        [outbound] => (outbound, None),
+        [outbound, inbound] => (outbound, Some(inbound)),
+        legs => bail!("expected 1 or 2 legs, link has {}", legs.len()),
+    };
+    let filters = leg_filters(outbound)?;
+    let mut builder = Config::builder()
+        .departing_date(leg_date(outbound, 0)?)
+        .travelers(travellers(itinerary)?)
+        .travel_class(travel_class(itinerary.class))
+        .sort_order(sort_order(itinerary.sort_order)?)
+        .stop_options(filters.stop_options)
+        .airlines_include(filters.airlines_include)
+        .airlines_exclude(filters.airlines_exclude)
+        .connecting_airports(filters.connecting_airports)
+        .stopover_min(filters.stopover_min)
+        .stopover_max(filters.stopover_max)
+        .duration_max(filters.duration_max)
+        .lower_emissions(filters.lower_emissions)
+        .departing_times(filters.departing_times);
+    if let Some(price) = itinerary.max_price {
+        builder = builder.max_price(price);
+    }
+    if let Some((carry_on, checked)) = baggage(itinerary)? {
+        builder = builder.baggage(carry_on, checked);
+    }
+    builder.departure = locations(&outbound.departure);
+    builder.destination = locations(&outbound.arrival);
+    if let Some(inbound) = inbound {
+        builder = builder
+            .return_date(leg_date(inbound, 1)?)
+            .return_times(flight_times(inbound));
+        let same_filters = inbound.stops == outbound.stops
+            && inbound.airlines == outbound.airlines
+            && inbound.excluded_airlines == outbound.excluded_airlines
+            && inbound.connecting_airports == outbound.connecting_airports
+            && inbound.lower_emissions == outbound.lower_emissions
+            && inbound.min_stopover_minutes == outbound.min_stopover_minutes
+            && inbound.max_stopover_minutes == outbound.max_stopover_minutes
+            && inbound.max_duration_minutes == outbound.max_duration_minutes;
+        if !same_filters {
+            builder = builder.return_filters(leg_filters(inbound)?);
+        }
+    }
+    builder.build()
+}
+
+fn multi_city_from_itinerary(itinerary: &ItineraryUrl) -> Result<MultiCityConfig> {
+    let mut builder = MultiCityConfig::builder()
+        .travellers(travellers(itinerary)?)
+        .travel_class(travel_class(itinerary.class))
+        .sort_order(sort_order(itinerary.sort_order)?);
+    if let Some(price) = itinerary.max_price {
+        builder = builder.max_price(price);
+    }
+    if let Some((carry_on, checked)) = baggage(itinerary)? {
+        builder = builder.baggage(carry_on, checked);
+    }
+    for (i, leg) in itinerary.legs.iter().enumerate() {
+        builder = builder.add_leg_locations_with_filters(
+            locations(&leg.departure),
+            locations(&leg.arrival),
+            leg_date(leg, i)?,
+            leg_filters(leg)?,
+        );
+    }
+    builder.build()
+}
+
+fn leg_date(leg: &Leg, index: usize) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(&leg.date, "%Y-%m-%d")
+        .with_context(|| format!("leg {index} has an invalid date {:?}", leg.date))
+}
+
+fn locations(protos: &[crate::protos::urls::Location]) -> Vec<Location> {
+    protos
+        .iter()
+        .map(|proto| {
+            let loc_type = match LocType::try_from(proto.r#type) {
+                Ok(LocType::Airport) => PlaceType::Airport,
+                Ok(LocType::City) => PlaceType::City,
+                _ => PlaceType::Unspecified,
+            };
+            Location {
+                location_name: (loc_type == PlaceType::Airport).then(|| proto.place_name.clone()),
+                loc_identifier: proto.place_name.clone(),
+                loc_type,
+            }
+        })
+        .collect()
+}
+
+/// Inverse of [`Travelers::to_proto_vec`].
+fn travellers(itinerary: &ItineraryUrl) -> Result<Travelers> {
+    if itinerary.travellers.is_empty() {
+        return Ok(Travelers::default());
+    }
+    let count = |code: i32| itinerary.travellers.iter().filter(|&&t| t == code).count() as i32;
+    Travelers::new(vec![count(1), count(2), count(4), count(3)])
+}
+
+fn travel_class(code: i32) -> TravelClass {
+    if code == 0 {
+        TravelClass::default()
+    } else {
+        TravelClass::from(code)
+    }
+}
+
+fn sort_order(code: Option<i32>) -> Result<SortOrder> {
+    Ok(match code.map(SortOrderProto::try_from) {
+        None | Some(Ok(SortOrderProto::UnspecifiedSort | SortOrderProto::Best)) => SortOrder::Best,
+        Some(Ok(SortOrderProto::Price)) => SortOrder::Price,
+        Some(Ok(SortOrderProto::Duration)) => SortOrder::Duration,
+        Some(Ok(SortOrderProto::DepartureTime)) => SortOrder::DepartureTime,
+        Some(Ok(SortOrderProto::ArrivalTime)) => SortOrder::ArrivalTime,
+        Some(Err(_)) => bail!("unknown sort order {} in link", code.unwrap_or_default()),
+    })
+}
+
+fn baggage(itinerary: &ItineraryUrl) -> Result<Option<(u8, u8)>> {
+    itinerary
+        .baggage
+        .as_ref()
+        .map(|b| {
+            Ok((
+                u8::try_from(b.carry_on).context("link has an invalid carry-on bag count")?,
+                u8::try_from(b.checked).context("link has an invalid checked bag count")?,
+            ))
+        })
+        .transpose()
+}
+
+fn airline_filters(codes: &[String]) -> Result<Vec<AirlineFilter>> {
+    codes
+        .iter()
+        .map(|code| code.parse::<AirlineFilter>())
+        .collect::<Result<Vec<_>>>()
+        .context("link has an invalid airline filter")
+}
+
+fn leg_filters(leg: &Leg) -> Result<LegFilters> {
+    let stop_options = match leg.stops {
+        None => StopOptions::All,
+        Some(0) => StopOptions::NoStop,
+        Some(1) => StopOptions::OneOrLess,
+        Some(2) => StopOptions::TwoOrLess,
+        Some(other) => return Err(anyhow!("unknown stop option {other} in link")),
+    };
+    Ok(LegFilters {
+        stop_options,
+        airlines_include: airline_filters(&leg.airlines)?,
+        airlines_exclude: airline_filters(&leg.excluded_airlines)?,
+        connecting_airports: leg.connecting_airports.clone(),
+        stopover_min: leg
+            .min_stopover_minutes
+            .map_or(StopoverDuration::UNLIMITED, StopoverDuration::Minutes),
+        stopover_max: leg
+            .max_stopover_minutes
+            .map_or(StopoverDuration::UNLIMITED, StopoverDuration::Minutes),
+        duration_max: leg
+            .max_duration_minutes
+            .map_or(TotalDuration::UNLIMITED, TotalDuration::Minutes),
+        lower_emissions: leg.lower_emissions,
+        departing_times: flight_times(leg),
+    })
+}
+
+fn flight_times(leg: &Leg) -> FlightTimes {
+    FlightTimes::new(
+        leg.min_hour_departure.unwrap_or(0),
+        leg.max_hour_departure.unwrap_or(0),
+        leg.min_hour_arrival.unwrap_or(0),
+        leg.max_hour_arrival.unwrap_or(0),
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::requests::config::ConfigBuilder;
+
+    fn airport(code: &str) -> Location {
+        Location {
+            loc_identifier: code.to_owned(),
+            loc_type: PlaceType::Airport,
+            location_name: Some(code.to_owned()),
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn return_search_round_trips_through_link() {
+        let config = Config::builder()
+            .departing_date(date("2026-09-01"))
+            .return_date(date("2026-09-08"))
+            .departure_location(airport("LUX"))
+            .destination_location(airport("ZRH"))
+            .travelers(Travelers::new(vec![2, 1, 1, 0]).unwrap())
+            .travel_class(TravelClass::Business)
+            .stop_options(StopOptions::OneOrLess)
+            .add_airline_include(AirlineFilter::Alliance(
+                crate::parsers::common::Alliance::StarAlliance,
+            ))
+            .add_airline_include("LX".parse().unwrap())
+            .stopover_max(StopoverDuration::Minutes(180))
+            .duration_max(TotalDuration::Minutes(600))
+            .departing_times(FlightTimes::new(7, 12, 0, 0))
+            .return_times(FlightTimes::new(17, 23, 0, 0))
+            .build()
+            .unwrap();
+
+        let back = Config::from_flight_url(&config.to_flight_url()).unwrap();
+
+        assert_eq!(back.to_encoded(), config.to_encoded());
+        assert_eq!(back.departure, config.departure);
+        assert_eq!(back.return_date, Some(date("2026-09-08")));
+        assert_eq!(back.travellers.children, 1);
+        assert_eq!(back.travellers.infant_on_lap, 1);
+        assert!(matches!(back.travel_class, TravelClass::Business));
+        assert!(matches!(back.stop_options, StopOptions::OneOrLess));
+        assert_eq!(back.airlines_include, config.airlines_include);
+        assert_eq!(back.return_times.get_departure_hour_min(), Some(17));
+        assert!(back.return_filters.is_none());
+    }
+
+    #[test]
+    fn per_direction_filters_round_trip_through_link() {
+        let config = Config::builder()
+            .departing_date(date("2026-09-01"))
+            .return_date(date("2026-09-08"))
+            .departure_location(airport("LUX"))
+            .destination_location(airport("JFK"))
+            .stop_options(StopOptions::NoStop)
+            .return_filters(LegFilters {
+                airlines_include: vec!["LH".parse().unwrap()],
+                departing_times: FlightTimes::new(17, 23, 0, 0),
+                ..LegFilters::default()
+            })
+            .build()
+            .unwrap();
+
+        let back = Config::from_flight_url(&config.to_flight_url()).unwrap();
+
+        assert_eq!(back.to_encoded(), config.to_encoded());
+        assert!(matches!(back.stop_options, StopOptions::NoStop));
+        assert!(back.airlines_include.is_empty());
+        let inbound = back.return_filters.expect("return leg has its own filters");
+        assert!(matches!(inbound.stop_options, StopOptions::All));
+        assert_eq!(inbound.airlines_include, ["LH".parse().unwrap()]);
+        assert_eq!(back.return_times.get_departure_hour_min(), Some(17));
+    }
+
+    /// A one-way LUX→JFK search with `set` applied, decoded back from its
+    /// link.
+    fn round_trip(set: impl FnOnce(ConfigBuilder) -> ConfigBuilder) -> Config {
+        let config = set(Config::builder()
+            .departing_date(date("2026-09-01"))
+            .departure_location(airport("LUX"))
+            .destination_location(airport("JFK")))
+        .build()
+        .unwrap();
+        assert!(config.filters_missing_from_url().is_empty());
+        let back = Config::from_flight_url(&config.to_flight_url()).unwrap();
+        assert_eq!(back.to_encoded(), config.to_encoded());
+        back
+    }
+
+    #[test]
+    fn excluded_airlines_round_trip_through_link() {
+        let back = round_trip(|b| b.airlines_exclude(vec!["FR".parse().unwrap()]));
+        assert_eq!(back.airlines_exclude, ["FR".parse().unwrap()]);
+    }
+
+    #[test]
+    fn connecting_airports_round_trip_through_link() {
+        let back = round_trip(|b| b.connecting_airports(vec!["DUB".to_owned()]));
+        assert_eq!(back.connecting_airports, ["DUB"]);
+    }
+
+    #[test]
+    fn minimum_layover_round_trips_through_link() {
+        let back = round_trip(|b| b.stopover_min(StopoverDuration::Minutes(90)));
+        assert_eq!(back.stopover_min.to_option(), Some(90));
+    }
+
+    #[test]
+    fn lower_emissions_round_trips_through_link() {
+        let back = round_trip(|b| b.lower_emissions(true));
+        assert!(back.lower_emissions);
+    }
+
+    #[test]
+    fn max_price_round_trips_through_link() {
+        let back = round_trip(|b| b.max_price(450));
+        assert_eq!(back.max_price, Some(450));
+    }
+
+    #[test]
+    fn baggage_round_trips_through_link() {
+        let back = round_trip(|b| b.baggage(1, 2));
+        assert_eq!(back.baggage, Some((1, 2)));
+    }
+
+    #[test]
+    fn sort_order_round_trips_through_link() {
+        let back = round_trip(|b| b.sort_order(SortOrder::Duration));
+        assert!(matches!(back.sort_order, SortOrder::Duration));
+        let back = round_trip(|b| b);
+        assert!(matches!(back.sort_order, SortOrder::Best));
+    }
+
+    #[test]
+    fn return_leg_filters_round_trip_through_link() {
+        let config = Config::builder()
+            .departing_date(date("2026-09-01"))
+            .return_date(date("2026-09-08"))
+            .departure_location(airport("LUX"))
+            .destination_location(airport("JFK"))
+            .return_filters(LegFilters {
+                airlines_exclude: vec!["FR".parse().unwrap()],
+                connecting_airports: vec!["DUB".to_owned()],
+                stopover_min: StopoverDuration::Minutes(90),
+                lower_emissions: true,
+                ..LegFilters::default()
+            })
+            .build()
+            .unwrap();
+
+        let back = Config::from_flight_url(&config.to_flight_url()).unwrap();
+
+        assert!(back.airlines_exclude.is_empty());
+        let inbound = back.return_filters.expect("return leg has its own filters");
+        assert_eq!(inbound.airlines_exclude, ["FR".parse().unwrap()]);
+        assert_eq!(inbound.connecting_airports, ["DUB"]);
+        assert_eq!(inbound.stopover_min.to_option(), Some(90));
+        assert!(inbound.lower_emissions);
+    }
+
+    #[test]
+    fn city_links_decode_to_city_locations() {
+        // New York → London return, first class, two adults and three children
+        // of different kinds (see `protos` tests).
+        let tfs = "GisSCjIwMjQtMTEtMDYoAGoNCAISCS9tLzAyXzI4NnIMCAISCC9tLzA0anBsGisSCjIwMjQtMTEtMTMoAGoMCAISCC9tLzA0anBscg0IAhIJL20vMDJfMjg2QgUBAQIDBEgEmAEB";
+        let config = Config::from_flight_url(tfs).unwrap();
+        assert_eq!(config.departure[0].loc_type, PlaceType::City);
+        assert_eq!(config.destination[0].loc_identifier, "/m/04jpl");
+        assert!(matches!(config.stop_options, StopOptions::NoStop));
+        assert!(matches!(config.travel_class, TravelClass::First));
+        assert_eq!(config.travellers.adults, 2);
+        assert_eq!(config.get_diff_days(), Some(7));
+    }
+
+    #[test]
+    fn multi_city_links_decode_to_multi_city_config() {
+        let config = MultiCityConfig::builder()
+            .add_leg_locations(
+                vec![airport("LUX")],
+                vec![airport("FCO")],
+                date("2026-09-10"),
+            )
+            .add_leg_locations(
+                vec![airport("FCO")],
+                vec![airport("MAD")],
+                date("2026-09-13"),
+            )
+            .add_leg_locations(
+                vec![airport("MAD")],
+                vec![airport("LUX")],
+                date("2026-09-17"),
+            )
+            .build()
+            .unwrap();
+        let link = config.to_flight_url();
+
+        let UrlSearch::MultiCity(back) = parse_flight_url(&link).unwrap() else {
+            panic!("expected a multi-city search");
+        };
+        assert_eq!(back.legs.len(), 3);
+        assert_eq!(back.legs[1].from, vec![airport("FCO")]);
+        assert_eq!(back.legs[2].date, date("2026-09-17"));
+        assert!(Config::from_flight_url(&link).is_err());
+    }
+
+    #[test]
+    fn open_jaw_round_trip_links_decode_as_multi_city() {
+        let config = Config::builder()
+            .departing_date(date("2026-09-01"))
+            .return_date(date("2026-09-08"))
+            .departure_location(airport("LHR"))
+            .destination_location(airport("JFK"))
+            .build()
+            .unwrap();
+        let mut itinerary = ItineraryUrl::from(&config);
+        itinerary.legs[1].departure[0].place_name = "BOS".to_owned();
+
+        let UrlSearch::MultiCity(back) = parse_flight_url(&itinerary.to_encoded()).unwrap() else {
+            panic!("expected a multi-city search");
+        };
+        assert_eq!(back.legs[0].to, vec![airport("JFK")]);
+        assert_eq!(back.legs[1].from, vec![airport("BOS")]);
+        assert_eq!(back.legs[1].to, vec![airport("LHR")]);
+    }
+}
@@ -7,13 +7,16 @@ use parsers::common::{
     AirlineFilter, FlightTimes, Location, PlaceType, SortOrder, StopOptions, StopoverDuration,
     TotalDuration, TravelClass, Travelers,
 };
-use protos::urls::{ItineraryUrl, Leg};
+use protos::urls::{Baggage, ItineraryUrl, Leg};
 
 mod builder;
 mod currency;
 pub mod deals;
 pub mod explore;
+mod from_url;
 pub mod multi_city;
+pub mod saved;
+pub mod validate;
 
 pub use builder::ConfigBuilder;
 pub use currency::Currency;
@@ -21,7 +24,10 @@ pub use deals::{DealConfig, DealResult};
 pub use explore::{
     ExploreConfig, ExploreDate, ExploreDuration, ExploreResult, Interest, MapBounds,
 };
+pub use from_url::{parse_flight_url, UrlSearch};
 pub use multi_city::{LegFilters, MultiCityConfig, MultiCityConfigBuilder, MultiCityLeg};
+pub use saved::{SavedConfig, SavedSearch};
+pub use validate::{Diagnostic, Severity};
 
 /// The `TripType` enum is used to specify the type of trip.
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
@@ -32,12 +38,29 @@ pub enum TripType {
     MultiCity,
 }
 
+/// One direction of a return trip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Outbound,
+    Return,
+}
+
 /// The `Config` struct is used to specify the options for a flight search.
 ///
 /// `departure` and `destination` each hold 1–7 airports.  When multiple
 /// airports are supplied Google Flights treats them as "any of these" for
 /// that end of the journey (e.g. all London-area airports as the origin).
-#[derive(Debug, Clone, Default)]
+///
+/// The per-leg filters (`stop_options`, the airline and connecting-airport
+/// lists, layover and duration limits, `lower_emissions`) apply to both legs
+/// of a return trip unless [`return_filters`](Self::return_filters) gives the
+/// return leg its own set; [`leg_filters`](Self::leg_filters) returns the set
+/// in effect for each direction.
+///
+/// Deserialising a `Config` goes through [`ConfigBuilder::build`], so it is
+/// checked the same way; see [`saved`] for the versioned document form.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "ConfigBuilder")]
 pub struct Config {
     pub departing_date: NaiveDate,
     /// One to seven departure airports / city identifiers.
@@ -54,7 +77,10 @@ pub struct Config {
     /// Minimum layover / connection time (default: no minimum).
     pub stopover_min: StopoverDuration,
     pub duration_max: TotalDuration,
+    /// Derived from `return_date`; not serialised.
+    #[serde(skip_serializing)]
     pub trip_type: TripType,
+    #[serde(skip_serializing)]
     pub fixed_flights: FixedFlights,
     /// Sort order applied to the search results.
     pub sort_order: SortOrder,
@@ -74,6 +100,11 @@ pub struct Config {
     /// Baggage filter `(carry_on_count, checked_count)` (outer itinerary array position \[10\]).
     /// `None` = no restriction.
     pub baggage: Option<(u8, u8)>,
+    /// Filters for the return leg only.  `None` = the return leg uses the
+    /// same filters as the outbound leg.  Its `departing_times` is not used:
+    /// the return leg's time window is always `return_times`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_filters: Option<LegFilters>,
 }
 
 impl Config {
@@ -129,6 +160,49 @@ impl Config {
             lower_emissions: false,
             max_price: None,
             baggage: None,
+            return_filters: None,
+        }
+    }
+
+    /// The time window for the flights a search with this config lists:
+    /// [`return_times`](Self::return_times) once the outbound flight of a
+    /// round trip is fixed in [`fixed_flights`](Self::fixed_flights), since
+    /// Google then lists return flights, and
+    /// [`departing_times`](Self::departing_times) otherwise.  Pass it to
+    /// [`get_flights_filtered`](crate::parsers::flight_response::FlightResponseContainer::get_flights_filtered).
+    pub fn listed_leg_times(&self) -> &FlightTimes {
+        let outbound_fixed = self.fixed_flights.maybe_get_nth_flight_info(0).is_some();
+        if self.return_date.is_some() && outbound_fixed {
+            &self.return_times
+        } else {
+            &self.departing_times
+        }
+    }
+
+    /// The per-leg filters in effect for one direction of the trip: the
+    /// shared fields, or [`return_filters`](Self::return_filters) for the
+    /// return leg when set.  `departing_times` holds the direction's time
+    /// window (`departing_times` or `return_times`).
+    pub fn leg_filters(&self, direction: Direction) -> LegFilters {
+        match (direction, &self.return_filters) {
+            (Direction::Return, Some(filters)) => LegFilters {
+                departing_times: self.return_times.clone(),
+                ..filters.clone()
+            },
+            (direction, _) => LegFilters {
+                stop_options: self.stop_options,
+                airlines_include: self.airlines_include.clone(),
+                airlines_exclude: self.airlines_exclude.clone(),
+                connecting_airports: self.connecting_airports.clone(),
+                stopover_min: self.stopover_min,
+                stopover_max: self.stopover_max,
+                duration_max: self.duration_max.clone(),
+                lower_emissions: self.lower_emissions,
+                departing_times: match direction {
+                    Direction::Outbound => self.departing_times.clone(),
+                    Direction::Return => self.return_times.clone(),
+                },
+            },
         }
     }
 
@@ -147,6 +221,10 @@ impl Config {
     }
 
     /// Returns the Google flight URL for this flight search.
+    ///
+    /// The link carries every filter of the search; only time windows that
+    /// do not fit a single hour range are widened (see
+    /// [`filters_missing_from_url`](Self::filters_missing_from_url)).
     pub fn to_flight_url(&self) -> String {
         ItineraryUrl::from(self).to_flight_url()
     }
@@ -156,6 +234,20 @@ impl Config {
         ItineraryUrl::from(self).to_encoded()
     }
 
+    /// Filters set on this search that [`to_flight_url`](Self::to_flight_url)
+    /// cannot carry exactly, so the link opens a broader search than the API
+    /// call made.  Empty when the link reproduces the search exactly.
+    ///
+    /// The only such filter is `time_windows`: several windows, or one across
+    /// midnight, which the link can only widen to a single hour range.
+    pub fn filters_missing_from_url(&self) -> Vec<&'static str> {
+        if self.departing_times.fits_hour_range() && self.return_times.fits_hour_range() {
+            Vec::new()
+        } else {
+            vec!["time_windows"]
+        }
+    }
+
     pub fn builder() -> ConfigBuilder {
         ConfigBuilder::default()
     }
@@ -164,13 +256,6 @@ impl Config {
 /// Conversion to Leg Protobuf, used for creating the URL.
 impl From<&Config> for Vec<Leg> {
     fn from(options: &Config) -> Vec<Leg> {
-        let stops = match options.stop_options {
-            StopOptions::All => None,
-            variant => Some(variant as i32 - 1),
-        };
-        let max_stopover_minutes = options.stopover_max.to_option();
-        let max_duration_minutes = options.duration_max.to_option();
-
         let departure: Vec<LocationProto> =
             options.departure.iter().map(LocationProto::from).collect();
         let destination: Vec<LocationProto> = options
@@ -179,41 +264,26 @@ impl From<&Config> for Vec<Leg> {
             .map(LocationProto::from)
             .collect();
 
-        let first_leg = Leg {
-            date: options.departing_date.to_string(),
-            departure: departure.clone(),
-            arrival: destination.clone(),
-            min_hour_departure: options.departing_times.get_departure_hour_min(),
-            max_hour_departure: options.departing_times.get_departure_hour_max(),
-            min_hour_arrival: options.departing_times.get_arrival_hour_min(),
-            max_hour_arrival: options.departing_times.get_arrival_hour_max(),
-            stops,
-            max_stopover_minutes,
-            max_duration_minutes,
-        };
-
         let mut leg_vector: Vec<Leg> = Vec::new();
-        leg_vector.push(first_leg);
+        leg_vector.push(url_leg(
+            options.departing_date,
+            &departure,
+            &destination,
+            &options.leg_filters(Direction::Outbound),
+        ));
 
         match options.trip_type {
             TripType::OneWay => {} //already done
             TripType::Return => {
-                let second_leg = Leg {
-                    date: options
-                        .return_date
-                        .expect("return_date is always Some when TripType is Return")
-                        .to_string(),
-                    departure: destination.clone(),
-                    arrival: departure.clone(),
-                    min_hour_departure: options.return_times.get_departure_hour_min(),
-                    max_hour_departure: options.return_times.get_departure_hour_max(),
-                    min_hour_arrival: options.return_times.get_arrival_hour_min(),
-                    max_hour_arrival: options.return_times.get_arrival_hour_max(),
-                    stops,
-                    max_stopover_minutes,
-                    max_duration_minutes,
-                };
-                leg_vector.push(second_leg);
+                let return_date = options
+                    .return_date
+                    .expect("return_date is always Some when TripType is Return");
+                leg_vector.push(url_leg(
+                    return_date,
+                    &destination,
+                    &departure,
+                    &options.leg_filters(Direction::Return),
+                ));
             }
             TripType::MultiCity => unreachable!(
                 "MultiCity Config cannot be created via Config::new() or Config::builder(); \
@@ -225,6 +295,62 @@ impl From<&Config> for Vec<Leg> {
     }
 }
 
+/// One leg of a link, with its filters.
+fn url_leg(
+    date: NaiveDate,
+    departure: &[LocationProto],
+    arrival: &[LocationProto],
+    filters: &LegFilters,
+) -> Leg {
+    let times = &filters.departing_times;
+    Leg {
+        date: date.to_string(),
+        departure: departure.to_vec(),
+        arrival: arrival.to_vec(),
+        min_hour_departure: times.get_departure_hour_min(),
+        max_hour_departure: times.get_departure_hour_max(),
+        min_hour_arrival: times.get_arrival_hour_min(),
+        max_hour_arrival: times.get_arrival_hour_max(),
+        stops: match filters.stop_options {
+            StopOptions::All => None,
+            variant => Some(variant as i32 - 1),
+        },
+        airlines: airline_strings(&filters.airlines_include),
+        excluded_airlines: airline_strings(&filters.airlines_exclude),
+        connecting_airports: filters.connecting_airports.clone(),
+        lower_emissions: filters.lower_emissions,
+        min_stopover_minutes: filters.stopover_min.to_option(),
+        max_stopover_minutes: filters.stopover_max.to_option(),
+        max_duration_minutes: filters.duration_max.to_option(),
+    }
+}
+
+/// The whole-search filters of a link: max price, baggage and sort order
+/// (left out when it is Google's default).
+pub(super) fn url_search_filters(
+    max_price: Option<i32>,
+    baggage: Option<(u8, u8)>,
+    sort_order: SortOrder,
+) -> (Option<i32>, Option<Baggage>, Option<i32>) {
+    (
+        max_price,
+        baggage.map(|(carry_on, checked)| Baggage {
+            carry_on: carry_on.into(),
+            checked: checked.into(),
+        }),
+        (!matches!(sort_order, SortOrder::Best)).then_some(sort_order as i32),
+    )
+}
+
+/// Airline filters as they appear in a link's `airlines` and
+/// `excluded_airlines` fields.
+pub(super) fn airline_strings(filters: &[AirlineFilter]) -> Vec<String> {
+    filters
+        .iter()
+        .map(|f| f.as_google_str().to_owned())
+        .collect()
+}
+
 /// Converts a [`Location`] to the protobuf [`LocationProto`] used in URL encoding.
 ///
 /// Airports use type `1`; cities, regions, and unspecified types use type `2`,
@@ -259,10 +385,15 @@ impl From<&Config> for ItineraryUrl {
         let travellers = options.travellers.to_proto_vec();
 
         let legs: Vec<Leg> = options.into();
+        let (max_price, baggage, sort_order) =
+            url_search_filters(options.max_price, options.baggage, options.sort_order);
         Self {
             legs,
             travellers,
             class,
+            max_price,
+            baggage,
+            sort_order,
             trip_type,
         }
     }
@@ -272,6 +403,7 @@ impl From<&Config> for ItineraryUrl {
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use crate::parsers::common::TimeOfDay;
     use chrono::{Duration, Utc};
 
     fn future_date(days: i64) -> NaiveDate {
@@ -519,6 +651,107 @@ mod tests {
         assert_eq!(legs[1].arrival.len(), 2);
     }
 
+    #[test]
+    fn airline_include_filter_is_encoded_on_every_leg() {
+        use crate::parsers::common::Alliance;
+        let config = Config {
+            departing_date: future_date(30),
+            return_date: Some(future_date(37)),
+            trip_type: TripType::Return,
+            departure: airports(&["LHR"]),
+            destination: airports(&["JFK"]),
+            airlines_include: vec![
+                "BA".parse().unwrap(),
+                AirlineFilter::Alliance(Alliance::OneWorld),
+            ],
+            ..Default::default()
+        };
+        let legs: Vec<protos::urls::Leg> = (&config).into();
+        for leg in &legs {
+            assert_eq!(leg.airlines, ["BA", "ONEWORLD"]);
+        }
+        assert!(config.filters_missing_from_url().is_empty());
+    }
+
+    #[test]
+    fn every_filter_is_carried_by_the_link() {
+        let mut config = Config {
+            departing_date: future_date(30),
+            departure: airports(&["LHR"]),
+            destination: airports(&["JFK"]),
+            ..Default::default()
+        };
+        config.airlines_exclude = vec!["FR".parse().unwrap()];
+        config.connecting_airports = vec!["DUB".to_owned()];
+        config.stopover_min = StopoverDuration::Minutes(60);
+        config.lower_emissions = true;
+        config.max_price = Some(500);
+        config.baggage = Some((1, 2));
+        config.sort_order = SortOrder::Price;
+        assert!(config.filters_missing_from_url().is_empty());
+
+        let url = ItineraryUrl::from(&config);
+        let leg = &url.legs[0];
+        assert_eq!(leg.excluded_airlines, ["FR"]);
+        assert_eq!(leg.connecting_airports, ["DUB"]);
+        assert_eq!(leg.min_stopover_minutes, Some(60));
+        assert!(leg.lower_emissions);
+        assert_eq!(url.max_price, Some(500));
+        assert_eq!(
+            url.baggage,
+            Some(Baggage {
+                carry_on: 1,
+                checked: 2
+            })
+        );
+        assert_eq!(url.sort_order, Some(SortOrder::Price as i32));
+    }
+
+    #[test]
+    fn time_windows_a_link_cannot_carry_are_reported() {
+        let mut config = Config {
+            departing_date: future_date(30),
+            departure: airports(&["LHR"]),
+            destination: airports(&["JFK"]),
+            ..Default::default()
+        };
+        config.departing_times = FlightTimes::default().depart_during(TimeOfDay::Morning);
+        assert!(config.filters_missing_from_url().is_empty());
+
+        config.departing_times = config.departing_times.depart_during(TimeOfDay::Evening);
+        assert_eq!(config.filters_missing_from_url(), ["time_windows"]);
+
+        config.departing_times = FlightTimes::default();
+        config.return_times = FlightTimes::default().arrive_during(TimeOfDay::RedEye);
+        assert_eq!(config.filters_missing_from_url(), ["time_windows"]);
+    }
+
+    #[test]
+    fn listed_leg_times_switch_to_the_return_once_the_outbound_is_fixed() {
+        use crate::parsers::flight_response::FlightResponseContainer;
+
+        let mut config = Config {
+            departing_date: future_date(30),
+            return_date: Some(future_date(37)),
+            departure: airports(&["LHR"]),
+            destination: airports(&["JFK"]),
+            fixed_flights: FixedFlights::new(2),
+            ..Default::default()
+        };
+        config.departing_times = FlightTimes::default().depart_during(TimeOfDay::Morning);
+        config.return_times = FlightTimes::default().depart_during(TimeOfDay::Evening);
+        assert!(config.listed_leg_times().allows_departure(9));
+
+        let body = std::fs::read_to_string("test_files/lux_milan_oneway.txt").unwrap();
+        let response = FlightResponseContainer {
+            responses: vec![serde_json::from_str(&body).unwrap()],
+        };
+        let outbound = response.get_all_flights().remove(0);
+        config.fixed_flights.add_element(outbound).unwrap();
+        assert!(config.listed_leg_times().allows_departure(19));
+        assert!(!config.listed_leg_times().allows_departure(9));
+    }
+
     // -----------------------------------------------------------------------
     // Config::new()
     // -----------------------------------------------------------------------
@@ -1,21 +1,22 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use chrono::NaiveDate;
 
 use crate::parsers::common::{
     AirlineFilter, FlightTimes, Location, PlaceType, SortOrder, StopOptions, StopoverDuration,
-    TotalDuration, TravelClass, Travelers,
+    TimeWindow, TotalDuration, TravelClass, Travelers,
 };
 use crate::protos::urls::{ItineraryUrl, Leg};
 use crate::requests::api::ApiClient;
 
-use super::builder::get_location_pub;
+use super::builder::get_locations_pub;
 
 /// Per-leg filter overrides for multi-city searches.
 ///
 /// All fields default to "unrestricted" (same as `Config`'s defaults).
 /// Obtain a default value via [`LegFilters::default()`] and override
 /// only the fields you need.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct LegFilters {
     pub stop_options: StopOptions,
     pub airlines_include: Vec<AirlineFilter>,
@@ -46,8 +47,28 @@ impl Default for LegFilters {
     }
 }
 
+impl LegFilters {
+    /// Adds a departure window to `departing_times`; see
+    /// [`FlightTimes::depart_during`].
+    pub fn depart_during(mut self, window: impl Into<TimeWindow>) -> Self {
+        self.departing_times = self.departing_times.depart_during(window);
+        self
+    }
+
+    /// Adds an arrival window to `departing_times`; see
+    /// [`FlightTimes::arrive_during`].
+    pub fn arrive_during(mut self, window: impl Into<TimeWindow>) -> Self {
+        self.departing_times = self.departing_times.arrive_during(window);
+        self
+    }
+}
+
 /// A single leg in a multi-city itinerary.
-#[derive(Debug, Clone)]
+///
+/// When deserialised, the per-leg filters may be left out and default to
+/// "unrestricted".
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct MultiCityLeg {
     /// One to seven origin airports / city identifiers.
     pub from: Vec<Location>,
@@ -55,16 +76,25 @@ pub struct MultiCityLeg {
     pub to: Vec<Location>,
     pub date: NaiveDate,
     // Per-leg filters — default to "unrestricted".
+    #[serde(default)]
     pub stop_options: StopOptions,
+    #[serde(default)]
     pub airlines_include: Vec<AirlineFilter>,
+    #[serde(default)]
     pub airlines_exclude: Vec<AirlineFilter>,
     /// IATA airport codes required as connection points.
+    #[serde(default)]
     pub connecting_airports: Vec<String>,
+    #[serde(default)]
     pub stopover_min: StopoverDuration,
+    #[serde(default)]
     pub stopover_max: StopoverDuration,
+    #[serde(default)]
     pub duration_max: TotalDuration,
     /// If `true`, only return flights with below-average CO₂ emissions.
+    #[serde(default)]
     pub lower_emissions: bool,
+    #[serde(default)]
     pub departing_times: FlightTimes,
 }
 
@@ -75,7 +105,10 @@ pub struct MultiCityLeg {
 ///
 /// Google Flights returns independent flight options per leg; the result is
 /// `Vec<FlightResponseContainer>` — one container per leg.
-#[derive(Debug, Clone)]
+///
+/// Deserialising goes through [`MultiCityConfigBuilder::build`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "MultiCityConfigBuilder")]
 pub struct MultiCityConfig {
     /// Ordered list of legs (minimum 2).
     pub legs: Vec<MultiCityLeg>,
@@ -92,10 +125,32 @@ impl MultiCityConfig {
     pub fn builder() -> MultiCityConfigBuilder {
         MultiCityConfigBuilder::default()
     }
+
+    /// Returns the Google flight URL for this multi-city search, with every
+    /// filter except the time windows named by
+    /// [`filters_missing_from_url`](Self::filters_missing_from_url).
+    pub fn to_flight_url(&self) -> String {
+        ItineraryUrl::from(self).to_flight_url()
+    }
+
+    /// Filters set on this search that [`to_flight_url`](Self::to_flight_url)
+    /// cannot carry exactly; see [`Config::filters_missing_from_url`](super::Config::filters_missing_from_url).
+    pub fn filters_missing_from_url(&self) -> Vec<&'static str> {
+        if self
+            .legs
+            .iter()
+            .all(|l| l.departing_times.fits_hour_range())
+        {
+            Vec::new()
+        } else {
+            vec!["time_windows"]
+        }
+    }
 }
 
 /// Builder for [`MultiCityConfig`]. Obtain via [`MultiCityConfig::builder()`].
-#[derive(Default)]
+#[derive(Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct MultiCityConfigBuilder {
     legs: Vec<MultiCityLeg>,
     travellers: Travelers,
@@ -106,7 +161,8 @@ pub struct MultiCityConfigBuilder {
 }
 
 impl MultiCityConfigBuilder {
-    /// Resolve `from` and `to` via the API and append a leg.
+    /// Resolve `from` and `to` (offline where possible, see
+    /// [`ConfigBuilder::departure`](super::ConfigBuilder::departure)) and append a leg.
     pub async fn add_leg(
         mut self,
         from: &str,
@@ -114,12 +170,12 @@ impl MultiCityConfigBuilder {
         date: NaiveDate,
         client: &ApiClient,
     ) -> Result<Self> {
-        let from_loc = get_location_pub(from, client).await?;
-        let to_loc = get_location_pub(to, client).await?;
+        let from_locs = get_locations_pub(from, client).await?;
+        let to_locs = get_locations_pub(to, client).await?;
         let filters = LegFilters::default();
         self.legs.push(MultiCityLeg {
-            from: vec![from_loc],
-            to: vec![to_loc],
+            from: from_locs,
+            to: to_locs,
             date,
             stop_options: filters.stop_options,
             airlines_include: filters.airlines_include,
@@ -134,7 +190,8 @@ impl MultiCityConfigBuilder {
         Ok(self)
     }
 
-    /// Resolve `from` and `to` via the API and append a leg with per-leg filters.
+    /// Resolve `from` and `to` as in [`add_leg`](Self::add_leg) and append a
+    /// leg with per-leg filters.
     pub async fn add_leg_with_filters(
         mut self,
         from: &str,
@@ -143,11 +200,11 @@ impl MultiCityConfigBuilder {
         client: &ApiClient,
         filters: LegFilters,
     ) -> Result<Self> {
-        let from_loc = get_location_pub(from, client).await?;
-        let to_loc = get_location_pub(to, client).await?;
+        let from_locs = get_locations_pub(from, client).await?;
+        let to_locs = get_locations_pub(to, client).await?;
         self.legs.push(MultiCityLeg {
-            from: vec![from_loc],
-            to: vec![to_loc],
+            from: from_locs,
+            to: to_locs,
             date,
             stop_options: filters.stop_options,
             airlines_include: filters.airlines_include,
@@ -164,12 +221,23 @@ impl MultiCityConfigBuilder {
 
     /// Append a leg using pre-resolved [`Location`] values (no network call).
     pub fn add_leg_locations(
+        self,
+        from: Vec<Location>,
+        to: Vec<Location>,
+        date: NaiveDate,
+    ) -> Self {
+        self.add_leg_locations_with_filters(from, to, date, LegFilters::default())
+    }
+
+    /// Append a leg with per-leg filters using pre-resolved [`Location`]
+    /// values (no network call).
+    pub fn add_leg_locations_with_filters(
         mut self,
         from: Vec<Location>,
         to: Vec<Location>,
         date: NaiveDate,
+        filters: LegFilters,
     ) -> Self {
-        let filters = LegFilters::default();
         self.legs.push(MultiCityLeg {
             from,
             to,
@@ -214,26 +282,29 @@ impl MultiCityConfigBuilder {
         self
     }
 
+    /// Builds the [`MultiCityConfig`], failing with
+    /// [`Error::Validation`](crate::Error::Validation) on every problem that
+    /// does not depend on today's date.  Call [`MultiCityConfig::validate`]
+    /// for the full check, dates included.
     pub fn build(self) -> Result<MultiCityConfig> {
-        if self.legs.len() < 2 {
-            return Err(anyhow!("multi-city requires at least 2 legs"));
-        }
-        for (i, leg) in self.legs.iter().enumerate() {
-            if leg.from.is_empty() {
-                return Err(anyhow!("leg {i} has no departure airport"));
-            }
-            if leg.to.is_empty() {
-                return Err(anyhow!("leg {i} has no destination airport"));
-            }
-        }
-        Ok(MultiCityConfig {
+        let config = MultiCityConfig {
             legs: self.legs,
             travellers: self.travellers,
             travel_class: self.travel_class,
             sort_order: self.sort_order,
             max_price: self.max_price,
             baggage: self.baggage,
-        })
+        };
+        super::validate::into_result(super::validate::check_multi_city(&config, None))?;
+        Ok(config)
+    }
+}
+
+impl TryFrom<MultiCityConfigBuilder> for MultiCityConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(builder: MultiCityConfigBuilder) -> Result<Self> {
+        builder.build()
     }
 }
 
@@ -273,13 +344,21 @@ impl From<&MultiCityConfig> for Vec<Leg> {
                 date: leg.date.to_string(),
                 departure: leg.from.iter().map(loc_to_proto).collect(),
                 arrival: leg.to.iter().map(loc_to_proto).collect(),
-                min_hour_departure: None,
-                max_hour_departure: None,
-                min_hour_arrival: None,
-                max_hour_arrival: None,
-                stops: None,
-                max_stopover_minutes: None,
-                max_duration_minutes: None,
+                min_hour_departure: leg.departing_times.get_departure_hour_min(),
+                max_hour_departure: leg.departing_times.get_departure_hour_max(),
+                min_hour_arrival: leg.departing_times.get_arrival_hour_min(),
+                max_hour_arrival: leg.departing_times.get_arrival_hour_max(),
+                stops: match leg.stop_options {
+                    StopOptions::All => None,
+                    variant => Some(variant as i32 - 1),
+                },
+                airlines: super::airline_strings(&leg.airlines_include),
+                excluded_airlines: super::airline_strings(&leg.airlines_exclude),
+                connecting_airports: leg.connecting_airports.clone(),
+                lower_emissions: leg.lower_emissions,
+                min_stopover_minutes: leg.stopover_min.to_option(),
+                max_stopover_minutes: leg.stopover_max.to_option(),
+                max_duration_minutes: leg.duration_max.to_option(),
             })
             .collect()
     }
@@ -300,10 +379,15 @@ fn loc_to_proto(loc: &Location) -> crate::protos::urls::Location {
 impl From<&MultiCityConfig> for ItineraryUrl {
     fn from(config: &MultiCityConfig) -> Self {
         let legs: Vec<Leg> = config.into();
+        let (max_price, baggage, sort_order) =
+            super::url_search_filters(config.max_price, config.baggage, config.sort_order);
         ItineraryUrl {
             legs,
             travellers: config.travellers.to_proto_vec(),
             class: config.travel_class as i32,
+            max_price,
+            baggage,
+            sort_order,
             trip_type: 3, // multi_city
         }
     }
@@ -428,6 +512,39 @@ mod tests {
         assert_eq!(url.legs.len(), 2);
     }
 
+    #[test]
+    fn link_carries_per_leg_filters() {
+        let filters = LegFilters {
+            stop_options: StopOptions::NoStop,
+            airlines_include: vec!["LX".parse().unwrap()],
+            stopover_max: StopoverDuration::Minutes(120),
+            lower_emissions: true,
+            ..LegFilters::default()
+        };
+        let cfg = MultiCityConfig::builder()
+            .add_leg_locations_with_filters(
+                vec![airport("LUX")],
+                vec![airport("FCO")],
+                date("2026-09-10"),
+                filters.clone(),
+            )
+            .add_leg_locations_with_filters(
+                vec![airport("FCO")],
+                vec![airport("MAD")],
+                date("2026-09-13"),
+                filters,
+            )
+            .build()
+            .unwrap();
+
+        let legs: Vec<Leg> = (&cfg).into();
+        assert_eq!(legs[0].stops, Some(0));
+        assert_eq!(legs[1].airlines, ["LX"]);
+        assert_eq!(legs[1].max_stopover_minutes, Some(120));
+        assert!(legs[1].lower_emissions);
+        assert!(cfg.filters_missing_from_url().is_empty());
+    }
+
     /// Verify that `LegFilters::default()` produces all-unrestricted values.
     #[test]
     fn leg_filters_default_is_unrestricted() {
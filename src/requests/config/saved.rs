@@ -0,0 +1,480 @@
+//! Versioned JSON / TOML documents for the search configs, so a search can be
+//! saved, handed to another service or queued and run later.
+//!
+//! A document wraps one config with the format version and the kind of
+//! search it holds:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "kind": "flights",
+//!   "config": { "departing_date": "2026-09-01", "departure": [...], ... }
+//! }
+//! ```
+//!
+//! or, in TOML (with the default `toml` feature):
+//!
+//! ```toml
+//! version = 1
+//! kind = "flights"
+//!
+//! [config]
+//! departing_date = "2026-09-01"
+//! ```
+//!
+//! Loading checks the version and kind before decoding the config, and the
+//! config is validated as its builder would: a [`Config`] goes through
+//! [`ConfigBuilder::build`](super::ConfigBuilder::build) and a
+//! [`MultiCityConfig`] through
+//! [`MultiCityConfigBuilder::build`](super::MultiCityConfigBuilder::build).
+//! Fields left out take their builder defaults; unknown fields are rejected.
+//!
+//! Use [`SavedConfig`] when the kind is known in advance and [`SavedSearch`]
+//! when it is not, e.g. in a job queue carrying every kind of search.
+//!
+//! ```rust
+//! use gflights::requests::config::{DealConfig, SavedConfig, SavedSearch};
+//!
+//! let text = r#"{"version":1,"kind":"deals","config":{
+//!     "origin":[{"loc_identifier":"LUX","loc_type":1}],
+//!     "outbound_date":"2026-06-20","return_date":"2026-06-27"}}"#;
+//! let deals = DealConfig::from_json(text)?;
+//! assert_eq!(deals.origin[0].loc_identifier, "LUX");
+//!
+//! match SavedSearch::from_json(&deals.to_json()?)? {
+//!     SavedSearch::Deals(again) => assert_eq!(again.return_date, deals.return_date),
+//!     other => panic!("unexpected {}", other.kind()),
+//! }
+//! # Ok::<(), gflights::Error>(())
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::{Config, DealConfig, ExploreConfig, MultiCityConfig};
+use crate::error::{Error, Result};
+
+/// Format version written by this crate and the only one it loads.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A search config with a versioned JSON / TOML form.
+pub trait SavedConfig: Serialize + DeserializeOwned + private::Check {
+    /// Value of the document's `kind` field.
+    const KIND: &'static str;
+
+    /// Writes the config as a JSON document.
+    fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&Document::new(Self::KIND, self))
+            .map_err(|e| Error::InvalidConfig(format!("cannot write saved search: {e}")))
+    }
+
+    /// Reads a JSON document written by [`to_json`](Self::to_json).
+    ///
+    /// # Errors
+    /// [`Error::InvalidConfig`] if the document is not valid JSON, has another
+    /// version or kind, or holds a config its builder would reject.
+    fn from_json(text: &str) -> Result<Self> {
+        let header: Header = serde_json::from_str(text).map_err(decode_error)?;
+        header.expect(Self::KIND)?;
+        let de = &mut serde_json::Deserializer::from_str(text);
+        let doc: Document<Self> = serde_path_to_error::deserialize(de).map_err(decode_error)?;
+        doc.config.check()?;
+        Ok(doc.config)
+    }
+
+    /// Writes the config as a TOML document.
+    #[cfg(feature = "toml")]
+    fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(&Document::new(Self::KIND, self))
+            .map_err(|e| Error::InvalidConfig(format!("cannot write saved search: {e}")))
+    }
+
+    /// Reads a TOML document written by [`to_toml`](Self::to_toml).
+    ///
+    /// # Errors
+    /// As for [`from_json`](Self::from_json).
+    #[cfg(feature = "toml")]
+    fn from_toml(text: &str) -> Result<Self> {
+        let header: Header = toml::from_str(text).map_err(decode_error)?;
+        header.expect(Self::KIND)?;
+        let doc: Document<Self> = toml::from_str(text).map_err(decode_error)?;
+        doc.config.check()?;
+        Ok(doc.config)
+    }
+}
+
+impl SavedConfig for Config {
+    const KIND: &'static str = "flights";
+}
+
+impl SavedConfig for MultiCityConfig {
+    const KIND: &'static str = "multi_city";
+}
+
+impl SavedConfig for ExploreConfig {
+    const KIND: &'static str = "explore";
+}
+
+impl SavedConfig for DealConfig {
+    const KIND: &'static str = "deals";
+}
+
+/// A saved search of any kind, for readers that do not know the kind up front.
+// Read once per document, like `UrlSearch`; boxing `Config` would only make
+// every caller's match noisier.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum SavedSearch {
+    Flights(Config),
+    MultiCity(MultiCityConfig),
+    Explore(ExploreConfig),
+    Deals(DealConfig),
+}
+
+impl SavedSearch {
+    /// The document `kind` this search is saved under.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SavedSearch::Flights(_) => Config::KIND,
+            SavedSearch::MultiCity(_) => MultiCityConfig::KIND,
+            SavedSearch::Explore(_) => ExploreConfig::KIND,
+            SavedSearch::Deals(_) => DealConfig::KIND,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        match self {
+            SavedSearch::Flights(c) => c.to_json(),
+            SavedSearch::MultiCity(c) => c.to_json(),
+            SavedSearch::Explore(c) => c.to_json(),
+            SavedSearch::Deals(c) => c.to_json(),
+        }
+    }
+
+    /// Reads a JSON document of any kind.
+    ///
+    /// # Errors
+    /// As for [`SavedConfig::from_json`], and for an unknown `kind`.
+    pub fn from_json(text: &str) -> Result<Self> {
+        let header: Header = serde_json::from_str(text).map_err(decode_error)?;
+        header.check_version()?;
+        match header.kind.as_str() {
+            Config::KIND => Config::from_json(text).map(SavedSearch::Flights),
+            MultiCityConfig::KIND => MultiCityConfig::from_json(text).map(SavedSearch::MultiCity),
+            ExploreConfig::KIND => ExploreConfig::from_json(text).map(SavedSearch::Explore),
+            DealConfig::KIND => DealConfig::from_json(text).map(SavedSearch::Deals),
+            other => Err(unknown_kind(other)),
+        }
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> Result<String> {
+        match self {
+            SavedSearch::Flights(c) => c.to_toml(),
+            SavedSearch::MultiCity(c) => c.to_toml(),
+            SavedSearch::Explore(c) => c.to_toml(),
+            SavedSearch::Deals(c) => c.to_toml(),
+        }
+    }
+
+    /// Reads a TOML document of any kind.
+    ///
+    /// # Errors
+    /// As for [`SavedSearch::from_json`].
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let header: Header = toml::from_str(text).map_err(decode_error)?;
+        header.check_version()?;
+        match header.kind.as_str() {
+            Config::KIND => Config::from_toml(text).map(SavedSearch::Flights),
+            MultiCityConfig::KIND => MultiCityConfig::from_toml(text).map(SavedSearch::MultiCity),
+            ExploreConfig::KIND => ExploreConfig::from_toml(text).map(SavedSearch::Explore),
+            DealConfig::KIND => DealConfig::from_toml(text).map(SavedSearch::Deals),
+            other => Err(unknown_kind(other)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Document<T> {
+    version: u32,
+    kind: String,
+    config: T,
+}
+
+impl<'a, T> Document<&'a T> {
+    fn new(kind: &str, config: &'a T) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            kind: kind.to_owned(),
+            config,
+        }
+    }
+}
+
+/// The envelope fields, read before the config so a wrong version or kind is
+/// reported as such rather than as a field error.
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+    kind: String,
+}
+
+impl Header {
+    fn check_version(&self) -> Result<()> {
+        if self.version != FORMAT_VERSION {
+            return Err(Error::InvalidConfig(format!(
+                "unsupported saved search version {} (expected {FORMAT_VERSION})",
+                self.version
+            )));
+        }
+        Ok(())
+    }
+
+    fn expect(&self, kind: &str) -> Result<()> {
+        self.check_version()?;
+        if self.kind != kind {
+            return Err(Error::InvalidConfig(format!(
+                "expected a saved {kind:?} search, found {:?}",
+                self.kind
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn decode_error(e: impl std::fmt::Display) -> Error {
+    Error::InvalidConfig(format!("invalid saved search: {e}"))
+}
+
+fn unknown_kind(kind: &str) -> Error {
+    Error::InvalidConfig(format!("unknown saved search kind {kind:?}"))
+}
+
+mod private {
+    use anyhow::{anyhow, Result};
+
+    use super::{Config, DealConfig, ExploreConfig, MultiCityConfig};
+
+    /// Checks run on a loaded config.  [`Config`] and [`MultiCityConfig`] are
+    /// already validated by their builders while deserialising.
+    pub trait Check {
+        fn check(&self) -> crate::Result<()> {
+            self.rules()
+                .map_err(|e| crate::Error::InvalidConfig(format!("{e:#}")))
+        }
+
+        fn rules(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Check for Config {}
+
+    impl Check for MultiCityConfig {}
+
+    impl Check for ExploreConfig {
+        fn rules(&self) -> Result<()> {
+            if self.origin.is_empty() {
+                return Err(anyhow!("At least one origin is required"));
+            }
+            if let Some(date) = self.trip_date {
+                if !(1..=12).contains(&date.month) {
+                    return Err(anyhow!("trip_date month must be 1-12, got {}", date.month));
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl Check for DealConfig {
+        fn rules(&self) -> Result<()> {
+            if self.origin.is_empty() {
+                return Err(anyhow!("At least one origin is required"));
+            }
+            if self.return_date < self.outbound_date {
+                return Err(anyhow!("return_date is before outbound_date"));
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::common::{
+        AirlineCode, AirlineFilter, Location, PlaceType, StopOptions, StopoverDuration, Travelers,
+    };
+    use crate::requests::config::{ExploreDuration, MapBounds};
+    use chrono::NaiveDate;
+
+    fn airport(code: &str) -> Location {
+        Location {
+            loc_identifier: code.to_owned(),
+            loc_type: PlaceType::Airport,
+            location_name: Some(code.to_owned()),
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").expect("valid date")
+    }
+
+    fn flights() -> Config {
+        Config::builder()
+            .departing_date(date("2026-09-01"))
+            .return_date(date("2026-09-08"))
+            .departure_location(airport("LUX"))
+            .destination_location(airport("ZRH"))
+            .stop_options(StopOptions::NoStop)
+            .stopover_max(StopoverDuration::Minutes(120))
+            .add_airline_include(AirlineFilter::Airline(
+                AirlineCode::new("LX").expect("valid code"),
+            ))
+            .travelers(Travelers::new(vec![2, 1, 0, 0]).expect("valid travellers"))
+            .baggage(1, 0)
+            .build()
+            .expect("valid config")
+    }
+
+    fn multi_city() -> MultiCityConfig {
+        MultiCityConfig::builder()
+            .add_leg_locations(
+                vec![airport("LUX")],
+                vec![airport("FCO")],
+                date("2026-09-10"),
+            )
+            .add_leg_locations(
+                vec![airport("FCO")],
+                vec![airport("MAD")],
+                date("2026-09-13"),
+            )
+            .max_price(400)
+            .build()
+            .expect("valid config")
+    }
+
+    #[test]
+    fn flights_round_trip_through_json_and_toml() {
+        let cfg = flights();
+        #[cfg_attr(not(feature = "toml"), allow(unused_mut))]
+        let mut loaded = vec![Config::from_json(&cfg.to_json().expect("json")).expect("loads")];
+        #[cfg(feature = "toml")]
+        loaded.push(Config::from_toml(&cfg.to_toml().expect("toml")).expect("loads"));
+        for back in loaded {
+            assert_eq!(back.to_encoded(), cfg.to_encoded());
+            assert_eq!(back.departure, cfg.departure);
+            assert_eq!(back.baggage, Some((1, 0)));
+            assert_eq!(back.airlines_include, cfg.airlines_include);
+            assert!(matches!(back.stop_options, StopOptions::NoStop));
+            assert!(matches!(back.trip_type, super::super::TripType::Return));
+            assert!(!back.fixed_flights.is_full());
+        }
+    }
+
+    #[test]
+    fn every_kind_round_trips_through_saved_search() {
+        let explore = ExploreConfig {
+            origin: vec![airport("LUX")],
+            trip_duration: ExploreDuration::Weekend,
+            map_bounds: Some(MapBounds {
+                sw: (35.0, -10.0),
+                ne: (60.0, 30.0),
+            }),
+            ..ExploreConfig::default()
+        };
+        let deals = DealConfig {
+            origin: vec![airport("LUX")],
+            ..DealConfig::default()
+        };
+        let searches = [
+            SavedSearch::Flights(flights()),
+            SavedSearch::MultiCity(multi_city()),
+            SavedSearch::Explore(explore),
+            SavedSearch::Deals(deals),
+        ];
+        for search in searches {
+            let json = SavedSearch::from_json(&search.to_json().expect("json")).expect("loads");
+            assert_eq!(json.kind(), search.kind());
+            #[cfg(feature = "toml")]
+            {
+                let toml = SavedSearch::from_toml(&search.to_toml().expect("toml")).expect("loads");
+                assert_eq!(toml.kind(), search.kind());
+            }
+        }
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn hand_written_toml_takes_builder_defaults() {
+        let text = r#"
+            version = 1
+            kind = "multi_city"
+
+            [config]
+            max_price = 300
+
+            [[config.legs]]
+            from = [{ loc_identifier = "LUX", loc_type = 1 }]
+            to = [{ loc_identifier = "FCO", loc_type = 1 }]
+            date = "2026-09-10"
+
+            [[config.legs]]
+            from = [{ loc_identifier = "FCO", loc_type = 1 }]
+            to = [{ loc_identifier = "LUX", loc_type = 1 }]
+            date = "2026-09-14"
+            stop_options = "NoStop"
+        "#;
+        let cfg = MultiCityConfig::from_toml(text).expect("loads");
+        assert_eq!(cfg.legs.len(), 2);
+        assert_eq!(cfg.travellers.adults, 1);
+        assert!(matches!(cfg.legs[0].stop_options, StopOptions::All));
+        assert!(matches!(cfg.legs[1].stop_options, StopOptions::NoStop));
+    }
+
+    #[test]
+    fn loading_applies_builder_rules() {
+        let missing_destination = r#"{"version":1,"kind":"flights","config":{
+            "departing_date":"2026-09-01",
+            "departure":[{"loc_identifier":"LUX","loc_type":1}]}}"#;
+        let err = Config::from_json(missing_destination).unwrap_err();
+        assert!(
+            matches!(&err, Error::InvalidConfig(m) if m.contains("destination")),
+            "{err}"
+        );
+
+        let one_leg = r#"{"version":1,"kind":"multi_city","config":{"legs":[
+            {"from":[{"loc_identifier":"LUX","loc_type":1}],
+             "to":[{"loc_identifier":"FCO","loc_type":1}],"date":"2026-09-10"}]}}"#;
+        assert!(MultiCityConfig::from_json(one_leg).is_err());
+
+        let backwards = r#"{"version":1,"kind":"deals","config":{
+            "origin":[{"loc_identifier":"LUX","loc_type":1}],
+            "outbound_date":"2026-06-27","return_date":"2026-06-20"}}"#;
+        assert!(DealConfig::from_json(backwards).is_err());
+
+        let no_origin = r#"{"version":1,"kind":"explore","config":{}}"#;
+        assert!(ExploreConfig::from_json(no_origin).is_err());
+    }
+
+    #[test]
+    fn version_kind_and_unknown_fields_are_checked() {
+        let cfg = flights().to_json().expect("json");
+
+        let future = cfg.replacen("\"version\": 1", "\"version\": 2", 1);
+        let err = Config::from_json(&future).unwrap_err().to_string();
+        assert!(err.contains("version 2"), "{err}");
+
+        let err = DealConfig::from_json(&cfg).unwrap_err().to_string();
+        assert!(err.contains("\"deals\""), "{err}");
+
+        let unknown = cfg.replacen("\"travel_class\"", "\"cabin\"", 1);
+        let err = Config::from_json(&unknown).unwrap_err().to_string();
+        assert!(err.contains("cabin"), "{err}");
+
+        let kind = cfg.replacen("\"flights\"", "\"hotels\"", 1);
+        let err = SavedSearch::from_json(&kind).unwrap_err().to_string();
+        assert!(err.contains("hotels"), "{err}");
+    }
+}
@@ -0,0 +1,512 @@
+//! Validation of search configurations.
+//!
+//! Google Flights answers most nonsensical searches (a departure in the past,
+//! the same airport at both ends, more lap infants than adults, …) with an
+//! empty result rather than an error.  [`Config::validate`] and
+//! [`MultiCityConfig::validate`] catch those up front and report every
+//! problem at once as a list of [`Diagnostic`]s, so a CLI, a tool server or
+//! a binding can show the user everything to fix in one go.
+//!
+//! The builders run the checks that do not depend on today's date and refuse
+//! to build when one of them is an [`Severity::Error`]; the date checks only
+//! run in `validate`, so a saved search can still be loaded after its dates
+//! have passed.
+
+use chrono::{Duration, NaiveDate, Utc};
+
+use crate::airports;
+use crate::parsers::common::travelers::check_travellers;
+use crate::parsers::common::{AirlineFilter, Location, PlaceType, StopoverDuration, Travelers};
+
+pub use crate::parsers::common::diagnostic::{into_result, Diagnostic, Severity};
+
+use super::{Config, MultiCityConfig};
+
+/// Maximum number of airports Google Flights accepts per origin/destination side.
+pub const MAX_AIRPORTS_PER_SIDE: usize = 7;
+
+/// How many days ahead Google Flights has fares for.  Airlines publish their
+/// schedules roughly eleven months out; dates beyond that return nothing.
+pub const BOOKING_HORIZON_DAYS: i64 = 331;
+
+impl Config {
+    /// Checks this search against today's date (UTC); see [`Config::validate_at`].
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        self.validate_at(Utc::now().date_naive())
+    }
+
+    /// Checks this search as of `today` and returns every problem found, or
+    /// an empty list when the search looks sound.
+    pub fn validate_at(&self, today: NaiveDate) -> Vec<Diagnostic> {
+        check_config(self, Some(today))
+    }
+
+    /// Runs [`validate`](Self::validate) and fails with
+    /// [`Error::Validation`](crate::Error::Validation) when any diagnostic is an error; otherwise
+    /// returns the warnings.
+    pub fn check(&self) -> crate::Result<Vec<Diagnostic>> {
+        into_result(self.validate())
+    }
+}
+
+impl MultiCityConfig {
+    /// Checks this search against today's date (UTC); see
+    /// [`MultiCityConfig::validate_at`].
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        self.validate_at(Utc::now().date_naive())
+    }
+
+    /// Checks this search as of `today` and returns every problem found, or
+    /// an empty list when the search looks sound.
+    pub fn validate_at(&self, today: NaiveDate) -> Vec<Diagnostic> {
+        check_multi_city(self, Some(today))
+    }
+
+    /// Runs [`validate`](Self::validate) and fails with
+    /// [`Error::Validation`](crate::Error::Validation) when any diagnostic is an error; otherwise
+    /// returns the warnings.
+    pub fn check(&self) -> crate::Result<Vec<Diagnostic>> {
+        into_result(self.validate())
+    }
+}
+
+/// Runs every check on `config`.  The date checks are skipped when `today`
+/// is `None`, which is how the builder calls it.
+pub(super) fn check_config(config: &Config, today: Option<NaiveDate>) -> Vec<Diagnostic> {
+    let mut c = Checker::default();
+    c.sides(
+        "departure",
+        &config.departure,
+        "destination",
+        &config.destination,
+    );
+    if let Some(today) = today {
+        c.date_in_range("departing_date", config.departing_date, today);
+        if let Some(ret) = config.return_date {
+            c.date_in_range("return_date", ret, today);
+        }
+    }
+    if let Some(ret) = config.return_date {
+        if ret < config.departing_date {
+            c.error(
+                "return_date",
+                format!(
+                    "return date {ret} is before the departure date {}",
+                    config.departing_date
+                ),
+            );
+        }
+    }
+    if config.return_date.is_some() && !config.return_times.fits_hour_range() {
+        c.warning(
+            "return_times",
+            "Google filters return flights by one hour range, so round-trip prices may include return flights outside these windows; they are applied exactly only to the return flights listed once an outbound flight is chosen",
+        );
+    }
+    c.layovers("", config.stopover_min, config.stopover_max);
+    c.airlines("", &config.airlines_include, &config.airlines_exclude);
+    if let Some(filters) = &config.return_filters {
+        if config.return_date.is_none() {
+            c.warning(
+                "return_filters",
+                "return-leg filters are ignored on a one-way search",
+            );
+        }
+        let prefix = "return_filters.";
+        c.layovers(prefix, filters.stopover_min, filters.stopover_max);
+        c.airlines(prefix, &filters.airlines_include, &filters.airlines_exclude);
+    }
+    c.travellers(&config.travellers);
+    c.max_price(config.max_price);
+    c.diagnostics
+}
+
+/// Multi-city counterpart of [`check_config`].
+pub(super) fn check_multi_city(
+    config: &MultiCityConfig,
+    today: Option<NaiveDate>,
+) -> Vec<Diagnostic> {
+    let mut c = Checker::default();
+    if config.legs.len() < 2 {
+        c.error("legs", "a multi-city search needs at least 2 legs");
+    }
+    for (i, leg) in config.legs.iter().enumerate() {
+        let prefix = format!("legs[{i}].");
+        c.sides(
+            &format!("{prefix}from"),
+            &leg.from,
+            &format!("{prefix}to"),
+            &leg.to,
+        );
+        if let Some(today) = today {
+            c.date_in_range(&format!("{prefix}date"), leg.date, today);
+        }
+        if let Some(previous) = i.checked_sub(1).map(|p| &config.legs[p]) {
+            if leg.date < previous.date {
+                c.error(
+                    format!("{prefix}date"),
+                    format!(
+                        "leg {i} departs on {} before the previous leg ({})",
+                        leg.date, previous.date
+                    ),
+                );
+            }
+        }
+        c.layovers(&prefix, leg.stopover_min, leg.stopover_max);
+        c.airlines(&prefix, &leg.airlines_include, &leg.airlines_exclude);
+    }
+    c.travellers(&config.travellers);
+    c.max_price(config.max_price);
+    c.diagnostics
+}
+
+#[derive(Default)]
+struct Checker {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker {
+    fn push(&mut self, severity: Severity, field: impl Into<String>, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            field: field.into(),
+            severity,
+            message: message.into(),
+        });
+    }
+
+    fn error(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Error, field, message);
+    }
+
+    fn warning(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Warning, field, message);
+    }
+
+    /// Both ends are set, hold at most [`MAX_AIRPORTS_PER_SIDE`] well-formed
+    /// airports and do not share an airport.
+    fn sides(&mut self, from_field: &str, from: &[Location], to_field: &str, to: &[Location]) {
+        for (field, side) in [(from_field, from), (to_field, to)] {
+            if side.is_empty() {
+                self.error(field, "at least one airport is required");
+            } else if side.len() > MAX_AIRPORTS_PER_SIDE {
+                self.error(
+                    field,
+                    format!(
+                        "{} airports given; Google Flights accepts at most {MAX_AIRPORTS_PER_SIDE}",
+                        side.len()
+                    ),
+                );
+            }
+        }
+        for loc in from.iter().chain(to) {
+            if !matches!(loc.loc_type, PlaceType::Airport) {
+                continue;
+            }
+            let code = &loc.loc_identifier;
+            let field = if from.contains(loc) {
+                from_field
+            } else {
+                to_field
+            };
+            if !(code.len() == 3 && code.bytes().all(|b| b.is_ascii_uppercase())) {
+                self.error(field, format!("{code:?} is not an IATA airport code"));
+            } else if airports::by_iata(code).is_none() && !airports::in_metro(code).is_empty() {
+                self.warning(
+                    field,
+                    format!(
+                        "{code} is a metropolitan-area code, not an airport; \
+                         pass it to the builder's departure/destination to expand it"
+                    ),
+                );
+            }
+        }
+        let shared: Vec<&str> = to
+            .iter()
+            .filter(|t| from.iter().any(|f| f.loc_identifier == t.loc_identifier))
+            .map(|t| t.loc_identifier.as_str())
+            .collect();
+        if !shared.is_empty() {
+            self.error(
+                to_field,
+                format!("{} is both an origin and a destination", shared.join(", ")),
+            );
+        }
+    }
+
+    fn date_in_range(&mut self, field: &str, date: NaiveDate, today: NaiveDate) {
+        if date < today {
+            self.error(field, format!("{date} is in the past"));
+        } else if date > today + Duration::days(BOOKING_HORIZON_DAYS) {
+            self.error(
+                field,
+                format!(
+                    "{date} is more than {BOOKING_HORIZON_DAYS} days ahead; \
+                     fares are not published that far out"
+                ),
+            );
+        }
+    }
+
+    fn layovers(&mut self, prefix: &str, min: StopoverDuration, max: StopoverDuration) {
+        if let (Some(min), Some(max)) = (min.to_option(), max.to_option()) {
+            if min > max {
+                self.error(
+                    format!("{prefix}stopover_min"),
+                    format!("minimum layover ({min} min) exceeds the maximum ({max} min)"),
+                );
+            }
+        }
+        if let Some(min) = min.to_option() {
+            if min % 30 != 0 {
+                self.warning(
+                    format!("{prefix}stopover_min"),
+                    format!(
+                        "minimum layover {min} min is rounded up to {} min",
+                        min.div_ceil(30) * 30
+                    ),
+                );
+            }
+        }
+    }
+
+    fn airlines(&mut self, prefix: &str, include: &[AirlineFilter], exclude: &[AirlineFilter]) {
+        for filter in exclude.iter().filter(|f| include.contains(f)) {
+            self.warning(
+                format!("{prefix}airlines_exclude"),
+                format!("{} is both included and excluded", filter.as_google_str()),
+            );
+        }
+    }
+
+    fn travellers(&mut self, t: &Travelers) {
+        self.diagnostics.extend(check_travellers(t));
+    }
+
+    fn max_price(&mut self, max_price: Option<i32>) {
+        if let Some(price) = max_price.filter(|p| *p <= 0) {
+            self.error("max_price", format!("{price} is not a positive price cap"));
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::parsers::common::{AirlineCode, FlightTimes, TimeOfDay};
+    use crate::requests::config::LegFilters;
+    use crate::Error;
+
+    fn airport(code: &str) -> Location {
+        Location {
+            loc_identifier: code.to_owned(),
+            loc_type: PlaceType::Airport,
+            location_name: None,
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn fields(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.field.as_str()).collect()
+    }
+
+    fn sound_config() -> Config {
+        Config::builder()
+            .departing_date(date(2030, 5, 1))
+            .return_date(date(2030, 5, 8))
+            .departure_location(airport("ZRH"))
+            .destination_location(airport("JFK"))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn inexact_return_windows_are_warned_about() {
+        let mut cfg = sound_config();
+        cfg.return_times = FlightTimes::default().depart_during(TimeOfDay::RedEye);
+        let diagnostics = cfg.validate_at(date(2030, 1, 1));
+        assert_eq!(fields(&diagnostics), ["return_times"]);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+
+        cfg.return_times = FlightTimes::default().depart_during(TimeOfDay::Morning);
+        assert!(cfg.validate_at(date(2030, 1, 1)).is_empty());
+    }
+
+    #[test]
+    fn sound_config_has_no_diagnostics() {
+        assert!(sound_config().validate_at(date(2030, 1, 1)).is_empty());
+    }
+
+    /// Every problem is reported at once, each against its own field.
+    #[test]
+    fn config_reports_every_problem() {
+        let mut cfg = sound_config();
+        cfg.departing_date = date(2029, 12, 1);
+        cfg.return_date = Some(date(2029, 11, 1));
+        cfg.destination = vec![airport("ZRH")];
+        cfg.departure = ["ZRH", "BSL", "GVA", "BRN", "LUG", "SIR", "ACH", "EML"]
+            .into_iter()
+            .map(airport)
+            .collect();
+        cfg.stopover_min = StopoverDuration::Minutes(240);
+        cfg.stopover_max = StopoverDuration::Minutes(120);
+        cfg.travellers = Travelers {
+            adults: 1,
+            children: 0,
+            infant_on_lap: 2,
+            infant_in_seat: 0,
+        };
+        cfg.max_price = Some(0);
+
+        let diagnostics = cfg.validate_at(date(2030, 1, 1));
+        assert!(
+            diagnostics.iter().all(Diagnostic::is_error),
+            "{diagnostics:?}"
+        );
+        assert_eq!(
+            fields(&diagnostics),
+            [
+                "departure",
+                "destination",
+                "departing_date",
+                "return_date",
+                "return_date",
+                "stopover_min",
+                "travellers.infant_on_lap",
+                "max_price",
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_airport_codes_are_errors() {
+        let mut cfg = sound_config();
+        cfg.destination = vec![airport("jfk"), airport("NEWYORK")];
+        let diagnostics = cfg.validate_at(date(2030, 1, 1));
+        assert_eq!(fields(&diagnostics), ["destination", "destination"]);
+        assert!(diagnostics[1].message.contains("NEWYORK"));
+
+        cfg.destination = vec![airport("NYC")];
+        let diagnostics = cfg.validate_at(date(2030, 1, 1));
+        assert_eq!(fields(&diagnostics), ["destination"]);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn dates_beyond_the_horizon_are_errors() {
+        let today = date(2030, 1, 1);
+        let mut cfg = sound_config();
+        cfg.departing_date = today + Duration::days(BOOKING_HORIZON_DAYS);
+        cfg.return_date = Some(today + Duration::days(BOOKING_HORIZON_DAYS + 1));
+        assert_eq!(fields(&cfg.validate_at(today)), ["return_date"]);
+    }
+
+    #[test]
+    fn warnings_do_not_fail_check() {
+        let mut cfg = sound_config();
+        cfg.departing_date = Utc::now().date_naive() + Duration::days(30);
+        cfg.return_date = None;
+        cfg.stopover_min = StopoverDuration::Minutes(45);
+        let lx = AirlineFilter::Airline(AirlineCode::new("LX").unwrap());
+        cfg.airlines_include = vec![lx.clone()];
+        cfg.airlines_exclude = vec![lx];
+
+        let warnings = cfg.check().unwrap();
+        assert_eq!(fields(&warnings), ["stopover_min", "airlines_exclude"]);
+        assert!(warnings.iter().all(|d| d.severity == Severity::Warning));
+
+        cfg.max_price = Some(-5);
+        match cfg.check() {
+            Err(Error::Validation(errors)) => assert_eq!(fields(&errors), ["max_price"]),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn return_filters_are_checked_with_a_prefix() {
+        let mut cfg = sound_config();
+        cfg.return_filters = Some(LegFilters {
+            stopover_min: StopoverDuration::Minutes(240),
+            stopover_max: StopoverDuration::Minutes(120),
+            ..LegFilters::default()
+        });
+        let diagnostics = cfg.validate_at(date(2030, 1, 1));
+        assert_eq!(fields(&diagnostics), ["return_filters.stopover_min"]);
+        assert!(diagnostics[0].is_error());
+
+        cfg.return_date = None;
+        cfg.return_filters = Some(LegFilters::default());
+        let diagnostics = cfg.validate_at(date(2030, 1, 1));
+        assert_eq!(fields(&diagnostics), ["return_filters"]);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    /// The builder rejects date-independent errors, listing all of them, but
+    /// leaves dates in the past alone.
+    #[test]
+    fn builder_rejects_structural_errors_only() {
+        let err = Config::builder()
+            .departing_date(date(2001, 1, 1))
+            .departure_location(airport("ZRH"))
+            .destination_location(airport("ZRH"))
+            .max_price(0)
+            .build()
+            .unwrap_err();
+        match err.downcast_ref::<Error>() {
+            Some(Error::Validation(errors)) => {
+                assert_eq!(fields(errors), ["destination", "max_price"])
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+
+        assert!(Config::builder()
+            .departing_date(date(2001, 1, 1))
+            .departure_location(airport("ZRH"))
+            .destination_location(airport("JFK"))
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn multi_city_reports_per_leg_fields() {
+        let today = date(2030, 1, 1);
+        let mut cfg = MultiCityConfig::builder()
+            .add_leg_locations(vec![airport("ZRH")], vec![airport("JFK")], date(2030, 3, 1))
+            .add_leg_locations(vec![airport("JFK")], vec![airport("LAX")], date(2030, 3, 5))
+            .build()
+            .unwrap();
+        assert!(cfg.validate_at(today).is_empty());
+
+        cfg.legs[1].date = date(2030, 2, 1);
+        cfg.legs[1].to = vec![airport("JFK")];
+        cfg.legs[0].stopover_min = StopoverDuration::Minutes(300);
+        cfg.legs[0].stopover_max = StopoverDuration::Minutes(60);
+        assert_eq!(
+            fields(&cfg.validate_at(today)),
+            ["legs[0].stopover_min", "legs[1].to", "legs[1].date"]
+        );
+
+        let err = MultiCityConfig::builder()
+            .add_leg_locations_with_filters(
+                vec![airport("ZRH")],
+                vec![airport("JFK")],
+                date(2030, 3, 5),
+                LegFilters::default(),
+            )
+            .add_leg_locations(vec![airport("JFK")], vec![airport("LAX")], date(2030, 3, 1))
+            .travellers(Travelers {
+                adults: 0,
+                children: 1,
+                infant_on_lap: 0,
+                infant_in_seat: 0,
+            })
+            .build()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("legs[1].date"), "got: {err}");
+        assert!(err.contains("travellers.adults"), "got: {err}");
+    }
+}
@@ -0,0 +1,123 @@
+//! Classification of outgoing request URLs by Google Flights endpoint.
+
+use crate::parsers::constants::{
+    BATCHEXECUTE, BOOKING_REQUEST, CALENDAR_GRAPH, CALENDAR_GRID, CLK_URL, EXPLORE_URL,
+    FLIGHTS_MAIN_PAGE, FLIGHT_DEALS_URL, FLIGHT_REQUEST,
+};
+
+/// The Google Flights endpoint a request is sent to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// City / airport lookup (`batchexecute`, rpc `H028ib`).
+    City,
+    /// Any other `batchexecute` call.
+    BatchExecute,
+    /// `GetCalendarGraph` — price graph.
+    CalendarGraph,
+    /// `GetCalendarGrid` — departure × return price matrix.
+    CalendarGrid,
+    /// `GetShoppingResults` — flight search.
+    Shopping,
+    /// `GetBookingResults` — booking offers.
+    Booking,
+    /// `GetExploreDestinations`.
+    Explore,
+    /// `GetFlightDealsStreaming`.
+    Deals,
+    /// Click tracker that resolves booking URLs.
+    BookingUrl,
+    /// Main page fetched for the frontend-version probe.
+    MainPage,
+    /// Anything not recognised above.
+    Other,
+}
+
+impl Endpoint {
+    /// Every variant, in declaration order.
+    pub const ALL: [Endpoint; 11] = [
+        Endpoint::City,
+        Endpoint::BatchExecute,
+        Endpoint::CalendarGraph,
+        Endpoint::CalendarGrid,
+        Endpoint::Shopping,
+        Endpoint::Booking,
+        Endpoint::Explore,
+        Endpoint::Deals,
+        Endpoint::BookingUrl,
+        Endpoint::MainPage,
+        Endpoint::Other,
+    ];
+
+    /// Classifies a request URL (query string ignored, except for the
+    /// `batchexecute` rpc id).
+    pub fn from_url(url: &str) -> Self {
+        let (base, query) = url.split_once('?').unwrap_or((url, ""));
+        match base {
+            BATCHEXECUTE if query.split('&').any(|p| p == "rpcids=H028ib") => Endpoint::City,
+            BATCHEXECUTE => Endpoint::BatchExecute,
+            CALENDAR_GRAPH => Endpoint::CalendarGraph,
+            CALENDAR_GRID => Endpoint::CalendarGrid,
+            FLIGHT_REQUEST => Endpoint::Shopping,
+            BOOKING_REQUEST => Endpoint::Booking,
+            EXPLORE_URL => Endpoint::Explore,
+            FLIGHT_DEALS_URL => Endpoint::Deals,
+            CLK_URL => Endpoint::BookingUrl,
+            FLIGHTS_MAIN_PAGE => Endpoint::MainPage,
+            _ => Endpoint::Other,
+        }
+    }
+
+    /// Short snake_case name, suitable as a log field or metrics label.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Endpoint::City => "city",
+            Endpoint::BatchExecute => "batchexecute",
+            Endpoint::CalendarGraph => "calendar_graph",
+            Endpoint::CalendarGrid => "calendar_grid",
+            Endpoint::Shopping => "shopping",
+            Endpoint::Booking => "booking",
+            Endpoint::Explore => "explore",
+            Endpoint::Deals => "deals",
+            Endpoint::BookingUrl => "booking_url",
+            Endpoint::MainPage => "main_page",
+            Endpoint::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_request_urls_ignoring_query() {
+        assert_eq!(
+            Endpoint::from_url(&format!("{FLIGHT_REQUEST}?f.sid=1&bl=x&rt=c")),
+            Endpoint::Shopping
+        );
+        assert_eq!(
+            Endpoint::from_url(&format!("{CLK_URL}?t=1700000000000")),
+            Endpoint::BookingUrl
+        );
+        assert_eq!(Endpoint::from_url(FLIGHTS_MAIN_PAGE), Endpoint::MainPage);
+        assert_eq!(Endpoint::from_url("https://example.com/x"), Endpoint::Other);
+    }
+
+    #[test]
+    fn batchexecute_city_lookup_is_told_apart_by_rpc_id() {
+        assert_eq!(
+            Endpoint::from_url(&format!("{BATCHEXECUTE}?rpcids=H028ib&rt=c")),
+            Endpoint::City
+        );
+        assert_eq!(
+            Endpoint::from_url(&format!("{BATCHEXECUTE}?rpcids=abc&rt=c")),
+            Endpoint::BatchExecute
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! Flexible-date search, used by
+//! [`ApiClient::request_flights_flexible`](super::api::ApiClient::request_flights_flexible).
+//!
+//! The search widens a [`Config`]'s dates by ±N days, prices every date (or
+//! date pair) in that window with one calendar-graph or date-grid call, and
+//! then fetches real itineraries for the cheapest few.  The itineraries come
+//! back as one list ranked by price, each tagged with the dates it flies on.
+
+use chrono::{Duration, Months, NaiveDate};
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::parsers::common::FlightTimes;
+use crate::parsers::date_grid_response::CheapDate;
+use crate::parsers::flight_response::{FlightResponseContainer, ItineraryContainer};
+use crate::requests::config::Config;
+
+/// How far [`ApiClient::request_flights_flexible`](super::api::ApiClient::request_flights_flexible)
+/// may move the dates, and how many date pairs it searches in full.
+///
+/// ```rust
+/// use gflights::requests::flexible::FlexibleDates;
+///
+/// // ±3 days on the way out, ±1 on the way back, itineraries for the 5
+/// // cheapest date pairs.
+/// let flex = FlexibleDates::new(3).with_return_days(1).with_best(5);
+/// assert_eq!(flex.departure_days, 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, serde::Deserialize)]
+pub struct FlexibleDates {
+    /// Days either side of the departure date.
+    pub departure_days: u32,
+    /// Days either side of the return date.  Ignored for one-way searches.
+    pub return_days: u32,
+    /// Number of cheapest date pairs to fetch itineraries for.
+    pub best: usize,
+}
+
+impl FlexibleDates {
+    /// Default number of date pairs searched in full.
+    pub const DEFAULT_BEST: usize = 3;
+
+    /// ±`days` on both dates, searching the [`DEFAULT_BEST`](Self::DEFAULT_BEST)
+    /// cheapest pairs.
+    pub fn new(days: u32) -> Self {
+        Self {
+            departure_days: days,
+            return_days: days,
+            best: Self::DEFAULT_BEST,
+        }
+    }
+
+    pub fn with_return_days(mut self, days: u32) -> Self {
+        self.return_days = days;
+        self
+    }
+
+    /// Must be at least 1; a search with `best == 0` is rejected.
+    pub fn with_best(mut self, best: usize) -> Self {
+        self.best = best;
+        self
+    }
+}
+
+/// An itinerary from a flexible-date search, with the dates it was found on.
+#[derive(Debug, Clone, Serialize)]
+pub struct DatedItinerary {
+    pub departure_date: NaiveDate,
+    /// `None` for one-way searches.
+    pub return_date: Option<NaiveDate>,
+    pub itinerary: ItineraryContainer,
+}
+
+impl DatedItinerary {
+    /// Trip price, when Google returned one.
+    pub fn price(&self) -> Option<i32> {
+        self.itinerary
+            .itinerary_cost
+            .trip_cost
+            .as_ref()
+            .map(|c| c.price)
+    }
+}
+
+/// Result of a flexible-date search.
+#[derive(Debug, Clone, Serialize)]
+pub struct FlexibleResults {
+    /// The date pairs that were searched in full, cheapest first, with the
+    /// calendar price that selected them.
+    pub dates: Vec<CheapDate>,
+    /// Itineraries for every searched date pair, cheapest first.
+    /// Itineraries without a price come last.
+    pub itineraries: Vec<DatedItinerary>,
+    /// Date pairs from `dates` whose itinerary search failed.  They have no
+    /// entries in `itineraries`.
+    pub failed: Vec<FailedDate>,
+}
+
+/// A date pair whose itinerary search failed during a flexible-date search.
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedDate {
+    pub date: CheapDate,
+    pub error: String,
+}
+
+/// An inclusive range of dates.
+type DateWindow = (NaiveDate, NaiveDate);
+
+/// Candidate date windows for `config` widened by `flex`, never starting
+/// before `today`.  Returns `(departure window, return window)`; the return
+/// window is `None` for one-way searches.
+///
+/// Fails when `flex.best` is 0 or a window lies entirely before `today`.
+pub(crate) fn windows(
+    config: &Config,
+    flex: &FlexibleDates,
+    today: NaiveDate,
+) -> Result<(DateWindow, Option<DateWindow>)> {
+    if flex.best == 0 {
+        return Err(Error::InvalidConfig(
+            "flexible search must fetch itineraries for at least one date (best is 0)".into(),
+        ));
+    }
+    let around = |date: NaiveDate, days: u32| {
+        let end = date + Duration::days(i64::from(days));
+        if end < today {
+            return Err(Error::InvalidConfig(format!(
+                "every date within {days} days of {date} is in the past"
+            )));
+        }
+        Ok(((date - Duration::days(i64::from(days))).max(today), end))
+    };
+    Ok((
+        around(config.departing_date, flex.departure_days)?,
+        config
+            .return_date
+            .map(|r| around(r, flex.return_days))
+            .transpose()?,
+    ))
+}
+
+/// Calendar-graph span needed to cover a one-way window starting at its
+/// first day.
+pub(crate) fn graph_months(start: NaiveDate, end: NaiveDate) -> Months {
+    let days = (end - start).num_days().max(0) as u32;
+    Months::new(days / 28 + 1)
+}
+
+/// The `best` cheapest candidates inside the windows, cheapest first.
+/// Pairs whose return precedes the departure are dropped.
+pub(crate) fn cheapest(
+    mut candidates: Vec<CheapDate>,
+    departure: (NaiveDate, NaiveDate),
+    ret: Option<(NaiveDate, NaiveDate)>,
+    best: usize,
+) -> Vec<CheapDate> {
+    let inside =
+        |date: NaiveDate, (start, end): (NaiveDate, NaiveDate)| (start..=end).contains(&date);
+    candidates.retain(|c| {
+        inside(c.departure_date, departure)
+            && match (ret, c.return_date) {
+                (Some(window), Some(r)) => inside(r, window) && r >= c.departure_date,
+                (None, _) => true,
+                (Some(_), None) => false,
+            }
+    });
+    candidates.sort_by_key(|c| (c.price, c.departure_date, c.return_date));
+    candidates.dedup_by_key(|c| (c.departure_date, c.return_date));
+    candidates.truncate(best);
+    candidates
+}
+
+/// `config` moved to the dates of `date`.
+pub(crate) fn config_for(config: &Config, date: &CheapDate) -> Config {
+    Config {
+        departing_date: date.departure_date,
+        return_date: config.return_date.and(date.return_date),
+        ..config.clone()
+    }
+}
+
+/// Tags every itinerary with its dates and ranks them all by price.
+/// Itineraries outside `connecting_airports` or `times` are dropped.
+pub(crate) fn merge(
+    results: Vec<(CheapDate, FlightResponseContainer)>,
+    connecting_airports: &[String],
+    times: &FlightTimes,
+) -> Vec<DatedItinerary> {
+    let mut merged: Vec<DatedItinerary> = results
+        .into_iter()
+        .flat_map(|(date, response)| {
+            response
+                .get_flights_filtered(connecting_airports, times)
+                .into_iter()
+                .map(move |itinerary| DatedItinerary {
+                    departure_date: date.departure_date,
+                    return_date: date.return_date,
+                    itinerary,
+                })
+        })
+        .collect();
+    merged.sort_by_key(|d| (d.price().is_none(), d.price(), d.departure_date));
+    merged
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::parsers::common::{Location, PlaceType};
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2030, 5, d).unwrap()
+    }
+
+    fn cheap(dep: u32, ret: Option<u32>, price: i32) -> CheapDate {
+        CheapDate {
+            departure_date: date(dep),
+            return_date: ret.map(date),
+            price,
+        }
+    }
+
+    fn config(ret: Option<u32>) -> Config {
+        let airport = |code: &str| Location {
+            loc_identifier: code.to_owned(),
+            loc_type: PlaceType::Airport,
+            location_name: None,
+        };
+        let mut builder = Config::builder()
+            .departure_location(airport("LHR"))
+            .destination_location(airport("JFK"))
+            .departing_date(date(10));
+        if let Some(r) = ret {
+            builder = builder.return_date(date(r));
+        }
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn windows_widen_both_dates_and_stop_at_today() {
+        let flex = FlexibleDates::new(3).with_return_days(1);
+        let (dep, ret) = windows(&config(Some(17)), &flex, date(1)).unwrap();
+        assert_eq!(dep, (date(7), date(13)));
+        assert_eq!(ret, Some((date(16), date(18))));
+
+        let (dep, ret) = windows(&config(None), &flex, date(9)).unwrap();
+        assert_eq!(dep, (date(9), date(13)));
+        assert_eq!(ret, None);
+
+        assert_eq!(graph_months(date(7), date(13)), Months::new(1));
+        assert_eq!(graph_months(date(1), date(31)), Months::new(2));
+    }
+
+    #[test]
+    fn windows_reject_past_trips_and_zero_best() {
+        let flex = FlexibleDates::new(3);
+        let err = windows(&config(None), &flex, date(14)).unwrap_err();
+        assert!(matches!(err, Error::InvalidConfig(_)), "got: {err}");
+        assert!(windows(&config(None), &flex, date(13)).is_ok());
+        // A round trip is rejected when its outbound window is over.
+        assert!(windows(&config(Some(20)), &flex, date(14)).is_err());
+
+        let err = windows(&config(None), &flex.with_best(0), date(1)).unwrap_err();
+        assert!(err.to_string().contains("best is 0"), "got: {err}");
+    }
+
+    #[test]
+    fn cheapest_keeps_best_pairs_inside_the_windows() {
+        let candidates = vec![
+            cheap(8, Some(16), 300),
+            cheap(9, Some(17), 250),
+            cheap(9, Some(17), 250),
+            cheap(20, Some(25), 90), // departure outside the window
+            cheap(12, Some(19), 80), // return outside the window
+            cheap(13, Some(16), 260),
+            cheap(11, Some(17), 240),
+        ];
+        let best = cheapest(
+            candidates,
+            (date(7), date(13)),
+            Some((date(16), date(18))),
+            3,
+        );
+        let picked: Vec<_> = best.iter().map(|c| c.price).collect();
+        assert_eq!(picked, [240, 250, 260]);
+    }
+
+    #[test]
+    fn config_for_moves_the_dates_only() {
+        let round_trip = config(Some(17));
+        let moved = config_for(&round_trip, &cheap(9, Some(18), 100));
+        assert_eq!(moved.departing_date, date(9));
+        assert_eq!(moved.return_date, Some(date(18)));
+        assert_eq!(moved.destination, round_trip.destination);
+
+        let one_way = config_for(&config(None), &cheap(12, None, 100));
+        assert_eq!(one_way.departing_date, date(12));
+        assert_eq!(one_way.return_date, None);
+    }
+
+    #[test]
+    fn merge_tags_dates_and_ranks_by_price() {
+        let load = |file: &str| FlightResponseContainer {
+            responses: vec![serde_json::from_str(&std::fs::read_to_string(file).unwrap()).unwrap()],
+        };
+        let merged = merge(
+            vec![
+                (cheap(9, None, 0), load("test_files/lux_milan_oneway.txt")),
+                (cheap(11, None, 0), load("test_files/lux_tokyo_oneway.txt")),
+            ],
+            &[],
+            &FlightTimes::default(),
+        );
+        assert!(merged.iter().any(|d| d.departure_date == date(9)));
+        assert!(merged.iter().any(|d| d.departure_date == date(11)));
+        let prices: Vec<_> = merged.iter().map(DatedItinerary::price).collect();
+        let priced = prices.iter().take_while(|p| p.is_some()).count();
+        assert!(prices[..priced].windows(2).all(|w| w[0] <= w[1]));
+        assert!(prices[priced..].iter().all(Option::is_none));
+    }
+}
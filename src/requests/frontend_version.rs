@@ -0,0 +1,359 @@
+//! Frontend build label (`bl=` query parameter) used by
+//! [`ApiClient`](super::api::ApiClient), and its optional refresh policy.
+//!
+//! The label is scraped from the Google Flights main page when the client is
+//! built; if that fails a hard-coded [`FALLBACK_FRONTEND_VERSION`] is used.
+//! Google rolls the label forward every few days and eventually rejects old
+//! ones, so a client that lives for days should opt in to a
+//! [`VersionRefreshPolicy`]:
+//!
+//! * the main page is re-probed once [`VersionRefreshPolicy::interval`] has
+//!   passed since the last probe (checked lazily, on the next request);
+//! * a response that looks like a rejected build label (see
+//!   [`is_stale_response`]) triggers an immediate re-probe, at most once per
+//!   [`VersionRefreshPolicy::min_reprobe_interval`], and the request is sent
+//!   again with the new label if it changed.  If the main page still serves
+//!   the label the request carried, the failure is the request's own: it is
+//!   not retried and the label is not flagged stale.
+//!
+//! Stale responses are flagged in [`FrontendVersion::stale`] whether or not a
+//! policy is set.
+
+use reqwest::StatusCode;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Build label used when the main-page probe does not yield one.
+pub const FALLBACK_FRONTEND_VERSION: &str = "boq_travel-frontend-flights-ui_20260527.01_p0";
+
+/// Where the label in use came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionSource {
+    /// Scraped from the Google Flights main page.
+    Probed,
+    /// [`FALLBACK_FRONTEND_VERSION`]; no probe has succeeded yet.
+    Fallback,
+}
+
+/// Snapshot of the frontend build label an `ApiClient` is sending.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrontendVersion {
+    pub label: String,
+    pub source: VersionSource,
+    /// When `label` was last set (construction or a successful probe).
+    pub updated_at: SystemTime,
+    /// `true` if the latest response looked like a rejected build label and no
+    /// request has succeeded with a fresh label since.
+    pub stale: bool,
+}
+
+/// When to re-probe the frontend version.
+///
+/// ```rust
+/// use gflights::requests::frontend_version::VersionRefreshPolicy;
+/// use std::time::Duration;
+///
+/// let policy = VersionRefreshPolicy {
+///     interval: Duration::from_secs(3 * 3600),
+///     ..VersionRefreshPolicy::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRefreshPolicy {
+    /// Re-probe once this long has passed since the last probe.
+    pub interval: Duration,
+    /// Re-probe when a response looks like a rejected build label.
+    pub refresh_on_stale: bool,
+    /// Minimum gap between stale-triggered probes, so a burst of failing
+    /// requests does not hammer the main page.
+    pub min_reprobe_interval: Duration,
+}
+
+impl Default for VersionRefreshPolicy {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(6 * 3600),
+            refresh_on_stale: true,
+            min_reprobe_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    current: FrontendVersion,
+    last_probe: Instant,
+    /// A probe is in flight; others skip rather than queue behind it.
+    probing: bool,
+}
+
+/// Label and probe bookkeeping shared by an `ApiClient` and its clones.
+#[derive(Debug)]
+pub(crate) struct VersionState {
+    policy: Option<VersionRefreshPolicy>,
+    state: Mutex<State>,
+}
+
+impl VersionState {
+    /// State after the construction-time probe returned `probed`.
+    pub(crate) fn new(probed: Option<String>, now: Instant) -> Self {
+        let (label, source) = match probed {
+            Some(label) => (label, VersionSource::Probed),
+            None => (
+                FALLBACK_FRONTEND_VERSION.to_owned(),
+                VersionSource::Fallback,
+            ),
+        };
+        Self::with_policy(
+            None,
+            FrontendVersion {
+                label,
+                source,
+                updated_at: SystemTime::now(),
+                stale: false,
+            },
+            now,
+        )
+    }
+
+    pub(crate) fn with_policy(
+        policy: Option<VersionRefreshPolicy>,
+        current: FrontendVersion,
+        now: Instant,
+    ) -> Self {
+        Self {
+            policy,
+            state: Mutex::new(State {
+                current,
+                last_probe: now,
+                probing: false,
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // Plain fields; a panic mid-update cannot leave them inconsistent.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn current(&self) -> FrontendVersion {
+        self.lock().current.clone()
+    }
+
+    pub(crate) fn label(&self) -> String {
+        self.lock().current.label.clone()
+    }
+
+    /// Claims the periodic probe if the policy's interval has elapsed at `now`.
+    pub(crate) fn claim_periodic(&self, now: Instant) -> Option<ProbeClaim<'_>> {
+        let policy = self.policy.as_ref()?;
+        let mut state = self.lock();
+        if state.probing || now.saturating_duration_since(state.last_probe) < policy.interval {
+            return None;
+        }
+        state.probing = true;
+        Some(ProbeClaim { state: self })
+    }
+
+    /// Records whether the latest response looked like a rejected label.
+    pub(crate) fn set_stale(&self, stale: bool) {
+        let mut state = self.lock();
+        if stale && !state.current.stale {
+            tracing::warn!(label = %state.current.label, "response suggests the frontend version is outdated");
+        }
+        state.current.stale = stale;
+    }
+
+    /// Claims a stale-triggered probe if the policy allows one at `now`.
+    pub(crate) fn claim_stale_probe(&self, now: Instant) -> Option<ProbeClaim<'_>> {
+        let policy = self.policy.as_ref()?;
+        let mut state = self.lock();
+        if !policy.refresh_on_stale
+            || state.probing
+            || now.saturating_duration_since(state.last_probe) < policy.min_reprobe_interval
+        {
+            return None;
+        }
+        state.probing = true;
+        Some(ProbeClaim { state: self })
+    }
+
+    /// Records the outcome of a probe started at `now`; a failed probe keeps
+    /// the current label.
+    pub(crate) fn finish_probe(&self, probed: Option<String>, now: Instant) -> FrontendVersion {
+        let mut state = self.lock();
+        state.last_probe = now;
+        match probed {
+            Some(label) => {
+                if label != state.current.label {
+                    tracing::info!(old = %state.current.label, new = %label, "frontend version updated");
+                    state.current.stale = false;
+                }
+                state.current.label = label;
+                state.current.source = VersionSource::Probed;
+                state.current.updated_at = SystemTime::now();
+            }
+            None => {
+                tracing::warn!(label = %state.current.label, "frontend version re-probe failed; keeping current label");
+            }
+        }
+        state.current.clone()
+    }
+}
+
+/// The right to run the one in-flight probe.  Dropping it, including with a
+/// future cancelled mid-probe, lets the next request probe again.
+#[must_use = "the probe is released as soon as the claim is dropped"]
+pub(crate) struct ProbeClaim<'a> {
+    state: &'a VersionState,
+}
+
+impl Drop for ProbeClaim<'_> {
+    fn drop(&mut self) {
+        self.state.lock().probing = false;
+    }
+}
+
+/// Returns `true` if a response looks like the backend rejected the build
+/// label: HTTP 400, or a batchexecute body carrying only an `["er", …]`
+/// envelope and no `wrb.fr` data frame.
+///
+/// A malformed request fails the same way, so under a
+/// [`VersionRefreshPolicy`] the client only treats the label as stale once a
+/// re-probe finds a different one.
+pub fn is_stale_response(status: StatusCode, body: &str) -> bool {
+    if status == StatusCode::BAD_REQUEST {
+        return true;
+    }
+    let mut error_frame = false;
+    for line in body.lines().map(str::trim) {
+        if line.starts_with(r#"[["wrb.fr""#) {
+            return false;
+        }
+        error_frame |= line.starts_with(r#"[["er""#);
+    }
+    error_frame
+}
+
+/// Value of the `bl=` query parameter in a request URL.
+pub(crate) fn label_in_url(url: &str) -> Option<&str> {
+    let (_, query) = url.split_once('?')?;
+    query.split('&').find_map(|pair| pair.strip_prefix("bl="))
+}
+
+/// Swaps the `bl=` query parameter of `url` for `label`.
+pub(crate) fn replace_label_in_url(url: &str, label: &str) -> Option<String> {
+    let old = label_in_url(url)?;
+    Some(url.replacen(&format!("bl={old}"), &format!("bl={label}"), 1))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn policy() -> VersionRefreshPolicy {
+        VersionRefreshPolicy {
+            interval: Duration::from_secs(100),
+            refresh_on_stale: true,
+            min_reprobe_interval: Duration::from_secs(10),
+        }
+    }
+
+    #[test]
+    fn failed_initial_probe_reports_fallback() {
+        let state = VersionState::new(None, Instant::now());
+        let current = state.current();
+        assert_eq!(current.label, FALLBACK_FRONTEND_VERSION);
+        assert_eq!(current.source, VersionSource::Fallback);
+        assert!(!current.stale);
+    }
+
+    #[test]
+    fn periodic_probe_only_when_due_and_with_policy() {
+        let t0 = Instant::now();
+        let without = VersionState::new(Some("a".into()), t0);
+        assert!(without
+            .claim_periodic(t0 + Duration::from_secs(1_000))
+            .is_none());
+
+        let state = VersionState::with_policy(Some(policy()), without.current(), t0);
+        assert!(state.claim_periodic(t0 + Duration::from_secs(99)).is_none());
+        let t1 = t0 + Duration::from_secs(100);
+        let claim = state.claim_periodic(t1);
+        assert!(claim.is_some());
+        assert!(
+            state.claim_periodic(t1).is_none(),
+            "probe already in flight"
+        );
+        let v = state.finish_probe(Some("b".into()), t1);
+        drop(claim);
+        assert_eq!((v.label.as_str(), v.source), ("b", VersionSource::Probed));
+        assert!(state.claim_periodic(t1 + Duration::from_secs(99)).is_none());
+    }
+
+    #[test]
+    fn stale_reprobe_is_throttled() {
+        let t0 = Instant::now();
+        let state =
+            VersionState::with_policy(Some(policy()), VersionState::new(None, t0).current(), t0);
+        state.set_stale(true);
+        assert!(
+            state
+                .claim_stale_probe(t0 + Duration::from_secs(5))
+                .is_none(),
+            "too soon"
+        );
+        assert!(state.current().stale);
+
+        let t1 = t0 + Duration::from_secs(10);
+        let claim = state.claim_stale_probe(t1);
+        assert!(claim.is_some());
+        assert!(
+            state.claim_stale_probe(t1).is_none(),
+            "probe already in flight"
+        );
+        state.finish_probe(None, t1);
+        drop(claim);
+        assert!(state.current().stale, "failed probe keeps the flag");
+        assert_eq!(state.current().source, VersionSource::Fallback);
+
+        let t2 = t1 + Duration::from_secs(10);
+        let _claim = state.claim_stale_probe(t2).unwrap();
+        let v = state.finish_probe(Some("new".into()), t2);
+        assert!(!v.stale);
+        assert_eq!(v.source, VersionSource::Probed);
+    }
+
+    #[test]
+    fn abandoned_probe_releases_its_claim() {
+        let t0 = Instant::now();
+        let state =
+            VersionState::with_policy(Some(policy()), VersionState::new(None, t0).current(), t0);
+        let t1 = t0 + Duration::from_secs(100);
+        // Cancelled before `finish_probe`, e.g. by a timeout.
+        drop(state.claim_periodic(t1));
+        assert!(state.claim_periodic(t1).is_some(), "not probed, still due");
+    }
+
+    #[test]
+    fn detects_stale_response_shapes() {
+        assert!(is_stale_response(StatusCode::BAD_REQUEST, ""));
+        let er = ")]}'\n\n104\n[[\"er\",null,null,null,null,400,null,null,null,3],[\"di\",24]]\n";
+        assert!(is_stale_response(StatusCode::OK, er));
+        let data = ")]}'\n\n[[\"wrb.fr\",null,\"[]\"]]\n[[\"er\",null]]\n";
+        assert!(!is_stale_response(StatusCode::OK, data));
+        assert!(!is_stale_response(StatusCode::OK, ""));
+    }
+
+    #[test]
+    fn rewrites_label_in_url() {
+        let url = "https://x/rpc?f.sid=1&bl=boq_old_p0&hl=en-GB&rt=c";
+        assert_eq!(label_in_url(url), Some("boq_old_p0"));
+        assert_eq!(
+            replace_label_in_url(url, "boq_new_p1").unwrap(),
+            "https://x/rpc?f.sid=1&bl=boq_new_p1&hl=en-GB&rt=c"
+        );
+        assert_eq!(replace_label_in_url("https://x/rpc?rt=c", "b"), None);
+    }
+}
@@ -0,0 +1,400 @@
+//! Metrics hooks for [`ApiClient`](super::api::ApiClient), and a bundled
+//! observer that renders them in the Prometheus text exposition format.
+//!
+//! Attach an observer with
+//! [`ApiClient::with_metrics`](super::api::ApiClient::with_metrics).  Every
+//! hook has an empty default, so an observer implements only what it needs.
+//! Hooks run inline on the request path and should return quickly.
+//!
+//! ```no_run
+//! # async fn example() -> gflights::Result<()> {
+//! use gflights::requests::api::ApiClient;
+//! use gflights::requests::metrics::PrometheusMetrics;
+//! use std::sync::Arc;
+//!
+//! let metrics = Arc::new(PrometheusMetrics::new());
+//! let client = ApiClient::new().await.with_metrics(metrics.clone());
+//! // ... serve `metrics.render()` from a `/metrics` handler.
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+use crate::error::Error;
+use crate::requests::endpoint::Endpoint;
+
+/// One HTTP attempt that reached the transport.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestEvent {
+    pub endpoint: Endpoint,
+    /// 1-based attempt number within the retry loop.
+    pub attempt: u32,
+    /// `None` if the transport failed before a response arrived.
+    pub status: Option<StatusCode>,
+    /// Time until the response (or its head, when streaming) was received.
+    pub latency: Duration,
+    /// Body size; `None` when unknown (streaming responses, transport errors).
+    pub bytes: Option<usize>,
+}
+
+/// Why an attempt is being retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryReason {
+    Timeout,
+    ServerError(StatusCode),
+    /// The response body could not be read (date-grid chunks only).
+    BodyRead,
+}
+
+/// A request about to be sent again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryEvent {
+    pub endpoint: Endpoint,
+    /// 1-based number of the attempt about to be made (so `2` for the first retry).
+    pub attempt: u32,
+    pub reason: RetryReason,
+}
+
+/// A response that could not be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseErrorEvent<'a> {
+    pub endpoint: Endpoint,
+    /// Location of the failing element; empty when not tied to one.
+    pub json_path: &'a str,
+    pub message: &'a str,
+}
+
+/// Receives request, retry, rate-limit and parse-failure events from an
+/// `ApiClient` and its clones.
+pub trait MetricsObserver: Send + Sync {
+    fn on_request(&self, event: &RequestEvent) {
+        let _ = event;
+    }
+
+    fn on_retry(&self, event: &RetryEvent) {
+        let _ = event;
+    }
+
+    /// Google answered HTTP 429.
+    fn on_rate_limited(&self, endpoint: Endpoint) {
+        let _ = endpoint;
+    }
+
+    fn on_parse_error(&self, event: &ParseErrorEvent<'_>) {
+        let _ = event;
+    }
+}
+
+/// Observer that ignores every event; the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoMetrics;
+
+impl MetricsObserver for NoMetrics {}
+
+/// Lifts a parser error like [`Error::parse`], reporting it to `observer`
+/// when it is an [`Error::Parse`].
+pub(crate) fn parse_error(
+    observer: &dyn MetricsObserver,
+    endpoint: Endpoint,
+    err: anyhow::Error,
+) -> Error {
+    let err = Error::parse(endpoint, err);
+    if let Error::Parse {
+        endpoint,
+        json_path,
+        message,
+    } = &err
+    {
+        observer.on_parse_error(&ParseErrorEvent {
+            endpoint: *endpoint,
+            json_path,
+            message,
+        });
+    }
+    err
+}
+
+/// Upper bounds, in seconds, of the request-latency histogram buckets.
+pub const LATENCY_BUCKETS: [f64; 10] = [0.1, 0.25, 0.5, 1.0, 2.0, 3.0, 5.0, 10.0, 20.0, 30.0];
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Non-cumulative count per bucket in [`LATENCY_BUCKETS`], plus `+Inf`.
+    buckets: [u64; LATENCY_BUCKETS.len() + 1],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        let i = LATENCY_BUCKETS
+            .iter()
+            .position(|&le| seconds <= le)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.buckets[i] += 1;
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    /// Keyed by (endpoint, status label); `"error"` for transport failures.
+    requests: BTreeMap<(&'static str, String), u64>,
+    latency: BTreeMap<&'static str, Histogram>,
+    bytes: BTreeMap<&'static str, u64>,
+    /// Keyed by (endpoint, reason).
+    retries: BTreeMap<(&'static str, &'static str), u64>,
+    rate_limited: BTreeMap<&'static str, u64>,
+    parse_errors: BTreeMap<&'static str, u64>,
+}
+
+/// In-memory [`MetricsObserver`] rendered with [`PrometheusMetrics::render`].
+///
+/// | Metric | Type | Labels |
+/// |---|---|---|
+/// | `gflights_requests_total` | counter | `endpoint`, `status` |
+/// | `gflights_request_duration_seconds` | histogram | `endpoint` |
+/// | `gflights_response_bytes_total` | counter | `endpoint` |
+/// | `gflights_retries_total` | counter | `endpoint`, `reason` |
+/// | `gflights_rate_limited_total` | counter | `endpoint` |
+/// | `gflights_parse_errors_total` | counter | `endpoint` |
+#[derive(Debug, Default)]
+pub struct PrometheusMetrics {
+    counters: Mutex<Counters>,
+}
+
+impl PrometheusMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Counters> {
+        // Counters only ever grow; a panic mid-update loses at most one sample.
+        self.counters.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Current values in the Prometheus text exposition format (0.0.4).
+    pub fn render(&self) -> String {
+        let c = self.lock();
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "gflights_requests_total",
+            "counter",
+            "HTTP attempts by endpoint and status.",
+        );
+        for ((endpoint, status), n) in &c.requests {
+            let _ = writeln!(
+                out,
+                "gflights_requests_total{{endpoint=\"{endpoint}\",status=\"{status}\"}} {n}"
+            );
+        }
+
+        header(
+            &mut out,
+            "gflights_request_duration_seconds",
+            "histogram",
+            "Time until the response was received.",
+        );
+        for (endpoint, h) in &c.latency {
+            let mut cumulative = 0;
+            for (i, n) in h.buckets.iter().enumerate() {
+                cumulative += n;
+                let le = LATENCY_BUCKETS
+                    .get(i)
+                    .map_or_else(|| "+Inf".to_owned(), f64::to_string);
+                let _ = writeln!(out, "gflights_request_duration_seconds_bucket{{endpoint=\"{endpoint}\",le=\"{le}\"}} {cumulative}");
+            }
+            let _ = writeln!(
+                out,
+                "gflights_request_duration_seconds_sum{{endpoint=\"{endpoint}\"}} {}",
+                h.sum
+            );
+            let _ = writeln!(
+                out,
+                "gflights_request_duration_seconds_count{{endpoint=\"{endpoint}\"}} {}",
+                h.count
+            );
+        }
+
+        header(
+            &mut out,
+            "gflights_response_bytes_total",
+            "counter",
+            "Response body bytes received.",
+        );
+        for (endpoint, n) in &c.bytes {
+            let _ = writeln!(
+                out,
+                "gflights_response_bytes_total{{endpoint=\"{endpoint}\"}} {n}"
+            );
+        }
+
+        header(
+            &mut out,
+            "gflights_retries_total",
+            "counter",
+            "Attempts retried, by reason.",
+        );
+        for ((endpoint, reason), n) in &c.retries {
+            let _ = writeln!(
+                out,
+                "gflights_retries_total{{endpoint=\"{endpoint}\",reason=\"{reason}\"}} {n}"
+            );
+        }
+
+        header(
+            &mut out,
+            "gflights_rate_limited_total",
+            "counter",
+            "HTTP 429 responses.",
+        );
+        for (endpoint, n) in &c.rate_limited {
+            let _ = writeln!(
+                out,
+                "gflights_rate_limited_total{{endpoint=\"{endpoint}\"}} {n}"
+            );
+        }
+
+        header(
+            &mut out,
+            "gflights_parse_errors_total",
+            "counter",
+            "Responses that failed to parse.",
+        );
+        for (endpoint, n) in &c.parse_errors {
+            let _ = writeln!(
+                out,
+                "gflights_parse_errors_total{{endpoint=\"{endpoint}\"}} {n}"
+            );
+        }
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+impl MetricsObserver for PrometheusMetrics {
+    fn on_request(&self, event: &RequestEvent) {
+        let endpoint = event.endpoint.as_str();
+        let status = event
+            .status
+            .map_or_else(|| "error".to_owned(), |s| s.as_u16().to_string());
+        let mut c = self.lock();
+        *c.requests.entry((endpoint, status)).or_default() += 1;
+        c.latency
+            .entry(endpoint)
+            .or_default()
+            .observe(event.latency.as_secs_f64());
+        if let Some(bytes) = event.bytes {
+            *c.bytes.entry(endpoint).or_default() += bytes as u64;
+        }
+    }
+
+    fn on_retry(&self, event: &RetryEvent) {
+        let reason = match event.reason {
+            RetryReason::Timeout => "timeout",
+            RetryReason::ServerError(_) => "server_error",
+            RetryReason::BodyRead => "body_read",
+        };
+        *self
+            .lock()
+            .retries
+            .entry((event.endpoint.as_str(), reason))
+            .or_default() += 1;
+    }
+
+    fn on_rate_limited(&self, endpoint: Endpoint) {
+        *self
+            .lock()
+            .rate_limited
+            .entry(endpoint.as_str())
+            .or_default() += 1;
+    }
+
+    fn on_parse_error(&self, event: &ParseErrorEvent<'_>) {
+        *self
+            .lock()
+            .parse_errors
+            .entry(event.endpoint.as_str())
+            .or_default() += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_counters_and_cumulative_histogram() {
+        let m = PrometheusMetrics::new();
+        for (status, secs, bytes) in [
+            (Some(StatusCode::OK), 0.2, Some(100)),
+            (Some(StatusCode::OK), 4.0, None),
+            (None, 40.0, None),
+        ] {
+            m.on_request(&RequestEvent {
+                endpoint: Endpoint::Shopping,
+                attempt: 1,
+                status,
+                latency: Duration::from_secs_f64(secs),
+                bytes,
+            });
+        }
+        m.on_retry(&RetryEvent {
+            endpoint: Endpoint::Shopping,
+            attempt: 2,
+            reason: RetryReason::ServerError(StatusCode::BAD_GATEWAY),
+        });
+        m.on_rate_limited(Endpoint::City);
+
+        let text = m.render();
+        for line in [
+            "# TYPE gflights_requests_total counter",
+            r#"gflights_requests_total{endpoint="shopping",status="200"} 2"#,
+            r#"gflights_requests_total{endpoint="shopping",status="error"} 1"#,
+            r#"gflights_request_duration_seconds_bucket{endpoint="shopping",le="0.25"} 1"#,
+            r#"gflights_request_duration_seconds_bucket{endpoint="shopping",le="5"} 2"#,
+            r#"gflights_request_duration_seconds_bucket{endpoint="shopping",le="+Inf"} 3"#,
+            r#"gflights_request_duration_seconds_count{endpoint="shopping"} 3"#,
+            r#"gflights_response_bytes_total{endpoint="shopping"} 100"#,
+            r#"gflights_retries_total{endpoint="shopping",reason="server_error"} 1"#,
+            r#"gflights_rate_limited_total{endpoint="city"} 1"#,
+        ] {
+            assert!(
+                text.lines().any(|l| l == line),
+                "missing {line:?} in\n{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn only_parse_errors_are_reported_as_such() {
+        let m = PrometheusMetrics::new();
+        let backend = anyhow::Error::new(Error::Backend {
+            messages: vec!["x".into()],
+        });
+        assert!(matches!(
+            parse_error(&m, Endpoint::Deals, backend),
+            Error::Backend { .. }
+        ));
+        assert!(matches!(
+            parse_error(&m, Endpoint::Deals, anyhow::anyhow!("bad shape")),
+            Error::Parse { .. }
+        ));
+        assert!(m
+            .render()
+            .contains(r#"gflights_parse_errors_total{endpoint="deals"} 1"#));
+    }
+}
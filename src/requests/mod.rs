@@ -1,2 +1,14 @@
 pub mod api;
+pub mod batch;
+pub mod bulk;
+pub mod cache;
+pub mod cassette;
+pub mod clock;
 pub mod config;
+pub mod endpoint;
+pub mod flexible;
+pub mod frontend_version;
+pub mod metrics;
+pub mod proxy_pool;
+pub mod rate_limit;
+pub mod transport;
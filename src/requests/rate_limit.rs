@@ -0,0 +1,244 @@
+//! Shared HTTP 429 state for [`ApiClient`](super::api::ApiClient) and the
+//! optional automatic cooldown policy.
+//!
+//! Without a [`CooldownPolicy`] the first 429 blocks the client (and all its
+//! clones) until [`ApiClient::reset_rate_limit`](super::api::ApiClient::reset_rate_limit)
+//! is called.  With one, the block lifts on its own once the cooldown has
+//! elapsed:
+//!
+//! * the cooldown starts at [`CooldownPolicy::base`] and is multiplied by
+//!   [`CooldownPolicy::factor`] for every further 429 received before a
+//!   request succeeds again, up to [`CooldownPolicy::max`];
+//! * a `Retry-After` header (delta-seconds or HTTP-date) extends the cooldown
+//!   when it asks for longer than the escalated value.
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Automatic cooldown applied after HTTP 429.
+///
+/// ```rust
+/// use gflights::requests::rate_limit::CooldownPolicy;
+/// use std::time::Duration;
+///
+/// let policy = CooldownPolicy {
+///     base: Duration::from_secs(30),
+///     ..CooldownPolicy::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CooldownPolicy {
+    /// Cooldown after the first 429.
+    pub base: Duration,
+    /// Multiplier applied for each consecutive 429.
+    pub factor: u32,
+    /// Upper bound for the escalated cooldown (`Retry-After` may exceed it).
+    pub max: Duration,
+}
+
+impl Default for CooldownPolicy {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_secs(60),
+            factor: 2,
+            max: Duration::from_secs(30 * 60),
+        }
+    }
+}
+
+impl CooldownPolicy {
+    /// Escalated cooldown for the `strikes`-th consecutive 429 (1-based).
+    fn escalated(&self, strikes: u32) -> Duration {
+        let multiplier = self.factor.max(1).saturating_pow(strikes.saturating_sub(1));
+        self.base.saturating_mul(multiplier).min(self.max)
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    blocked: bool,
+    /// Consecutive 429s since the last successful request.
+    strikes: u32,
+    /// End of the current cooldown; `None` while blocked means "until reset".
+    until: Option<Instant>,
+}
+
+/// 429 flag and cooldown state shared by an `ApiClient` and its clones.
+#[derive(Debug, Default)]
+pub(crate) struct RateLimitState {
+    policy: Option<CooldownPolicy>,
+    state: Mutex<State>,
+}
+
+impl RateLimitState {
+    pub(crate) fn new(policy: Option<CooldownPolicy>) -> Self {
+        Self {
+            policy,
+            state: Mutex::default(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        // The state is a few plain fields; a panic mid-update cannot leave it
+        // inconsistent, so recover from poisoning.
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Records a 429 received at `now`.
+    pub(crate) fn record_429(&self, retry_after: Option<Duration>, now: Instant) {
+        let mut state = self.lock();
+        state.blocked = true;
+        state.strikes = state.strikes.saturating_add(1);
+        state.until = self.policy.as_ref().map(|policy| {
+            let cooldown = policy
+                .escalated(state.strikes)
+                .max(retry_after.unwrap_or_default());
+            tracing::warn!(
+                strikes = state.strikes,
+                cooldown_secs = cooldown.as_secs(),
+                "rate limited — cooling down"
+            );
+            now + cooldown
+        });
+    }
+
+    /// Records a successful request, resetting the escalation.
+    pub(crate) fn record_success(&self) {
+        let mut state = self.lock();
+        if !state.blocked {
+            state.strikes = 0;
+        }
+    }
+
+    /// Returns `true` if requests must be refused at `now`.
+    pub(crate) fn is_blocked(&self, now: Instant) -> bool {
+        let mut state = self.lock();
+        match state.until {
+            Some(until) if state.blocked && now >= until => {
+                tracing::info!("rate-limit cooldown elapsed — resuming requests");
+                state.blocked = false;
+                state.until = None;
+                false
+            }
+            _ => state.blocked,
+        }
+    }
+
+    /// Time left before the cooldown lifts at `now`; `None` when not blocked
+    /// or when blocked without a policy (until reset).
+    pub(crate) fn remaining(&self, now: Instant) -> Option<Duration> {
+        let state = self.lock();
+        match state.until {
+            Some(until) if state.blocked => Some(until.saturating_duration_since(now)),
+            _ => None,
+        }
+    }
+
+    /// Clears the block and the escalation.
+    pub(crate) fn reset(&self) {
+        *self.lock() = State::default();
+    }
+}
+
+/// Parses a `Retry-After` header given either as delta-seconds or as an
+/// HTTP-date, relative to `now`.
+pub(crate) fn retry_after(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    (at.with_timezone(&Utc) - now).to_std().ok()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn policy() -> CooldownPolicy {
+        CooldownPolicy {
+            base: Duration::from_secs(10),
+            factor: 2,
+            max: Duration::from_secs(35),
+        }
+    }
+
+    #[test]
+    fn without_policy_blocks_until_reset() {
+        let state = RateLimitState::new(None);
+        let now = Instant::now();
+        state.record_429(None, now);
+        assert!(state.is_blocked(now + Duration::from_secs(86_400)));
+        assert_eq!(state.remaining(now), None);
+        state.reset();
+        assert!(!state.is_blocked(now));
+    }
+
+    #[test]
+    fn cooldown_lifts_and_escalates_on_consecutive_429s() {
+        let state = RateLimitState::new(Some(policy()));
+        let t0 = Instant::now();
+
+        state.record_429(None, t0);
+        assert_eq!(state.remaining(t0), Some(Duration::from_secs(10)));
+        assert!(state.is_blocked(t0 + Duration::from_secs(9)));
+        assert!(!state.is_blocked(t0 + Duration::from_secs(10)));
+
+        let t1 = t0 + Duration::from_secs(10);
+        state.record_429(None, t1);
+        assert_eq!(state.remaining(t1), Some(Duration::from_secs(20)));
+
+        let t2 = t1 + Duration::from_secs(20);
+        assert!(!state.is_blocked(t2));
+        state.record_429(None, t2);
+        // 40 s escalated, capped at 35 s.
+        assert_eq!(state.remaining(t2), Some(Duration::from_secs(35)));
+    }
+
+    #[test]
+    fn success_resets_escalation() {
+        let state = RateLimitState::new(Some(policy()));
+        let t0 = Instant::now();
+        state.record_429(None, t0);
+        let t1 = t0 + Duration::from_secs(10);
+        assert!(!state.is_blocked(t1));
+        state.record_success();
+        state.record_429(None, t1);
+        assert_eq!(state.remaining(t1), Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn retry_after_extends_cooldown() {
+        let state = RateLimitState::new(Some(policy()));
+        let now = Instant::now();
+        state.record_429(Some(Duration::from_secs(120)), now);
+        assert_eq!(state.remaining(now), Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn parses_retry_after_seconds_and_http_date() {
+        let now = DateTime::parse_from_rfc3339("2026-06-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers, now), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("42"));
+        assert_eq!(retry_after(&headers, now), Some(Duration::from_secs(42)));
+
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Mon, 01 Jun 2026 12:01:30 GMT"),
+        );
+        assert_eq!(retry_after(&headers, now), Some(Duration::from_secs(90)));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers, now), None);
+    }
+}
@@ -0,0 +1,293 @@
+//! Pluggable HTTP transport used by [`ApiClient`](super::api::ApiClient).
+//!
+//! Every network call the client makes — the frontend-version probe, the
+//! `FlightsFrontendService/*` POSTs and the booking-URL click tracker — goes
+//! through a single [`Transport::send`].  The default implementation,
+//! [`ReqwestTransport`], wraps a `reqwest::Client`; tests and embedders can
+//! supply their own (e.g. an in-process fake serving fixtures from
+//! `test_files/`) via [`ApiClient::new_with_transport`](super::api::ApiClient::new_with_transport).
+//!
+//! Streaming calls (e.g. [`ApiClient::request_flights_stream`](super::api::ApiClient::request_flights_stream))
+//! use [`Transport::send_streaming`] instead, which by default buffers the
+//! whole body through `send`; override it to hand out chunks as they arrive.
+//!
+//! ```rust
+//! use futures::future::BoxFuture;
+//! use gflights::requests::transport::{Transport, TransportRequest, TransportResponse};
+//! use reqwest::StatusCode;
+//!
+//! /// Answers every request with an empty `200 OK`.
+//! struct Empty;
+//!
+//! impl Transport for Empty {
+//!     fn send(&self, request: TransportRequest) -> BoxFuture<'_, anyhow::Result<TransportResponse>> {
+//!         Box::pin(async move {
+//!             Ok(TransportResponse {
+//!                 status: StatusCode::OK,
+//!                 url: request.url,
+//!                 headers: Default::default(),
+//!                 body: String::new(),
+//!             })
+//!         })
+//!     }
+//! }
+//! ```
+
+use anyhow::Result;
+use futures::future::BoxFuture;
+use futures::stream::{self, BoxStream, StreamExt};
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Method, StatusCode};
+
+/// A single outgoing HTTP request.
+#[derive(Debug, Clone)]
+pub struct TransportRequest {
+    /// `GET` for the frontend-version probe, `POST` for everything else.
+    pub method: Method,
+    pub url: String,
+    pub headers: HeaderMap,
+    /// Form-encoded POST body; `None` for `GET`.
+    pub body: Option<String>,
+}
+
+/// A fully-read HTTP response.
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    /// Final URL after any redirects (used to detect main-page redirects).
+    pub url: String,
+    /// Response headers; only `Retry-After` is consulted (on HTTP 429).
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+/// Response body delivered chunk by chunk, in arrival order.
+pub type BodyStream = BoxStream<'static, Result<Vec<u8>>>;
+
+/// An HTTP response whose head has arrived but whose body is still streaming.
+pub struct StreamingResponse {
+    pub status: StatusCode,
+    /// Final URL after any redirects.
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: BodyStream,
+}
+
+impl StreamingResponse {
+    /// Wraps a fully-read response as a single-chunk stream.
+    pub fn from_response(response: TransportResponse) -> Self {
+        Self {
+            status: response.status,
+            url: response.url,
+            headers: response.headers,
+            body: stream::once(async move { Ok(response.body.into_bytes()) }).boxed(),
+        }
+    }
+}
+
+impl std::fmt::Debug for StreamingResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamingResponse")
+            .field("status", &self.status)
+            .field("url", &self.url)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sends a [`TransportRequest`] and returns the status and full body.
+///
+/// Implementations must be cheap to share: [`ApiClient`](super::api::ApiClient)
+/// holds the transport behind an `Arc` and all clones use the same instance.
+///
+/// Return [`TransportTimeout`] (or a `reqwest::Error` whose `is_timeout()` is
+/// true) to have the request retried according to the client's
+/// [`RetryConfig`](super::api::RetryConfig); any other error is surfaced
+/// immediately.
+pub trait Transport: Send + Sync {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>>;
+
+    /// Like [`Self::send`], but resolves as soon as the response head arrives
+    /// and yields the body as it is read.
+    ///
+    /// The default implementation awaits [`Self::send`] and yields the whole
+    /// body as one chunk, so streaming calls still work (without the latency
+    /// benefit) on transports that only implement `send`.
+    fn send_streaming(
+        &self,
+        request: TransportRequest,
+    ) -> BoxFuture<'_, Result<StreamingResponse>> {
+        Box::pin(async move {
+            self.send(request)
+                .await
+                .map(StreamingResponse::from_response)
+        })
+    }
+}
+
+/// Error a custom [`Transport`] can return to signal a timed-out request,
+/// which the client treats as transient and retries.
+#[derive(Debug)]
+pub struct TransportTimeout;
+
+impl std::fmt::Display for TransportTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "request timed out")
+    }
+}
+
+impl std::error::Error for TransportTimeout {}
+
+/// Returns `true` if `err` represents a timed-out request.
+pub(crate) fn is_timeout(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause.is::<TransportTimeout>()
+            || cause
+                .downcast_ref::<reqwest::Error>()
+                .is_some_and(reqwest::Error::is_timeout)
+    })
+}
+
+/// Returns `true` if `err` was raised while streaming the response body
+/// (e.g. an unexpected EOF from a forcibly-closed connection).
+pub(crate) fn is_body_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.is_body() || e.is_decode())
+    })
+}
+
+/// Extracts and percent-decodes the `f.req` value from a form-encoded body.
+///
+/// The rest of the body (`at=…:<epoch_ms>`) is a per-request token, so the
+/// decoded `f.req` is what identifies a request's payload.
+pub(crate) fn decoded_f_req(body: &str) -> Option<String> {
+    body.split('&')
+        .find_map(|pair| pair.strip_prefix("f.req="))
+        .map(|value| {
+            percent_encoding::percent_decode_str(value)
+                .decode_utf8_lossy()
+                .into_owned()
+        })
+}
+
+/// Default [`Transport`] backed by a `reqwest::Client`.
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    /// Wraps an already-configured `reqwest::Client` (proxy, TLS, timeouts…).
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+impl ReqwestTransport {
+    async fn head(&self, request: TransportRequest) -> Result<reqwest::Response> {
+        let mut builder = self
+            .client
+            .request(request.method, &request.url)
+            .headers(request.headers);
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+        let res = builder.send().await?;
+        tracing::trace!(
+            status = %res.status(),
+            http_version = ?res.version(),
+            "Response received"
+        );
+        Ok(res)
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>> {
+        Box::pin(async move {
+            let res = self.head(request).await?;
+            let status = res.status();
+            let url = res.url().to_string();
+            let headers = res.headers().clone();
+            let body = res.text().await?;
+            Ok(TransportResponse {
+                status,
+                url,
+                headers,
+                body,
+            })
+        })
+    }
+
+    fn send_streaming(
+        &self,
+        request: TransportRequest,
+    ) -> BoxFuture<'_, Result<StreamingResponse>> {
+        Box::pin(async move {
+            let res = self.head(request).await?;
+            let status = res.status();
+            let url = res.url().to_string();
+            let headers = res.headers().clone();
+            let body = stream::try_unfold(res, |mut res| async move {
+                Ok(res.chunk().await?.map(|chunk| (chunk.to_vec(), res)))
+            })
+            .boxed();
+            Ok(StreamingResponse {
+                status,
+                url,
+                headers,
+                body,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transport_timeout_is_detected() {
+        assert!(is_timeout(&anyhow::Error::new(TransportTimeout)));
+        assert!(is_timeout(
+            &anyhow::Error::new(TransportTimeout).context("while fetching flights")
+        ));
+    }
+
+    #[tokio::test]
+    async fn default_send_streaming_yields_the_whole_body() {
+        struct Fixed;
+        impl Transport for Fixed {
+            fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>> {
+                Box::pin(async move {
+                    Ok(TransportResponse {
+                        status: StatusCode::OK,
+                        url: request.url,
+                        headers: HeaderMap::new(),
+                        body: "line one\nline two".into(),
+                    })
+                })
+            }
+        }
+        let res = Fixed
+            .send_streaming(TransportRequest {
+                method: Method::GET,
+                url: "https://example.com/".into(),
+                headers: HeaderMap::new(),
+                body: None,
+            })
+            .await
+            .unwrap();
+        let chunks: Vec<Vec<u8>> = res.body.map(|c| c.unwrap()).collect().await;
+        assert_eq!(chunks, [b"line one\nline two".to_vec()]);
+    }
+
+    #[test]
+    fn other_errors_are_not_timeouts() {
+        let err = anyhow::anyhow!("connection refused");
+        assert!(!is_timeout(&err));
+        assert!(!is_body_error(&err));
+    }
+}
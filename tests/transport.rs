@@ -0,0 +1,1134 @@
+//! Offline client tests: drive `ApiClient` end-to-end through an in-process
+//! fake [`Transport`] that serves fixtures from `test_files/`.
+//!
+//! These exercise request dispatch, retry and rate-limit handling, the
+//! cassette record/replay transports and the response cache, above the
+//! parsers without any network access.
+//!
+//! Run with: `cargo test --test transport`
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, Utc};
+use futures::future::BoxFuture;
+use futures::StreamExt;
+use gflights::parsers::common::{Location, PlaceType};
+use gflights::parsers::flight_response::FlightResponseContainer;
+use gflights::requests::api::{ApiClient, RetryConfig};
+use gflights::requests::batch::BatchRequest;
+use gflights::requests::bulk::{BulkMode, BulkOptions, BulkProgress};
+use gflights::requests::cache::{CacheConfig, CacheMode, MemoryCache};
+use gflights::requests::cassette::{Cassette, RecordingTransport, ReplayTransport};
+use gflights::requests::clock::{Clock, SeededRng};
+use gflights::requests::config::Config;
+use gflights::requests::endpoint::Endpoint;
+use gflights::requests::flexible::FlexibleDates;
+use gflights::requests::frontend_version::{VersionRefreshPolicy, VersionSource};
+use gflights::requests::metrics::PrometheusMetrics;
+use gflights::requests::rate_limit::CooldownPolicy;
+use gflights::requests::transport::{
+    StreamingResponse, Transport, TransportRequest, TransportResponse,
+};
+use gflights::Error;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, StatusCode};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Answers every POST with a fixed status and body; the frontend-version GET
+/// gets an empty page so the client falls back to its built-in label.
+#[derive(Clone)]
+struct FakeTransport {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: String,
+    posts: Arc<Mutex<Vec<TransportRequest>>>,
+}
+
+impl FakeTransport {
+    fn new(status: StatusCode, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+            posts: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn with_header(mut self, name: HeaderName, value: &'static str) -> Self {
+        self.headers.insert(name, HeaderValue::from_static(value));
+        self
+    }
+
+    fn post_count(&self) -> usize {
+        self.posts.lock().unwrap().len()
+    }
+}
+
+impl Transport for FakeTransport {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>> {
+        Box::pin(async move {
+            if request.method == Method::GET {
+                return Ok(TransportResponse {
+                    status: StatusCode::OK,
+                    url: request.url,
+                    headers: HeaderMap::new(),
+                    body: String::new(),
+                });
+            }
+            let url = request.url.clone();
+            self.posts.lock().unwrap().push(request);
+            Ok(TransportResponse {
+                status: self.status,
+                url,
+                headers: self.headers.clone(),
+                body: self.body.clone(),
+            })
+        })
+    }
+}
+
+/// Answers every `GetCalendarGrid` POST with a single-cell grid whose price
+/// increments per call, so each date-grid chunk gets a distinct response.
+#[derive(Default)]
+struct GridTransport {
+    calls: AtomicI32,
+}
+
+impl Transport for GridTransport {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>> {
+        Box::pin(async move {
+            let body = if request.method == Method::GET {
+                String::new()
+            } else {
+                let price = 100 + self.calls.fetch_add(1, Ordering::SeqCst);
+                let inner =
+                    format!(r#"[null,[["2026-09-01","2026-09-08",[[null,{price}],"tok"],1]]]"#);
+                serde_json::json!([["wrb.fr", null, inner]]).to_string()
+            };
+            Ok(TransportResponse {
+                status: StatusCode::OK,
+                url: request.url,
+                headers: HeaderMap::new(),
+                body,
+            })
+        })
+    }
+}
+
+/// Answers `GetCalendarGrid` with `grid` and every flight search with the
+/// `raw_multiline.txt` fixture, except searches departing on `failing`,
+/// which get HTTP 503.
+struct FlexTransport {
+    grid: String,
+    failing: NaiveDate,
+}
+
+impl Transport for FlexTransport {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>> {
+        Box::pin(async move {
+            let body = request.body.as_deref().unwrap_or("");
+            let (status, body) = if request.method == Method::GET {
+                (StatusCode::OK, String::new())
+            } else if request.url.contains("GetCalendarGrid") {
+                (StatusCode::OK, self.grid.clone())
+            } else if body.contains(&self.failing.to_string()) {
+                (StatusCode::SERVICE_UNAVAILABLE, String::new())
+            } else {
+                (
+                    StatusCode::OK,
+                    std::fs::read_to_string("test_files/raw_multiline.txt")?,
+                )
+            };
+            Ok(TransportResponse {
+                status,
+                url: request.url,
+                headers: HeaderMap::new(),
+                body,
+            })
+        })
+    }
+}
+
+/// Serves a main page carrying `label`, and rejects (HTTP 400) every POST
+/// whose `bl=` parameter is not the current label, or every POST at all
+/// once `reject_all` is set.  While `hang_probe` is set the main page never
+/// answers.
+#[derive(Clone)]
+struct VersionedTransport {
+    label: Arc<Mutex<String>>,
+    posts: Arc<AtomicI32>,
+    probes: Arc<AtomicI32>,
+    reject_all: Arc<AtomicBool>,
+    hang_probe: Arc<AtomicBool>,
+}
+
+impl VersionedTransport {
+    fn new(label: &str) -> Self {
+        Self {
+            label: Arc::new(Mutex::new(label.to_owned())),
+            posts: Arc::new(AtomicI32::new(0)),
+            probes: Arc::new(AtomicI32::new(0)),
+            reject_all: Arc::new(AtomicBool::new(false)),
+            hang_probe: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+impl Transport for VersionedTransport {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>> {
+        Box::pin(async move {
+            if request.method == Method::GET {
+                self.probes.fetch_add(1, Ordering::SeqCst);
+                if self.hang_probe.load(Ordering::SeqCst) {
+                    futures::future::pending::<()>().await;
+                }
+            }
+            let label = self.label.lock().unwrap().clone();
+            let (status, body) = if request.method == Method::GET {
+                (
+                    StatusCode::OK,
+                    format!(r#"<script>var bl="{label}";</script>"#),
+                )
+            } else {
+                self.posts.fetch_add(1, Ordering::SeqCst);
+                if !self.reject_all.load(Ordering::SeqCst)
+                    && request.url.contains(&format!("bl={label}&"))
+                {
+                    let body = std::fs::read_to_string("test_files/raw_multiline.txt")?;
+                    (StatusCode::OK, body)
+                } else {
+                    (StatusCode::BAD_REQUEST, String::new())
+                }
+            };
+            Ok(TransportResponse {
+                status,
+                url: request.url,
+                headers: HeaderMap::new(),
+                body,
+            })
+        })
+    }
+}
+
+/// Streams `test_files/raw_multiline.txt` in two halves, holding the second
+/// back until `release` fires — so a consumer that sees the first frame
+/// before releasing proves frames are yielded while the body is still open.
+struct SplitBodyTransport {
+    release: Mutex<Option<tokio::sync::oneshot::Receiver<()>>>,
+}
+
+impl Transport for SplitBodyTransport {
+    fn send(&self, _: TransportRequest) -> BoxFuture<'_, Result<TransportResponse>> {
+        Box::pin(async { anyhow::bail!("only streaming requests are expected") })
+    }
+
+    fn send_streaming(
+        &self,
+        request: TransportRequest,
+    ) -> BoxFuture<'_, Result<StreamingResponse>> {
+        Box::pin(async move {
+            let body = std::fs::read_to_string("test_files/raw_multiline.txt")?;
+            // Split just after the first frame's newline.
+            let first_frame = body.find(r#"[["wrb.fr""#).expect("fixture has frames");
+            let split = first_frame + body[first_frame..].find('\n').expect("frame ends") + 1;
+            let (head, tail) = (
+                body.as_bytes()[..split].to_vec(),
+                body.as_bytes()[split..].to_vec(),
+            );
+            let release = self.release.lock().unwrap().take().expect("single request");
+            let chunks = futures::stream::once(async move { Ok(head) })
+                .chain(futures::stream::once(async move {
+                    release.await?;
+                    Ok(tail)
+                }))
+                .boxed();
+            Ok(StreamingResponse {
+                status: StatusCode::OK,
+                url: request.url,
+                headers: HeaderMap::new(),
+                body: chunks,
+            })
+        })
+    }
+}
+
+fn temp_cassette(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!(
+        "gflights-{name}-{}.cassette.json",
+        std::process::id()
+    ))
+}
+
+fn airport(code: &str) -> Location {
+    Location {
+        loc_identifier: code.to_owned(),
+        loc_type: PlaceType::Airport,
+        location_name: None,
+    }
+}
+
+fn lux_to_zrh() -> Config {
+    Config::builder()
+        .departure_location(airport("LUX"))
+        .destination_location(airport("ZRH"))
+        .departing_date(Utc::now().date_naive() + Duration::days(30))
+        .build()
+        .expect("valid config")
+}
+
+/// Real time, but records every back-off instead of sleeping through it.
+#[derive(Default)]
+struct RecordingClock {
+    sleeps: Mutex<Vec<std::time::Duration>>,
+}
+
+impl Clock for RecordingClock {
+    fn now(&self) -> std::time::Instant {
+        std::time::Instant::now()
+    }
+
+    fn sleep(&self, duration: std::time::Duration) -> BoxFuture<'static, ()> {
+        self.sleeps.lock().unwrap().push(duration);
+        Box::pin(std::future::ready(()))
+    }
+}
+
+/// A [`Clock`] whose calendar stands still at `today`.
+struct DatedClock {
+    today: NaiveDate,
+}
+
+impl Clock for DatedClock {
+    fn now(&self) -> std::time::Instant {
+        std::time::Instant::now()
+    }
+
+    fn sleep(&self, _: std::time::Duration) -> BoxFuture<'static, ()> {
+        Box::pin(std::future::ready(()))
+    }
+
+    fn today(&self) -> NaiveDate {
+        self.today
+    }
+}
+
+fn no_delay_retries(max_attempts: u32) -> RetryConfig {
+    RetryConfig {
+        max_attempts,
+        base_delay_ms: 0,
+        cap_delay_ms: 0,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn request_flights_parses_body_served_by_fake_transport() -> Result<()> {
+    let body = std::fs::read_to_string("test_files/raw_multiline.txt")?;
+    let fake = FakeTransport::new(StatusCode::OK, body);
+    let client = ApiClient::new_with_transport(fake.clone()).await;
+
+    let results = client.request_flights(&lux_to_zrh()).await?;
+
+    assert!(!results.get_all_flights().is_empty());
+    assert_eq!(fake.post_count(), 1);
+    let posts = fake.posts.lock().unwrap();
+    assert!(posts[0].url.contains("GetShoppingResults"));
+    assert!(posts[0].body.as_deref().unwrap_or("").starts_with("f.req="));
+    Ok(())
+}
+
+#[tokio::test]
+async fn too_many_requests_sets_shared_flag_without_retrying() {
+    let fake = FakeTransport::new(StatusCode::TOO_MANY_REQUESTS, "");
+    let client = ApiClient::new_with_transport(fake.clone())
+        .await
+        .with_retry_config(no_delay_retries(3));
+
+    let err = client.request_flights(&lux_to_zrh()).await.unwrap_err();
+
+    assert!(matches!(err, Error::RateLimited));
+    assert!(client.clone().is_rate_limited());
+    assert_eq!(fake.post_count(), 1, "429 must not be retried");
+
+    // Further calls fail fast without reaching the transport.
+    assert!(client.request_flights(&lux_to_zrh()).await.is_err());
+    assert_eq!(fake.post_count(), 1);
+}
+
+#[tokio::test]
+async fn cooldown_honours_retry_after_and_lifts_automatically() {
+    let fake = FakeTransport::new(StatusCode::TOO_MANY_REQUESTS, "")
+        .with_header(reqwest::header::RETRY_AFTER, "120");
+    let client = ApiClient::new_with_transport(fake.clone())
+        .await
+        .with_rate_limit_cooldown(CooldownPolicy {
+            base: std::time::Duration::from_millis(50),
+            ..CooldownPolicy::default()
+        });
+
+    let err = client.request_flights(&lux_to_zrh()).await.unwrap_err();
+    assert!(matches!(err, Error::RateLimited));
+    let remaining = client.rate_limit_cooldown_remaining().unwrap();
+    assert!(
+        remaining > std::time::Duration::from_secs(100),
+        "{remaining:?}"
+    );
+
+    // Without Retry-After the base cooldown applies and lifts on its own.
+    let fake = FakeTransport::new(StatusCode::TOO_MANY_REQUESTS, "");
+    let client = ApiClient::new_with_transport(fake.clone())
+        .await
+        .with_rate_limit_cooldown(CooldownPolicy {
+            base: std::time::Duration::from_millis(50),
+            ..CooldownPolicy::default()
+        });
+    assert!(client.request_flights(&lux_to_zrh()).await.is_err());
+    assert!(client.is_rate_limited());
+    tokio::time::sleep(std::time::Duration::from_millis(60)).await;
+    assert!(!client.is_rate_limited());
+    assert_eq!(client.rate_limit_cooldown_remaining(), None);
+    assert!(client.request_flights(&lux_to_zrh()).await.is_err());
+    assert_eq!(
+        fake.post_count(),
+        2,
+        "request after cooldown reaches the transport"
+    );
+}
+
+#[tokio::test]
+async fn server_errors_are_retried_up_to_max_attempts() {
+    let fake = FakeTransport::new(StatusCode::SERVICE_UNAVAILABLE, "");
+    let client = ApiClient::new_with_transport(fake.clone())
+        .await
+        .with_retry_config(no_delay_retries(3));
+
+    let err = client.request_flights(&lux_to_zrh()).await.unwrap_err();
+
+    assert!(
+        matches!(err, Error::Http { status, .. } if status == StatusCode::SERVICE_UNAVAILABLE),
+        "got: {err}"
+    );
+    assert_eq!(fake.post_count(), 3);
+}
+
+async fn retry_delays(seed: u64) -> Vec<std::time::Duration> {
+    let clock = Arc::new(RecordingClock::default());
+    let client = ApiClient::new_with_transport(FakeTransport::new(StatusCode::BAD_GATEWAY, ""))
+        .await
+        .with_retry_config(RetryConfig {
+            max_attempts: 6,
+            base_delay_ms: 100,
+            cap_delay_ms: 1_000,
+        })
+        .with_clock(clock.clone())
+        .with_rng(Arc::new(SeededRng::new(seed)));
+    assert!(client.request_flights(&lux_to_zrh()).await.is_err());
+    let sleeps = clock.sleeps.lock().unwrap().clone();
+    sleeps
+}
+
+#[tokio::test]
+async fn retry_back_off_uses_injected_clock_and_rng() {
+    let delays = retry_delays(42).await;
+
+    assert_eq!(delays.len(), 5);
+    for (i, delay) in delays.iter().enumerate() {
+        let ceiling = (100u64 << i).min(1_000);
+        assert!(
+            *delay <= std::time::Duration::from_millis(ceiling),
+            "retry {}: {delay:?}",
+            i + 1
+        );
+    }
+    assert_eq!(delays, retry_delays(42).await, "same seed, same back-off");
+    assert_ne!(
+        delays,
+        retry_delays(43).await,
+        "seeds do not retry in lockstep"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn default_clock_back_off_follows_paused_tokio_time() {
+    let fake = FakeTransport::new(StatusCode::SERVICE_UNAVAILABLE, "");
+    let client = ApiClient::new_with_transport(fake.clone())
+        .await
+        .with_retry_config(RetryConfig {
+            max_attempts: 4,
+            base_delay_ms: 60_000,
+            cap_delay_ms: 600_000,
+        });
+
+    let started = tokio::time::Instant::now();
+    assert!(client.request_flights(&lux_to_zrh()).await.is_err());
+
+    assert_eq!(fake.post_count(), 4);
+    assert!(started.elapsed() <= std::time::Duration::from_secs(60 + 120 + 240));
+}
+
+#[tokio::test]
+async fn backend_error_envelope_surfaces_as_backend_error() {
+    let body = r#"[["wrb.fr",null,null,null,null,[3,null,[["Invalid request",null]]]]]"#;
+    let client = ApiClient::new_with_transport(FakeTransport::new(StatusCode::OK, body)).await;
+
+    let err = client.request_flights(&lux_to_zrh()).await.unwrap_err();
+
+    match err {
+        Error::Backend { messages } => assert_eq!(messages, ["Invalid request"]),
+        other => panic!("expected Error::Backend, got: {other}"),
+    }
+}
+
+#[tokio::test]
+async fn malformed_frame_surfaces_as_parse_error_with_endpoint() {
+    let body = r#"[["wrb.fr",null,{"not":"a payload"}]]"#;
+    let client = ApiClient::new_with_transport(FakeTransport::new(StatusCode::OK, body)).await;
+
+    let err = client.request_flights(&lux_to_zrh()).await.unwrap_err();
+
+    match err {
+        Error::Parse {
+            endpoint,
+            json_path,
+            ..
+        } => {
+            assert_eq!(endpoint, Endpoint::Shopping);
+            assert!(!json_path.is_empty());
+        }
+        other => panic!("expected Error::Parse, got: {other}"),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Flexible dates
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn flexible_search_keeps_dates_that_succeeded() -> Result<()> {
+    let departure = Utc::now().date_naive() + Duration::days(30);
+    let (failing, ret) = (departure + Duration::days(1), departure + Duration::days(7));
+    let inner = format!(
+        r#"[null,[["{departure}","{ret}",[[null,100],"a"],1],["{failing}","{ret}",[[null,120],"b"],1]]]"#
+    );
+    let transport = FlexTransport {
+        grid: serde_json::json!([["wrb.fr", null, inner]]).to_string(),
+        failing,
+    };
+    let client = ApiClient::new_with_transport(transport)
+        .await
+        .with_retry_config(no_delay_retries(1));
+    let config = Config::builder()
+        .departure_location(airport("LUX"))
+        .destination_location(airport("ZRH"))
+        .departing_date(departure)
+        .return_date(ret)
+        .build()?;
+
+    let results = client
+        .request_flights_flexible(&config, &FlexibleDates::new(1).with_best(2))
+        .await?;
+
+    assert_eq!(results.dates.len(), 2);
+    assert_eq!(results.failed.len(), 1);
+    assert_eq!(results.failed[0].date.departure_date, failing);
+    assert!(!results.itineraries.is_empty());
+    assert!(results
+        .itineraries
+        .iter()
+        .all(|d| d.departure_date == departure));
+    Ok(())
+}
+
+#[tokio::test]
+async fn flexible_windows_start_at_the_clocks_today() -> Result<()> {
+    let departure = Utc::now().date_naive() + Duration::days(30);
+    let (next, ret) = (departure + Duration::days(1), departure + Duration::days(7));
+    let inner = format!(
+        r#"[null,[["{departure}","{ret}",[[null,100],"a"],1],["{next}","{ret}",[[null,120],"b"],1]]]"#
+    );
+    let grid = serde_json::json!([["wrb.fr", null, inner]]).to_string();
+    let config = Config::builder()
+        .departure_location(airport("LUX"))
+        .destination_location(airport("ZRH"))
+        .departing_date(departure)
+        .return_date(ret)
+        .build()?;
+    let flex = FlexibleDates::new(1).with_best(2);
+    let client_on = |today| {
+        let transport = FlexTransport {
+            grid: grid.clone(),
+            failing: ret + Duration::days(1),
+        };
+        async move {
+            ApiClient::new_with_transport(transport)
+                .await
+                .with_clock(Arc::new(DatedClock { today }))
+        }
+    };
+
+    // The cheaper departure is already behind the client's today.
+    let results = client_on(next)
+        .await
+        .request_flights_flexible(&config, &flex)
+        .await?;
+    let dates: Vec<_> = results.dates.iter().map(|d| d.departure_date).collect();
+    assert_eq!(dates, [next]);
+
+    let err = client_on(next + Duration::days(1))
+        .await
+        .request_flights_flexible(&config, &flex)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidConfig(_)), "got: {err}");
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Cassette record / replay
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn recorded_flight_search_replays_without_inner_transport() -> Result<()> {
+    let path = temp_cassette("flights");
+    let body = std::fs::read_to_string("test_files/raw_multiline.txt")?;
+    let config = lux_to_zrh();
+
+    let recorder = RecordingTransport::new(FakeTransport::new(StatusCode::OK, body), &path);
+    let live = ApiClient::new_with_transport(recorder).await;
+    let recorded = live.request_flights(&config).await?.get_all_flights();
+
+    let cassette = Cassette::load(&path)?;
+    assert_eq!(cassette.entries.len(), 2, "version probe + shopping search");
+    let search = &cassette.entries[1];
+    assert!(search.url.contains("GetShoppingResults"));
+    assert!(search.f_req.as_deref().unwrap_or("").contains("LUX"));
+
+    let replay = ApiClient::new_replaying(&path).await?;
+    let replayed = replay.request_flights(&config).await?.get_all_flights();
+    assert_eq!(replayed.len(), recorded.len());
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn chunked_date_grid_replays_every_chunk() -> Result<()> {
+    let path = temp_cassette("grid");
+    let dep_start = NaiveDate::from_ymd_opt(2026, 9, 1).unwrap();
+    let config = Config::builder()
+        .departure_location(airport("LHR"))
+        .destination_location(airport("JFK"))
+        .departing_date(dep_start)
+        .return_date(dep_start + Duration::days(7))
+        .build()?;
+    let (dep_end, ret_start, ret_end) = (
+        dep_start + Duration::days(29),
+        dep_start + Duration::days(7),
+        dep_start + Duration::days(36),
+    );
+
+    let live =
+        ApiClient::new_with_transport(RecordingTransport::new(GridTransport::default(), &path))
+            .await;
+    let recorded = live
+        .request_date_grid(&config, dep_start, dep_end, ret_start, ret_end)
+        .await?;
+    assert!(recorded.entries.len() > 1, "30×30 window must be chunked");
+
+    let replay = ApiClient::new_with_transport(ReplayTransport::load(&path)?).await;
+    let replayed = replay
+        .request_date_grid(&config, dep_start, dep_end, ret_start, ret_end)
+        .await?;
+
+    let mut want: Vec<i32> = recorded.entries.iter().map(|e| e.price).collect();
+    let mut got: Vec<i32> = replayed.entries.iter().map(|e| e.price).collect();
+    want.sort_unstable();
+    got.sort_unstable();
+    assert_eq!(got, want, "each chunk must be served its own recording");
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn concurrent_recordings_all_reach_the_cassette() -> Result<()> {
+    let path = temp_cassette("concurrent");
+    let recorder = RecordingTransport::new(FakeTransport::new(StatusCode::OK, "[]"), &path);
+    let sends = (0..16).map(|i| {
+        recorder.send(TransportRequest {
+            method: Method::POST,
+            url: format!("https://x/Rpc{i}"),
+            headers: HeaderMap::new(),
+            body: Some(format!("f.req=%5B{i}%5D")),
+        })
+    });
+    for response in futures::future::join_all(sends).await {
+        response?;
+    }
+    assert_eq!(Cassette::load(&path)?.entries.len(), 16);
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn replayed_429_keeps_retry_after() -> Result<()> {
+    let path = temp_cassette("retry-after");
+    let fake = FakeTransport::new(StatusCode::TOO_MANY_REQUESTS, "")
+        .with_header(reqwest::header::RETRY_AFTER, "7");
+    let request = || TransportRequest {
+        method: Method::POST,
+        url: "https://x/Rpc".into(),
+        headers: HeaderMap::new(),
+        body: Some("f.req=%5B1%5D".into()),
+    };
+    RecordingTransport::new(fake, &path).send(request()).await?;
+
+    let replayed = ReplayTransport::load(&path)?.send(request()).await?;
+    assert_eq!(replayed.status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(replayed.headers[reqwest::header::RETRY_AFTER], "7");
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn replay_rejects_unrecorded_requests() {
+    let replay = ApiClient::new_with_transport(ReplayTransport::new(Cassette::default())).await;
+    let err = replay.request_flights(&lux_to_zrh()).await.unwrap_err();
+    assert!(err.to_string().contains("no cassette entry"), "got: {err}");
+}
+
+// ---------------------------------------------------------------------------
+// Response cache
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn cached_search_is_served_without_a_second_post() -> Result<()> {
+    let body = std::fs::read_to_string("test_files/raw_multiline.txt")?;
+    let fake = FakeTransport::new(StatusCode::OK, body);
+    let client = ApiClient::new_with_transport(fake.clone())
+        .await
+        .with_cache(MemoryCache::new(), CacheConfig::default());
+    let config = lux_to_zrh();
+
+    let first = client.request_flights(&config).await?.get_all_flights();
+    let second = client.request_flights(&config).await?.get_all_flights();
+
+    assert_eq!(first.len(), second.len());
+    assert_eq!(fake.post_count(), 1, "second call must be a cache hit");
+    Ok(())
+}
+
+#[tokio::test]
+async fn backend_error_envelope_is_not_served_from_the_cache() {
+    let body = r#"[["wrb.fr",null,null,null,null,[3,null,[["Invalid request",null]]]]]"#;
+    let fake = FakeTransport::new(StatusCode::OK, body);
+    let client = ApiClient::new_with_transport(fake.clone())
+        .await
+        .with_cache(MemoryCache::new(), CacheConfig::default());
+    let config = lux_to_zrh();
+
+    for _ in 0..2 {
+        let err = client.request_flights(&config).await.unwrap_err();
+        assert!(matches!(err, Error::Backend { .. }), "got: {err}");
+    }
+    assert_eq!(fake.post_count(), 2, "the error must be asked for again");
+}
+
+#[tokio::test]
+async fn offline_cache_mode_never_reaches_the_transport() {
+    let fake = FakeTransport::new(StatusCode::OK, "");
+    let client = ApiClient::new_with_transport(fake.clone())
+        .await
+        .with_cache(
+            MemoryCache::new(),
+            CacheConfig::default().with_mode(CacheMode::Offline),
+        );
+
+    let err = client.request_flights(&lux_to_zrh()).await.unwrap_err();
+    assert!(matches!(err, Error::CacheMiss { .. }), "got: {err}");
+    let err = client.resolve_booking_url("token").await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::CacheMiss {
+                endpoint: Endpoint::BookingUrl
+            }
+        ),
+        "got: {err}"
+    );
+    assert_eq!(fake.post_count(), 0);
+}
+
+#[tokio::test]
+async fn offline_client_skips_the_version_probe() {
+    let client = ApiClient::new_offline(MemoryCache::new(), CacheConfig::default());
+    assert_eq!(client.frontend_version().source, VersionSource::Fallback);
+    assert_eq!(
+        client.refresh_frontend_version().await.source,
+        VersionSource::Fallback
+    );
+    let err = client.request_flights(&lux_to_zrh()).await.unwrap_err();
+    assert!(matches!(err, Error::CacheMiss { .. }), "got: {err}");
+}
+
+#[tokio::test]
+async fn outdated_frontend_version_is_reprobed_and_request_retried() -> Result<()> {
+    const OLD: &str = "boq_travel-frontend-flights-ui_20260527.01_p0";
+    const NEW: &str = "boq_travel-frontend-flights-ui_20260610.02_p1";
+    let server = VersionedTransport::new(OLD);
+    let client = ApiClient::new_with_transport(server.clone())
+        .await
+        .with_frontend_version_refresh(VersionRefreshPolicy {
+            min_reprobe_interval: std::time::Duration::ZERO,
+            ..VersionRefreshPolicy::default()
+        });
+    assert_eq!(client.frontend_version().label, OLD);
+    assert_eq!(client.frontend_version().source, VersionSource::Probed);
+
+    // Google rolls the build label forward; the old one is now rejected.
+    *server.label.lock().unwrap() = NEW.to_owned();
+    let results = client.request_flights(&lux_to_zrh()).await?;
+
+    assert!(!results.get_all_flights().is_empty());
+    assert_eq!(server.posts.load(Ordering::SeqCst), 2);
+    let version = client.clone().frontend_version();
+    assert_eq!(version.label, NEW);
+    assert!(!version.stale);
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejected_request_with_a_current_label_is_not_retried() {
+    const LABEL: &str = "boq_travel-frontend-flights-ui_20260527.01_p0";
+    let server = VersionedTransport::new(LABEL);
+    let client = ApiClient::new_with_transport(server.clone())
+        .await
+        .with_frontend_version_refresh(VersionRefreshPolicy {
+            min_reprobe_interval: std::time::Duration::ZERO,
+            ..VersionRefreshPolicy::default()
+        });
+    server.reject_all.store(true, Ordering::SeqCst);
+
+    let _ = client.request_flights(&lux_to_zrh()).await;
+
+    assert_eq!(server.posts.load(Ordering::SeqCst), 1, "no retry");
+    assert_eq!(server.probes.load(Ordering::SeqCst), 2, "one re-probe");
+    assert!(!client.frontend_version().stale);
+}
+
+#[tokio::test]
+async fn probe_cancelled_mid_flight_does_not_block_later_probes() -> Result<()> {
+    let server = VersionedTransport::new("boq_travel-frontend-flights-ui_20260527.01_p0");
+    let client = ApiClient::new_with_transport(server.clone())
+        .await
+        .with_frontend_version_refresh(VersionRefreshPolicy {
+            interval: std::time::Duration::ZERO,
+            ..VersionRefreshPolicy::default()
+        });
+
+    server.hang_probe.store(true, Ordering::SeqCst);
+    let config = lux_to_zrh();
+    let search = client.request_flights(&config);
+    let timed_out = tokio::time::timeout(std::time::Duration::from_millis(50), search).await;
+    assert!(timed_out.is_err(), "the periodic probe never answers");
+
+    server.hang_probe.store(false, Ordering::SeqCst);
+    client.request_flights(&config).await?;
+    assert_eq!(
+        server.probes.load(Ordering::SeqCst),
+        3,
+        "construction, the cancelled probe, then a fresh one"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn outdated_frontend_version_is_flagged_without_refresh_policy() {
+    let client =
+        ApiClient::new_with_transport(FakeTransport::new(StatusCode::BAD_REQUEST, "")).await;
+    assert_eq!(client.frontend_version().source, VersionSource::Fallback);
+    assert!(!client.frontend_version().stale);
+
+    // The (empty) 400 body is handed to the parser as before; only the flag
+    // records that the label was rejected.
+    let _ = client.request_flights(&lux_to_zrh()).await;
+
+    assert!(client.frontend_version().stale);
+}
+
+#[tokio::test]
+async fn flight_stream_yields_frames_before_the_body_completes() -> Result<()> {
+    let (release, receiver) = tokio::sync::oneshot::channel();
+    let transport = SplitBodyTransport {
+        release: Mutex::new(Some(receiver)),
+    };
+    // The frontend-version GET fails here, so the fallback label is used.
+    let client = ApiClient::new_with_transport(transport).await;
+
+    let mut frames = client.request_flights_stream(&lux_to_zrh()).await?;
+    // Arrives while the rest of the body is still held back.
+    frames.next().await.expect("first frame")?;
+
+    release.send(()).expect("stream still open");
+    let rest: Vec<_> = frames.collect().await;
+    assert!(!rest.is_empty());
+    assert!(rest.iter().all(|frame| frame.is_ok()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn flight_stream_over_buffered_transport_matches_request_flights() -> Result<()> {
+    let body = std::fs::read_to_string("test_files/raw_multiline.txt")?;
+    let client = ApiClient::new_with_transport(FakeTransport::new(StatusCode::OK, body)).await;
+
+    let streamed: Vec<_> = client
+        .request_flights_stream(&lux_to_zrh())
+        .await?
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<gflights::Result<_>>()?;
+    let buffered = client.request_flights(&lux_to_zrh()).await?;
+
+    let streamed = FlightResponseContainer {
+        responses: streamed,
+    };
+    assert_eq!(
+        streamed.get_all_flights().len(),
+        buffered.get_all_flights().len()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn flight_stream_surfaces_error_statuses() {
+    let client = ApiClient::new_with_transport(FakeTransport::new(StatusCode::NOT_FOUND, "")).await;
+
+    let err = client
+        .request_flights_stream(&lux_to_zrh())
+        .await
+        .err()
+        .expect("error");
+
+    assert!(
+        matches!(
+            err,
+            Error::Http {
+                status: StatusCode::NOT_FOUND,
+                ..
+            }
+        ),
+        "got: {err}"
+    );
+}
+
+#[tokio::test]
+async fn batch_sends_one_post_and_demultiplexes_by_call() -> Result<()> {
+    let body = concat!(
+        ")]}'\n\n",
+        r#"[["wrb.fr","abc123","{\"ok\":true}",null,null,null,"2"]]"#,
+        "\n",
+        r#"[["wrb.fr","H028ib","[[[[3,null,\"London\",null,\"/m/04jpl\",null]]]]",null,null,null,"1"]]"#,
+        "\n",
+    );
+    let transport = FakeTransport::new(StatusCode::OK, body);
+    let client = ApiClient::new_with_transport(transport.clone()).await;
+
+    let mut batch = BatchRequest::new();
+    let london = batch.city("london");
+    let raw = batch.rpc::<serde_json::Value>("abc123", "[]");
+    let res = client.request_batch(&batch).await?;
+
+    assert_eq!(transport.post_count(), 1);
+    let url = transport.posts.lock().unwrap()[0].url.clone();
+    assert!(url.contains("rpcids=H028ib,abc123&"), "{url}");
+    assert_eq!(Endpoint::from_url(&url), Endpoint::BatchExecute);
+    assert_eq!(
+        res.get(london)?.result_container[0].city.city_name,
+        "London"
+    );
+    assert_eq!(res.get(raw)?["ok"], true);
+    Ok(())
+}
+
+/// The payload of the last, most complete `wrb.fr` frame in a recorded
+/// response.
+fn last_payload(fixture: &str) -> String {
+    let body = std::fs::read_to_string(fixture).unwrap();
+    body.lines()
+        .rev()
+        .filter(|line| line.starts_with(r#"[["wrb.fr""#))
+        .find_map(|line| {
+            let frames: serde_json::Value = serde_json::from_str(line).ok()?;
+            frames[0][2].as_str().map(str::to_owned)
+        })
+        .unwrap()
+}
+
+#[tokio::test]
+async fn batch_demultiplexes_city_graph_and_shopping_calls() -> Result<()> {
+    let frame = |rpc_id: &str, payload: &str, tag: &str| {
+        serde_json::json!([["wrb.fr", rpc_id, payload, null, null, null, tag]]).to_string()
+    };
+    // Answers arrive out of order, as they do when the backend streams them.
+    let body = [
+        ")]}'".to_owned(),
+        frame("shopId", &last_payload("test_files/raw_multiline.txt"), "3"),
+        frame("graphId", &last_payload("test_files/graph_response"), "2"),
+        frame(
+            "H028ib",
+            r#"[[[[3,null,"London",null,"/m/04jpl",null]]]]"#,
+            "1",
+        ),
+    ]
+    .join("\n");
+    let transport = FakeTransport::new(StatusCode::OK, body);
+    let client = ApiClient::new_with_transport(transport.clone()).await;
+    let config = lux_to_zrh();
+
+    let mut batch = BatchRequest::new();
+    let london = batch.city("london");
+    let graph = batch.graph("graphId", &config, chrono::Months::new(1))?;
+    let flights = batch.shopping("shopId", &config)?;
+    let res = client.request_batch(&batch).await?;
+
+    assert_eq!(transport.post_count(), 1);
+    let url = transport.posts.lock().unwrap()[0].url.clone();
+    assert!(url.contains("rpcids=H028ib,graphId,shopId&"), "{url}");
+    assert_eq!(
+        res.get(london)?.result_container[0].city.city_name,
+        "London"
+    );
+    assert!(res.get(graph)?.price_graph.is_some_and(|g| !g.is_empty()));
+    let flights = FlightResponseContainer {
+        responses: vec![res.get(flights)?],
+    };
+    assert!(!flights.get_all_flights().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn empty_batch_is_an_invalid_config() {
+    let transport = FakeTransport::new(StatusCode::OK, "");
+    let client = ApiClient::new_with_transport(transport.clone()).await;
+
+    let err = client
+        .request_batch(&BatchRequest::new())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::InvalidConfig(_)), "got: {err}");
+    assert_eq!(transport.post_count(), 0);
+}
+
+#[tokio::test]
+async fn search_many_answers_every_config_by_index() -> Result<()> {
+    let body = std::fs::read_to_string("test_files/raw_multiline.txt")?;
+    let fake = FakeTransport::new(StatusCode::OK, body);
+    let client = ApiClient::new_with_transport(fake.clone()).await;
+    let last = Arc::new(Mutex::new(BulkProgress::default()));
+    let sink = Arc::clone(&last);
+
+    let mut indices: Vec<usize> = client
+        .search_many_with(
+            vec![lux_to_zrh(); 5],
+            BulkOptions::new(2).with_progress(move |p| *sink.lock().unwrap() = p),
+        )
+        .map(|(index, result)| {
+            assert!(result.is_ok(), "#{index}");
+            index
+        })
+        .collect()
+        .await;
+    indices.sort_unstable();
+
+    assert_eq!(indices, [0, 1, 2, 3, 4]);
+    assert_eq!(fake.post_count(), 5);
+    assert_eq!(
+        *last.lock().unwrap(),
+        BulkProgress {
+            done: 5,
+            failed: 0,
+            remaining: 0
+        }
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn search_many_fail_fast_stops_on_rate_limit() {
+    let fake = FakeTransport::new(StatusCode::TOO_MANY_REQUESTS, "");
+    let client = ApiClient::new_with_transport(fake.clone()).await;
+
+    let results: Vec<_> = client
+        .search_many_with(
+            vec![lux_to_zrh(); 20],
+            BulkOptions::new(1).with_mode(BulkMode::FailFast),
+        )
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 1);
+    assert!(matches!(results[0].1, Err(Error::RateLimited)));
+    assert_eq!(fake.post_count(), 1);
+}
+
+#[tokio::test]
+async fn metrics_observer_sees_attempts_retries_and_parse_errors() {
+    let metrics = Arc::new(PrometheusMetrics::new());
+    let client =
+        ApiClient::new_with_transport(FakeTransport::new(StatusCode::SERVICE_UNAVAILABLE, ""))
+            .await
+            .with_retry_config(no_delay_retries(3))
+            .with_metrics(metrics.clone());
+    assert!(client.request_flights(&lux_to_zrh()).await.is_err());
+
+    let body = r#"[["wrb.fr",null,{"not":"a payload"}]]"#;
+    let client = ApiClient::new_with_transport(FakeTransport::new(StatusCode::OK, body))
+        .await
+        .with_metrics(metrics.clone());
+    assert!(client.request_flights(&lux_to_zrh()).await.is_err());
+
+    let text = metrics.render();
+    for line in [
+        r#"gflights_requests_total{endpoint="shopping",status="503"} 3"#,
+        r#"gflights_requests_total{endpoint="shopping",status="200"} 1"#,
+        r#"gflights_retries_total{endpoint="shopping",reason="server_error"} 2"#,
+        r#"gflights_parse_errors_total{endpoint="shopping"} 1"#,
+    ] {
+        assert!(
+            text.lines().any(|l| l == line),
+            "missing {line:?} in\n{text}"
+        );
+    }
+}
+
+#[test]
+fn blocking_client_runs_requests_without_a_caller_runtime() -> Result<()> {
+    let body = std::fs::read_to_string("test_files/raw_multiline.txt")?;
+    let fake = FakeTransport::new(StatusCode::OK, body);
+    let client = gflights::blocking::ApiClient::new_with_transport(fake.clone())?;
+
+    let results = client.request_flights(&lux_to_zrh())?;
+    assert!(!results.get_all_flights().is_empty());
+
+    let frames = client.request_flights_stream(&lux_to_zrh())?.count();
+    assert!(frames > 0);
+
+    let done = client
+        .search_many(vec![lux_to_zrh(); 3], 2)
+        .filter(|(_, r)| r.is_ok())
+        .count();
+    assert_eq!(done, 3);
+    assert_eq!(fake.post_count(), 5);
+    Ok(())
+}